@@ -26,6 +26,9 @@ fn main() {
     // Tell cargo to re-run this script if wrapper.h or env var changes
     println!("cargo:rerun-if-changed={}", wrapper_path_str);
     println!("cargo:rerun-if-env-changed=NUTTX_INCLUDE_DIR");
+    println!("cargo:rerun-if-env-changed=NUTTX_BINDGEN_ALLOWLIST");
+    // Read by the kconfig macros, which cannot declare it themselves
+    println!("cargo:rerun-if-env-changed=NUTTX_KCONFIG_STRICT");
 
     // Also add current directory as include path for wrapper.h
     let current_include = format!("-I{}", current_dir.to_str().unwrap());
@@ -39,6 +42,9 @@ fn main() {
         // Add flags to avoid standard includes and libraries
         .clang_arg("-nostdinc")
         .clang_arg("-nostdlib")
+        // Ioctl commands such as FBIOGET_VIDEOINFO are defined through function-like
+        // macros, e.g. _FBIOC(0x0001), which only clang can evaluate
+        .clang_macro_fallback()
         // Tell cargo to invalidate the crate when any of these change
         .parse_callbacks(Box::new(bindgen::CargoCallbacks::new()));
 
@@ -54,6 +60,31 @@ fn main() {
         }
     }
 
+    // Kconfig constants are also generated on their own, so the kconfig macros only
+    // have to parse a small file
+    let kconfig_builder = builder.clone().allowlist_var("CONFIG_.*");
+
+    // Restrict the bindings to the items matching NUTTX_BINDGEN_ALLOWLIST, a ':' or ','
+    // separated list of regexes. Without it, everything reachable from wrapper.h is kept.
+    // The patterns must cover the types and constants used by the enabled modules, e.g.
+    // "fb_.*,FB_.*,FBIO.*,PROT_.*,MAP_.*" for the framebuffer. Kconfig constants are
+    // always kept, as the kconfig macros evaluate their conditions against them.
+    if let Ok(allowlist) = env::var("NUTTX_BINDGEN_ALLOWLIST")
+        && !allowlist.trim().is_empty()
+    {
+        let patterns = allowlist
+            .split([':', ','])
+            .map(str::trim)
+            .filter(|pattern| !pattern.is_empty());
+
+        for pattern in patterns.chain(["CONFIG_.*"]) {
+            builder = builder
+                .allowlist_type(pattern)
+                .allowlist_function(pattern)
+                .allowlist_var(pattern);
+        }
+    }
+
     // Generate the bindings
     let bindings = builder.generate().expect("Unable to generate bindings");
 
@@ -63,4 +94,18 @@ fn main() {
     bindings
         .write_to_file(&bindings_path)
         .expect("Couldn't write bindings!");
+
+    // Write the Kconfig constants to the $OUT_DIR/kconfig.rs file, and point the kconfig
+    // macros at it through NUTTX_KCONFIG_BINDINGS
+    let kconfig_bindings = kconfig_builder
+        .generate()
+        .expect("Unable to generate Kconfig bindings");
+    let kconfig_path = out_path.join("kconfig.rs");
+    kconfig_bindings
+        .write_to_file(&kconfig_path)
+        .expect("Couldn't write Kconfig bindings!");
+    println!(
+        "cargo:rustc-env=NUTTX_KCONFIG_BINDINGS={}",
+        kconfig_path.display()
+    );
 }
@@ -21,73 +21,346 @@
 //!     // This struct will only be included when CONFIG_FEATURE_X is enabled
 //!     // and CONFIG_DEBUG is disabled
 //! }
+//!
+//! #[kconfig(any(CONFIG_ARCH_BOARD_A = "y", all(CONFIG_ARCH_BOARD_B = "y", CONFIG_DEBUG = "n")))]
+//! fn board_implementation() {
+//!     // This function will only be compiled for board A, or for board B
+//!     // when CONFIG_DEBUG is disabled
+//! }
+//!
+//! #[kconfig(CONFIG_RR_INTERVAL > "0")]
+//! fn round_robin_implementation() {
+//!     // This function will only be compiled when CONFIG_RR_INTERVAL is
+//!     // greater than zero
+//! }
+//!
+//! #[kconfig(CONFIG_FEATURE_X = "y")]
+//! mod feature_x {
+//!     // The whole module, or likewise an impl block, is only included when
+//!     // CONFIG_FEATURE_X is enabled
+//! }
+//! ```
+//!
+//! Use the `kconfig_value!` macro to read the value of an option inline:
+//!
+//! ```rust
+//! use kconfig::kconfig_value;
+//!
+//! static BUFFER: [u8; kconfig_value!(CONFIG_BUFFER_SIZE)] = [0; kconfig_value!(CONFIG_BUFFER_SIZE)];
+//! const BOARD: &str = kconfig_value!(CONFIG_ARCH_BOARD);
+//! ```
+//!
+//! Use the `kconfig_if!` macro to write an item and its fallback together:
+//!
+//! ```rust
+//! use kconfig::kconfig_if;
+//!
+//! kconfig_if!(CONFIG_FEATURE_X = "y" {
+//!     fn feature_x() -> bool { true }
+//! } else {
+//!     fn feature_x() -> bool { false }
+//! });
+//! ```
+//!
+//! Use the `kconfig_enabled!` macro to test options in an expression:
+//!
+//! ```rust
+//! use kconfig::kconfig_enabled;
+//!
+//! const HAS_UPDATE: bool = kconfig_enabled!(CONFIG_FB_UPDATE);
+//!
+//! if kconfig_enabled!(CONFIG_RR_INTERVAL > "0") {
+//!     // Round robin scheduling is configured
+//! }
 //! ```
 //!
 //! ## How it works
 //!
 //! The macro processes Kconfig bindings that are generated during the NuttX build process.
+//! The build script writes them to a dedicated file named by the `NUTTX_KCONFIG_BINDINGS`
+//! environment variable, with the full `bindings.rs` in `OUT_DIR` as a fallback.
 //! These bindings contain Rust constants that represent the values of Kconfig options.
 //! The macro checks these constants to determine if the specified conditions are met.
+//!
+//! ## Strict mode
+//!
+//! Disabled options are not defined in the bindings, so by default an option that is
+//! missing is treated as disabled, and a misspelled option silently compiles an item
+//! out. Setting `NUTTX_KCONFIG_STRICT` to a `:` separated list of directories, e.g. the
+//! NuttX source tree, enables strict mode: the options declared by the `Kconfig*` files
+//! below these directories are collected, and referencing an option that is neither
+//! defined in the bindings nor declared there is a compile error. Strict mode also
+//! rejects bindings that define no `CONFIG_` constant at all.
 
 use proc_macro::TokenStream;
 use quote::quote;
+use std::collections::HashSet;
 use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
 use syn::{
-    Expr, File, Ident, Item, ItemConst, Lit, LitStr, Token,
+    Expr, File, Ident, Item, ItemConst, Lit, LitStr, Token, UnOp, braced, parenthesized,
     parse::{Parse, ParseStream},
-    parse_file, parse_macro_input,
+    parse_file,
     punctuated::Punctuated,
+    token,
 };
 
+/// The comparison performed between a Kconfig option and the expected value.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Comparison {
+    /// `=`, matches the option against `"y"`, `"m"` or `"n"`
+    Assign,
+    /// `==`, the option equals an integer
+    Eq,
+    /// `>`, the option is greater than an integer
+    Gt,
+    /// `>=`, the option is greater than or equal to an integer
+    Ge,
+    /// `<`, the option is less than an integer
+    Lt,
+    /// `<=`, the option is less than or equal to an integer
+    Le,
+}
+
+impl Comparison {
+    /// Applies the numeric comparison to the actual and expected values.
+    fn compare(self, actual: i128, expected: i128) -> bool {
+        match self {
+            Comparison::Assign | Comparison::Eq => actual == expected,
+            Comparison::Gt => actual > expected,
+            Comparison::Ge => actual >= expected,
+            Comparison::Lt => actual < expected,
+            Comparison::Le => actual <= expected,
+        }
+    }
+}
+
+/// Implementation for parsing a comparison operator from a token stream.
+///
+/// Multi-character operators are checked before their single-character prefixes.
+impl Parse for Comparison {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let lookahead = input.lookahead1();
+        if lookahead.peek(Token![==]) {
+            let _: Token![==] = input.parse()?;
+            Ok(Comparison::Eq)
+        } else if lookahead.peek(Token![>=]) {
+            let _: Token![>=] = input.parse()?;
+            Ok(Comparison::Ge)
+        } else if lookahead.peek(Token![<=]) {
+            let _: Token![<=] = input.parse()?;
+            Ok(Comparison::Le)
+        } else if lookahead.peek(Token![=]) {
+            let _: Token![=] = input.parse()?;
+            Ok(Comparison::Assign)
+        } else if lookahead.peek(Token![>]) {
+            let _: Token![>] = input.parse()?;
+            Ok(Comparison::Gt)
+        } else if lookahead.peek(Token![<]) {
+            let _: Token![<] = input.parse()?;
+            Ok(Comparison::Lt)
+        } else {
+            Err(lookahead.error())
+        }
+    }
+}
+
 /// Represents a single Kconfig option in the attribute macro.
 ///
-/// Each option consists of a name (identifier) and a value (string literal).
+/// Each option consists of a name (identifier), a comparison and a value (string literal).
 /// For example, in `#[kconfig(CONFIG_FEATURE_X = "y")]`, `CONFIG_FEATURE_X` is the name
 /// and `"y"` is the value.
 struct KconfigOption {
     /// The name of the Kconfig option (e.g., `CONFIG_FEATURE_X`)
     name: Ident,
-    /// The expected value of the option, either `"y"` or `"n"`
+    /// The comparison between the option and the value
+    op: Comparison,
+    /// The expected value of the option, either `"y"`, `"m"` or `"n"` for `=`,
+    /// or an integer for the numeric comparisons, in decimal or with a `0x`,
+    /// `0o` or `0b` prefix
     value: LitStr,
 }
 
 /// Implementation for parsing a single Kconfig option from a token stream.
 ///
-/// Parses a key-value pair in the form `name = "value"` where:
+/// Parses a key-value pair in the form `name <op> "value"` where:
 /// - `name` is a valid Rust identifier
+/// - `<op>` is one of `=`, `==`, `>`, `>=`, `<` or `<=`
 /// - `value` is a string literal
 impl Parse for KconfigOption {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         let name = input.parse()?;
-        let _: Token![=] = input.parse()?; // Parse but don't store equals
+        let op = input.parse()?;
         let value = input.parse()?;
-        Ok(KconfigOption { name, value })
+        Ok(KconfigOption { name, op, value })
+    }
+}
+
+/// A condition in a `#[kconfig(...)]` attribute.
+///
+/// Conditions are either a single option or a combination of nested conditions,
+/// mirroring the `all(...)`, `any(...)` and `not(...)` predicates of `cfg`.
+enum KconfigPredicate {
+    /// A single option, e.g. `CONFIG_A = "y"`
+    Option(KconfigOption),
+    /// `all(...)`, which holds when every nested condition holds
+    All(Punctuated<KconfigPredicate, Token![,]>),
+    /// `any(...)`, which holds when at least one nested condition holds
+    Any(Punctuated<KconfigPredicate, Token![,]>),
+    /// `not(...)`, which holds when the nested condition does not
+    Not(Box<KconfigPredicate>),
+}
+
+/// Implementation for parsing a condition from a token stream.
+///
+/// An identifier followed by parentheses is parsed as a group, anything else as a
+/// single option.
+impl Parse for KconfigPredicate {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if !(input.peek(Ident) && input.peek2(token::Paren)) {
+            return Ok(KconfigPredicate::Option(input.parse()?));
+        }
+
+        let combinator: Ident = input.parse()?;
+        let content;
+        parenthesized!(content in input);
+
+        if combinator == "not" {
+            let predicate = content.parse()?;
+            // Allow a trailing comma, as `cfg(not(...))` does
+            if !content.is_empty() {
+                let _: Token![,] = content.parse()?;
+            }
+            if !content.is_empty() {
+                return Err(content.error("`not` takes a single condition"));
+            }
+            return Ok(KconfigPredicate::Not(Box::new(predicate)));
+        }
+
+        let predicates = Punctuated::parse_terminated(&content)?;
+
+        if combinator == "all" {
+            Ok(KconfigPredicate::All(predicates))
+        } else if combinator == "any" {
+            Ok(KconfigPredicate::Any(predicates))
+        } else {
+            Err(syn::Error::new(
+                combinator.span(),
+                "expected `all`, `any` or `not`",
+            ))
+        }
     }
 }
 
-/// Represents all options in a `#[kconfig(...)]` attribute.
+/// Represents all conditions in a `#[kconfig(...)]` attribute.
 ///
-/// Contains a punctuated sequence of `KconfigOption` items, separated by commas.
-/// For example, in `#[kconfig(CONFIG_A = "y", CONFIG_B = "n")]`, the options are
-/// `CONFIG_A = "y"` and `CONFIG_B = "n"`.
+/// Contains a punctuated sequence of `KconfigPredicate` items, separated by commas,
+/// which must all hold. For example, in `#[kconfig(CONFIG_A = "y", any(CONFIG_B = "y",
+/// CONFIG_C = "y"))]`, the conditions are `CONFIG_A = "y"` and the `any(...)` group.
 struct KconfigAttr {
-    /// A comma-separated list of Kconfig options
-    options: Punctuated<KconfigOption, Token![,]>,
+    /// A comma-separated list of conditions
+    predicates: Punctuated<KconfigPredicate, Token![,]>,
 }
 
-/// Implementation for parsing a comma-separated list of Kconfig options.
+/// Implementation for parsing a comma-separated list of conditions.
 impl Parse for KconfigAttr {
     fn parse(input: ParseStream) -> syn::Result<Self> {
-        let options = Punctuated::parse_terminated(input)?;
-        Ok(KconfigAttr { options })
+        let predicates = Punctuated::parse_terminated(input)?;
+        Ok(KconfigAttr { predicates })
+    }
+}
+
+/// Represents the input of a `kconfig_if!` invocation.
+///
+/// Consists of a list of conditions, the tokens to emit when they all hold and
+/// optionally the tokens to emit otherwise. For example, in
+/// `kconfig_if!(CONFIG_A = "y" { ... } else { ... })`, the condition is `CONFIG_A = "y"`.
+struct KconfigIf {
+    /// A comma-separated list of conditions
+    predicates: Punctuated<KconfigPredicate, Token![,]>,
+    /// The tokens emitted when the conditions hold
+    then_branch: proc_macro2::TokenStream,
+    /// The tokens emitted when the conditions do not hold
+    else_branch: Option<proc_macro2::TokenStream>,
+}
+
+/// Implementation for parsing the conditions and branches of `kconfig_if!`.
+///
+/// The branches are kept as raw tokens so they can hold any items, including
+/// associated functions inside an `impl` block.
+impl Parse for KconfigIf {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut predicates = Punctuated::new();
+        while !input.peek(token::Brace) {
+            predicates.push_value(input.parse()?);
+            if input.peek(token::Brace) {
+                break;
+            }
+            predicates.push_punct(input.parse()?);
+        }
+
+        let content;
+        braced!(content in input);
+        let then_branch = content.parse()?;
+
+        let else_branch = if input.peek(Token![else]) {
+            let _: Token![else] = input.parse()?;
+            let content;
+            braced!(content in input);
+            Some(content.parse()?)
+        } else {
+            None
+        };
+
+        Ok(KconfigIf {
+            predicates,
+            then_branch,
+            else_branch,
+        })
+    }
+}
+
+/// Represents the input of a `kconfig_enabled!` invocation.
+///
+/// Either a bare option name, which holds when the option is built in or built as
+/// a module, or the same conditions as the `#[kconfig]` attribute.
+struct KconfigEnabled {
+    /// The condition to evaluate
+    predicate: KconfigPredicate,
+}
+
+/// Implementation for parsing the option name or conditions of `kconfig_enabled!`.
+impl Parse for KconfigEnabled {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let fork = input.fork();
+        if fork.parse::<Ident>().is_ok() && fork.is_empty() {
+            let name: Ident = input.parse()?;
+            let option = |value| {
+                KconfigPredicate::Option(KconfigOption {
+                    name: name.clone(),
+                    op: Comparison::Assign,
+                    value: LitStr::new(value, name.span()),
+                })
+            };
+            let predicates = [option("y"), option("m")].into_iter().collect();
+            return Ok(KconfigEnabled {
+                predicate: KconfigPredicate::Any(predicates),
+            });
+        }
+
+        let predicates = Punctuated::parse_terminated(input)?;
+        Ok(KconfigEnabled {
+            predicate: KconfigPredicate::All(predicates),
+        })
     }
 }
 
 /// Fetches and parses the Rust bindings file generated from NuttX Kconfig options.
 ///
 /// This function:
-/// 1. Retrieves the output directory path from the `OUT_DIR` environment variable
-/// 2. Constructs the path to the bindings.rs file
+/// 1. Uses the Kconfig-only bindings file named by `NUTTX_KCONFIG_BINDINGS` if it exists
+/// 2. Otherwise, falls back to the full bindings.rs file in the `OUT_DIR` directory
 /// 3. Reads the file contents
 /// 4. Parses the file into a Rust AST (Abstract Syntax Tree)
 ///
@@ -98,22 +371,13 @@ impl Parse for KconfigAttr {
 /// # Errors
 ///
 /// This function will return an error if:
-/// - The `OUT_DIR` environment variable is not set
+/// - Neither `NUTTX_KCONFIG_BINDINGS` names an existing file nor `OUT_DIR` is set
 /// - The bindings file cannot be found or read
 /// - The bindings file cannot be parsed as valid Rust code
 ///
 /// When an error occurs, it provides descriptive error messages to aid debugging.
 fn fetch_bindings_ast() -> syn::Result<File> {
-    // Get the output directory from the environment variable
-    // This is set by Cargo when building and contains build artifacts
-    let output_dir = std::env::var("OUT_DIR").expect("OUT_DIR not set");
-
-    // Convert the string path to a PathBuf for easier manipulation
-    let output_path = std::path::PathBuf::from(output_dir);
-
-    // Construct the full path to the bindings.rs file
-    // This file contains Rust constants generated from NuttX Kconfig options
-    let bindings_path = output_path.join("bindings.rs");
+    let bindings_path = bindings_path();
 
     // Read the bindings file into a string
     // Return a descriptive error if the file cannot be read
@@ -138,6 +402,28 @@ fn fetch_bindings_ast() -> syn::Result<File> {
     })
 }
 
+/// Returns the path of the bindings file holding the Kconfig constants.
+///
+/// The build script writes the Kconfig constants to a dedicated file and exports its path
+/// as `NUTTX_KCONFIG_BINDINGS`, which is much faster to parse than the full bindings.
+/// Builds that do not provide it fall back to `OUT_DIR/bindings.rs`.
+fn bindings_path() -> std::path::PathBuf {
+    if let Ok(kconfig_path) = std::env::var("NUTTX_KCONFIG_BINDINGS") {
+        let kconfig_path = std::path::PathBuf::from(kconfig_path);
+        if kconfig_path.exists() {
+            return kconfig_path;
+        }
+    }
+
+    // Get the output directory from the environment variable
+    // This is set by Cargo when building and contains build artifacts
+    let output_dir = std::env::var("OUT_DIR").expect("OUT_DIR not set");
+
+    // Construct the full path to the bindings.rs file
+    // This file contains Rust constants generated from NuttX Kconfig options
+    std::path::PathBuf::from(output_dir).join("bindings.rs")
+}
+
 /// Helper function to find a specific Kconfig option in the bindings AST.
 ///
 /// # Arguments
@@ -150,15 +436,316 @@ fn fetch_bindings_ast() -> syn::Result<File> {
 /// Returns a reference to the constant item if found, otherwise `None`.
 fn find_kconfig_option<'a>(bindings_ast: &'a File, option_name: &str) -> Option<&'a ItemConst> {
     for item in &bindings_ast.items {
-        if let Item::Const(const_item) = item {
-            if const_item.ident.to_string() == option_name {
-                return Some(const_item);
-            }
+        if let Item::Const(const_item) = item
+            && const_item.ident == option_name
+        {
+            return Some(const_item);
         }
     }
     None
 }
 
+/// Returns the options declared by the Kconfig files used for strict mode.
+///
+/// The directories named by `NUTTX_KCONFIG_STRICT` are scanned once per compilation.
+///
+/// # Returns
+///
+/// Returns `Ok(None)` if strict mode is disabled.
+///
+/// # Errors
+///
+/// Returns an error message if one of the directories cannot be read.
+fn strict_options() -> Result<Option<&'static HashSet<String>>, String> {
+    static OPTIONS: OnceLock<Result<Option<HashSet<String>>, String>> = OnceLock::new();
+
+    let options = OPTIONS.get_or_init(|| {
+        let Ok(dirs) = std::env::var("NUTTX_KCONFIG_STRICT") else {
+            return Ok(None);
+        };
+
+        let dirs: Vec<&str> = dirs
+            .split(':')
+            .map(str::trim)
+            .filter(|dir| !dir.is_empty())
+            .collect();
+        if dirs.is_empty() {
+            return Ok(None);
+        }
+
+        let mut options = HashSet::new();
+        for dir in dirs {
+            collect_kconfig_options(Path::new(dir), &mut options)
+                .map_err(|error| format!("NUTTX_KCONFIG_STRICT: cannot read {}: {}", dir, error))?;
+        }
+        Ok(Some(options))
+    });
+
+    match options {
+        Ok(options) => Ok(options.as_ref()),
+        Err(error) => Err(error.clone()),
+    }
+}
+
+/// Adds the options declared by the `Kconfig*` files below `dir` to `options`.
+///
+/// Symbolic links are not followed, so the links of a configured NuttX tree are not
+/// visited twice.
+fn collect_kconfig_options(dir: &Path, options: &mut HashSet<String>) -> std::io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+
+        if file_type.is_dir() {
+            collect_kconfig_options(&entry.path(), options)?;
+        } else if file_type.is_file() && entry.file_name().to_string_lossy().starts_with("Kconfig")
+        {
+            // Kconfig files of other projects may not be UTF-8, skip them
+            let Ok(source) = fs::read_to_string(entry.path()) else {
+                continue;
+            };
+            scan_kconfig_options(&source, options);
+        }
+    }
+    Ok(())
+}
+
+/// Adds the options declared by the contents of a `Kconfig` file to `options`.
+///
+/// Options are declared by `config` and `menuconfig` entries, and named with the
+/// `CONFIG_` prefix of the bindings.
+fn scan_kconfig_options(source: &str, options: &mut HashSet<String>) {
+    for line in source.lines() {
+        let mut words = line.split_whitespace();
+        if let (Some("config" | "menuconfig"), Some(name)) = (words.next(), words.next()) {
+            options.insert(format!("CONFIG_{}", name));
+        }
+    }
+}
+
+/// Checks an option missing from the bindings in strict mode.
+///
+/// # Errors
+///
+/// In strict mode, returns an error if the bindings define no `CONFIG_` constant, or
+/// if the option is not declared by the Kconfig files either.
+fn check_strict(name: &Ident, bindings_ast: &File) -> syn::Result<()> {
+    match strict_options().map_err(|error| syn::Error::new(name.span(), error))? {
+        Some(options) => check_declared(name, bindings_ast, options),
+        None => Ok(()),
+    }
+}
+
+/// Checks that an option missing from the bindings is declared in `options`.
+///
+/// # Errors
+///
+/// Returns an error if the bindings define no `CONFIG_` constant, or if `options`
+/// does not hold the option either.
+fn check_declared(name: &Ident, bindings_ast: &File, options: &HashSet<String>) -> syn::Result<()> {
+    let has_kconfig = bindings_ast.items.iter().any(|item| {
+        matches!(item, Item::Const(const_item) if const_item.ident.to_string().starts_with("CONFIG_"))
+    });
+    if !has_kconfig {
+        return Err(syn::Error::new(
+            name.span(),
+            format!(
+                "the Kconfig bindings at {} define no CONFIG_ constants",
+                bindings_path().display()
+            ),
+        ));
+    }
+
+    if !options.contains(&name.to_string()) {
+        return Err(syn::Error::new(
+            name.span(),
+            format!("unknown Kconfig option {}", name),
+        ));
+    }
+    Ok(())
+}
+
+/// Evaluates a single Kconfig option against the bindings AST.
+///
+/// # Arguments
+///
+/// * `option` - The option to evaluate
+/// * `bindings_ast` - The parsed AST of the bindings file
+///
+/// # Returns
+///
+/// Returns `Ok(true)` if the option matches its expected value.
+///
+/// # Errors
+///
+/// Returns an error if a numeric comparison is used with a value that is not an integer,
+/// or if strict mode rejects an option missing from the bindings.
+fn evaluate_option(option: &KconfigOption, bindings_ast: &File) -> syn::Result<bool> {
+    let option_name = option.name.to_string();
+    let expected_value = option.value.value();
+    let const_item = find_kconfig_option(bindings_ast, &option_name);
+
+    if const_item.is_none() {
+        check_strict(&option.name, bindings_ast)?;
+    }
+
+    if option.op != Comparison::Assign {
+        let expected = parse_int(&expected_value).ok_or_else(|| {
+            syn::Error::new(
+                option.value.span(),
+                format!("expected an integer value for {}", option_name),
+            )
+        })?;
+
+        return Ok(match const_item {
+            // Only an equality test against zero holds for a missing option
+            None => option.op == Comparison::Eq && expected == 0,
+            Some(const_item) => match const_int_value(const_item, option.value.span())? {
+                Some(actual) => option.op.compare(actual, expected),
+                None => false,
+            },
+        });
+    }
+
+    // First, check if the option exists in the bindings
+    if let Some(const_item) = const_item {
+        // Option exists, now check if its value matches
+        if expected_value == "n" {
+            // If option exists but required value is "n", condition fails
+            return Ok(false);
+        }
+
+        // Check if option value matches the value of the const, tristate
+        // options being encoded as 1 when built in and 2 when built as a module
+        if let Some(actual_value) = const_int_value(const_item, option.value.span())? {
+            return Ok(match expected_value.as_str() {
+                "y" => actual_value == 1,
+                "m" => actual_value == 2,
+                _ => false,
+            });
+        }
+        if let Some(actual_value) = const_str_value(const_item) {
+            return Ok(expected_value == actual_value);
+        }
+        Ok(true)
+    } else {
+        // Option doesn't exist in bindings, which only matches an expected "n"
+        Ok(expected_value == "n")
+    }
+}
+
+/// Evaluates a condition against the bindings AST.
+///
+/// Groups short-circuit, so `all()` holds and `any()` does not, as with `cfg`.
+///
+/// # Errors
+///
+/// Returns an error if any evaluated option is invalid.
+fn evaluate_predicate(predicate: &KconfigPredicate, bindings_ast: &File) -> syn::Result<bool> {
+    match predicate {
+        KconfigPredicate::Option(option) => evaluate_option(option, bindings_ast),
+        KconfigPredicate::All(predicates) => {
+            for predicate in predicates {
+                if !evaluate_predicate(predicate, bindings_ast)? {
+                    return Ok(false);
+                }
+            }
+            Ok(true)
+        }
+        KconfigPredicate::Any(predicates) => {
+            for predicate in predicates {
+                if evaluate_predicate(predicate, bindings_ast)? {
+                    return Ok(true);
+                }
+            }
+            Ok(false)
+        }
+        KconfigPredicate::Not(predicate) => Ok(!evaluate_predicate(predicate, bindings_ast)?),
+    }
+}
+
+/// Extracts the integer value of a constant from the bindings.
+///
+/// bindgen may emit decimal, hexadecimal, octal or binary literals, with or without
+/// a type suffix, and negates negative values. All of these are accepted.
+///
+/// # Returns
+///
+/// Returns `Ok(None)` if the constant is not an integer literal.
+///
+/// # Errors
+///
+/// Returns an error at `span` if the literal does not fit in an `i128`.
+fn const_int_value(const_item: &ItemConst, span: proc_macro2::Span) -> syn::Result<Option<i128>> {
+    let (negative, expr) = match const_item.expr.as_ref() {
+        Expr::Unary(unary) if matches!(unary.op, UnOp::Neg(_)) => (true, unary.expr.as_ref()),
+        expr => (false, expr),
+    };
+
+    let Expr::Lit(expr_lit) = expr else {
+        return Ok(None);
+    };
+    let Lit::Int(lit_int) = &expr_lit.lit else {
+        return Ok(None);
+    };
+
+    // base10_digits() holds the value in decimal without the radix prefix and suffix
+    let value = lit_int.base10_parse::<i128>().map_err(|error| {
+        syn::Error::new(
+            span,
+            format!("invalid value for {}: {}", const_item.ident, error),
+        )
+    })?;
+    Ok(Some(if negative { -value } else { value }))
+}
+
+/// Parses an integer written in decimal or with a `0x`, `0o` or `0b` prefix.
+///
+/// A leading `-` and `_` separators are accepted. Returns `None` if the string is not
+/// an integer or does not fit in an `i128`.
+fn parse_int(value: &str) -> Option<i128> {
+    let (negative, value) = match value.trim().strip_prefix('-') {
+        Some(value) => (true, value),
+        None => (false, value.trim()),
+    };
+
+    let (radix, digits) = match value.get(..2) {
+        Some("0x" | "0X") => (16, &value[2..]),
+        Some("0o" | "0O") => (8, &value[2..]),
+        Some("0b" | "0B") => (2, &value[2..]),
+        _ => (10, value),
+    };
+
+    let digits = digits.replace('_', "");
+    if digits.is_empty() || digits.starts_with(['+', '-']) {
+        return None;
+    }
+
+    let value = i128::from_str_radix(&digits, radix).ok()?;
+    Some(if negative { -value } else { value })
+}
+
+/// Extracts the value of a string constant from the bindings.
+///
+/// bindgen emits string options as byte strings including the C terminator, which
+/// is stripped before the value is returned. Returns `None` if the constant is not
+/// a string, byte string or C string literal, or is not valid UTF-8.
+fn const_str_value(const_item: &ItemConst) -> Option<String> {
+    let Expr::Lit(expr_lit) = const_item.expr.as_ref() else {
+        return None;
+    };
+
+    let bytes = match &expr_lit.lit {
+        Lit::Str(lit_str) => return Some(lit_str.value()),
+        Lit::ByteStr(lit_bytes) => lit_bytes.value(),
+        Lit::CStr(lit_cstr) => lit_cstr.value().into_bytes(),
+        _ => return None,
+    };
+
+    let bytes = bytes.strip_suffix(b"\0").unwrap_or(&bytes);
+    String::from_utf8(bytes.to_vec()).ok()
+}
+
 /// Conditionally includes or excludes Rust items based on NuttX Kconfig options.
 ///
 /// This attribute macro enables conditional compilation based on the values of NuttX Kconfig
@@ -171,7 +758,36 @@ fn find_kconfig_option<'a>(bindings_ast: &'a File, option_name: &str) -> Option<
 /// - The key is the name of a Kconfig option
 /// - The value can be either:
 ///   - `"y"`: The option must be enabled (set to 1)
+///   - `"m"`: The tristate option must be built as a module (set to 2)
 ///   - `"n"`: The option must be disabled or undefined
+///   - Any other string for string options, which must be set to exactly that value
+///
+/// The numeric comparisons `==`, `>`, `>=`, `<` and `<=` take an integer value, written
+/// in decimal or with a `0x`, `0o` or `0b` prefix. The constants they are compared with
+/// may use any integer literal bindgen emits, including suffixed and negative ones.
+///
+/// Options can be grouped with `any(...)`, which holds when at least one of the nested
+/// conditions holds, and `all(...)`, which holds when all of them do. Groups can be
+/// nested, and the top-level list behaves like `all(...)`. `not(...)` negates a single
+/// condition.
+///
+/// `not(...)` differs from `= "n"`, which matches both disabled and undefined options:
+///
+/// | Constant in bindings | `= "y"` | `= "m"` | `= "n"` | `not(= "y")` | `not(== "0")` |
+/// |----------------------|---------|---------|---------|--------------|---------------|
+/// | present, `0`         | false   | false   | false   | true         | false         |
+/// | present, `1`         | true    | false   | false   | false        | true          |
+/// | present, `2`         | false   | true    | false   | true         | true          |
+/// | present, other value | false   | false   | false   | true         | true          |
+/// | absent               | false   | false   | true    | true         | false         |
+///
+/// Boolean and tristate options rely on the numeric encoding of the generated
+/// bindings: an option set to `y` is emitted as `1`, a tristate option set to `m` as
+/// `2`, and an option set to `n` is not emitted at all.
+///
+/// Integer options can instead be compared against an integer value with `==`, `>`,
+/// `>=`, `<` or `<=`, e.g. `CONFIG_RR_INTERVAL > "0"`. When the option is undefined,
+/// only `== "0"` is satisfied.
 ///
 /// # Examples
 ///
@@ -193,6 +809,63 @@ fn find_kconfig_option<'a>(bindings_ast: &'a File, option_name: &str) -> Option<
 /// }
 /// ```
 ///
+/// Include a function when either of two boards is selected:
+/// ```rust
+/// #[kconfig(any(CONFIG_ARCH_BOARD_A = "y", CONFIG_ARCH_BOARD_B = "y"))]
+/// fn board_implementation() {
+///     // This function will be compiled when either board is selected
+/// }
+/// ```
+///
+/// Include a function whenever an option is not enabled:
+/// ```rust
+/// #[kconfig(not(CONFIG_FEATURE_X = "y"))]
+/// fn feature_x_fallback() {
+///     // This function will be compiled when CONFIG_FEATURE_X is anything but enabled
+/// }
+/// ```
+///
+/// Include a function only when a string option has a specific value:
+/// ```rust
+/// #[kconfig(CONFIG_ARCH_BOARD = "sim")]
+/// fn simulator_implementation() {
+///     // This function will only be compiled when CONFIG_ARCH_BOARD is "sim"
+/// }
+/// ```
+///
+/// Include a function only when an integer option exceeds a threshold:
+/// ```rust
+/// #[kconfig(CONFIG_FB_NPLANES >= "2")]
+/// fn multi_plane_implementation() {
+///     // This function will only be compiled when CONFIG_FB_NPLANES is at least 2
+/// }
+/// ```
+///
+/// Include all methods of an `impl` block, or all items of an inline module, at once:
+/// ```rust
+/// struct Bus;
+///
+/// #[kconfig(CONFIG_I2C = "y")]
+/// impl Bus {
+///     fn transfer(&self) {}
+///     fn reset(&self) {}
+/// }
+///
+/// #[kconfig(CONFIG_I2C = "y")]
+/// mod i2c_helpers {
+///     pub fn probe() {}
+///     pub fn scan() {}
+/// }
+/// ```
+///
+/// Items inside the block can carry their own `#[kconfig]` attribute to be gated further.
+/// Attributes cannot be applied to file modules (`mod name;`), use `kconfig_if!` there:
+/// ```rust,ignore
+/// kconfig_if!(CONFIG_I2C = "y" {
+///     pub mod i2c;
+/// });
+/// ```
+///
 /// # How it works
 ///
 /// The macro examines the generated Kconfig bindings at compile time to determine
@@ -200,58 +873,599 @@ fn find_kconfig_option<'a>(bindings_ast: &'a File, option_name: &str) -> Option<
 /// matching names exist in the bindings and if their values match the expected values.
 #[proc_macro_attribute]
 pub fn kconfig(attr: TokenStream, items: TokenStream) -> TokenStream {
-    let kconfig_attr = parse_macro_input!(attr as KconfigAttr);
-    let target_item = parse_macro_input!(items as Item);
+    expand_with_bindings(|bindings_ast| expand_kconfig(attr.into(), items.into(), bindings_ast))
+}
 
-    // Fetch the bindings AST
-    let bindings_ast = match fetch_bindings_ast() {
-        Ok(ast) => ast,
-        Err(error) => return error.to_compile_error().into(),
-    };
+/// Expands to the value of a NuttX Kconfig option.
+///
+/// Integer options expand to an unsuffixed integer literal, so the value takes the type
+/// required by the surrounding expression. String options expand to a string literal
+/// without the C terminator.
+///
+/// # Examples
+///
+/// Size an array from an integer option:
+/// ```rust
+/// static BUFFER: [u8; kconfig_value!(CONFIG_BUFFER_SIZE)] = [0; kconfig_value!(CONFIG_BUFFER_SIZE)];
+/// ```
+///
+/// Read a string option:
+/// ```rust
+/// const BOARD: &str = kconfig_value!(CONFIG_ARCH_BOARD);
+/// ```
+///
+/// # Errors
+///
+/// Produces a compile error if the option is not defined in the bindings, or if its
+/// value is not an integer or string literal.
+#[proc_macro]
+pub fn kconfig_value(input: TokenStream) -> TokenStream {
+    expand_with_bindings(|bindings_ast| expand_kconfig_value(input.into(), bindings_ast))
+}
+
+/// Expands to `true` or `false` depending on NuttX Kconfig options.
+///
+/// A bare option name holds when the option is enabled, i.e. set to `"y"` or, for a
+/// tristate option, `"m"`. The same conditions as the `#[kconfig]` attribute are
+/// accepted as well. The expansion is a boolean literal, so it can be used in
+/// constant expressions and in regular code, where the untaken branch is still type
+/// checked but optimized out.
+///
+/// # Examples
+///
+/// Branch on an option in regular code:
+/// ```rust
+/// if kconfig_enabled!(CONFIG_FB_UPDATE) {
+///     // Issue the FBIO_UPDATE ioctl
+/// }
+/// ```
+///
+/// Evaluate conditions into a constant:
+/// ```rust
+/// const ROUND_ROBIN: bool = kconfig_enabled!(CONFIG_RR_INTERVAL > "0", CONFIG_DEBUG = "n");
+/// ```
+///
+/// # Errors
+///
+/// Produces a compile error in the same cases as the `#[kconfig]` attribute.
+#[proc_macro]
+pub fn kconfig_enabled(input: TokenStream) -> TokenStream {
+    expand_with_bindings(|bindings_ast| expand_kconfig_enabled(input.into(), bindings_ast))
+}
+
+/// Selects between two sets of items based on NuttX Kconfig options.
+///
+/// Takes the same conditions as the `#[kconfig]` attribute, followed by a braced
+/// block that is emitted when they all hold and an optional `else` block that is
+/// emitted otherwise. Writing both halves together keeps an API and its fallback
+/// from drifting apart.
+///
+/// # Examples
+///
+/// Provide a no-op fallback for a method inside an `impl` block:
+/// ```rust
+/// impl FrameBuffer {
+///     kconfig_if!(CONFIG_FB_UPDATE = "y" {
+///         pub fn update_area(&self, area: &Area) -> FrameBufferResult<()> {
+///             // Issue the FBIO_UPDATE ioctl
+///         }
+///     } else {
+///         pub fn update_area(&self, _area: &Area) -> FrameBufferResult<()> {
+///             Ok(())
+///         }
+///     });
+/// }
+/// ```
+#[proc_macro]
+pub fn kconfig_if(input: TokenStream) -> TokenStream {
+    expand_with_bindings(|bindings_ast| expand_kconfig_if(input.into(), bindings_ast))
+}
 
-    let mut include_item = true;
+/// Runs a macro expansion against the bindings AST.
+///
+/// Errors, including those fetching the bindings, are turned into compile errors.
+fn expand_with_bindings(
+    expand: impl FnOnce(&File) -> syn::Result<proc_macro2::TokenStream>,
+) -> TokenStream {
+    fetch_bindings_ast()
+        .and_then(|bindings_ast| expand(&bindings_ast))
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
 
-    for config_option in &kconfig_attr.options {
-        let option_name = config_option.name.to_string();
-        let expected_value = config_option.value.value();
+/// Evaluates a list of conditions, which hold when all of them do.
+///
+/// # Errors
+///
+/// Returns an error if any evaluated option is invalid.
+fn evaluate_all(
+    predicates: &Punctuated<KconfigPredicate, Token![,]>,
+    bindings_ast: &File,
+) -> syn::Result<bool> {
+    for predicate in predicates {
+        if !evaluate_predicate(predicate, bindings_ast)? {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
 
-        // First, check if the option exists in the bindings
-        if let Some(const_item) = find_kconfig_option(&bindings_ast, &option_name) {
-            // Option exists, now check if its value matches
-            if expected_value == "n" {
-                // If option exists but required value is "n", condition fails
-                include_item = false;
-                break;
-            }
+/// Expands `#[kconfig]` to the item if its conditions hold, or to nothing.
+fn expand_kconfig(
+    attr: proc_macro2::TokenStream,
+    item: proc_macro2::TokenStream,
+    bindings_ast: &File,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let kconfig_attr: KconfigAttr = syn::parse2(attr)?;
+    let target_item: Item = syn::parse2(item)?;
 
-            // Check if option value matches the value of the const
-            if let Expr::Lit(expr_lit) = const_item.expr.as_ref() {
-                if let Lit::Int(lit_int) = &expr_lit.lit {
-                    // Parse the integer literal
-                    let actual_value = lit_int.base10_parse::<i64>().unwrap();
-
-                    if expected_value == "y" && actual_value == 1 {
-                        // Option matched, continue checking other options
-                    } else {
-                        // Option value doesn't match
-                        include_item = false;
-                        break;
-                    }
-                }
-            }
+    if evaluate_all(&kconfig_attr.predicates, bindings_ast)? {
+        Ok(quote! { #target_item })
+    } else {
+        Ok(quote! {})
+    }
+}
+
+/// Expands `kconfig_value!` to the literal value of the option.
+///
+/// # Errors
+///
+/// Returns an error if the option is not defined in the bindings, or if its value is
+/// not an integer or string literal.
+fn expand_kconfig_value(
+    input: proc_macro2::TokenStream,
+    bindings_ast: &File,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let option_name: Ident = syn::parse2(input)?;
+
+    let Some(const_item) = find_kconfig_option(bindings_ast, &option_name.to_string()) else {
+        return Err(syn::Error::new(
+            option_name.span(),
+            format!("{} is not defined in the Kconfig bindings", option_name),
+        ));
+    };
+
+    if let Some(value) = const_int_value(const_item, option_name.span())? {
+        // Drop the suffix so the literal adapts to the surrounding expression
+        let literal = proc_macro2::Literal::u128_unsuffixed(value.unsigned_abs());
+        return Ok(if value < 0 {
+            quote! { -#literal }
         } else {
-            // Option doesn't exist in bindings
-            if expected_value != "n" {
-                // If we expected the option to be set (not "n"), but it doesn't exist, condition fails
-                include_item = false;
-                break;
+            quote! { #literal }
+        });
+    }
+
+    if let Some(value) = const_str_value(const_item) {
+        let value = LitStr::new(&value, option_name.span());
+        return Ok(quote! { #value });
+    }
+
+    Err(syn::Error::new(
+        option_name.span(),
+        format!("{} is not an integer or string option", option_name),
+    ))
+}
+
+/// Expands `kconfig_enabled!` to `true` or `false`.
+fn expand_kconfig_enabled(
+    input: proc_macro2::TokenStream,
+    bindings_ast: &File,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let kconfig_enabled: KconfigEnabled = syn::parse2(input)?;
+
+    if evaluate_predicate(&kconfig_enabled.predicate, bindings_ast)? {
+        Ok(quote! { true })
+    } else {
+        Ok(quote! { false })
+    }
+}
+
+/// Expands `kconfig_if!` to the branch selected by its conditions.
+fn expand_kconfig_if(
+    input: proc_macro2::TokenStream,
+    bindings_ast: &File,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let kconfig_if: KconfigIf = syn::parse2(input)?;
+
+    if evaluate_all(&kconfig_if.predicates, bindings_ast)? {
+        Ok(kconfig_if.then_branch)
+    } else {
+        Ok(kconfig_if.else_branch.unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn evaluate(option: &str, bindings: &str) -> bool {
+        let option: KconfigOption = syn::parse_str(option).unwrap();
+        evaluate_option(&option, &parse_file(bindings).unwrap()).unwrap()
+    }
+
+    fn evaluate_error(option: &str, bindings: &str) -> String {
+        let option: KconfigOption = syn::parse_str(option).unwrap();
+        match evaluate_option(&option, &parse_file(bindings).unwrap()) {
+            Ok(_) => panic!("{} was accepted", option.name),
+            Err(error) => error.to_string(),
+        }
+    }
+
+    /// Parses the conditions of an attribute and evaluates them all
+    fn evaluate_attr(attr: &str, bindings: &str) -> syn::Result<bool> {
+        let attr: KconfigAttr = syn::parse_str(attr)?;
+        evaluate_all(&attr.predicates, &parse_file(bindings).unwrap())
+    }
+
+    fn parse_error(attr: &str) -> String {
+        match syn::parse_str::<KconfigAttr>(attr) {
+            Ok(_) => panic!("{attr} was accepted"),
+            Err(error) => error.to_string(),
+        }
+    }
+
+    fn int_value(bindings: &str) -> syn::Result<Option<i128>> {
+        let ast = parse_file(bindings).unwrap();
+        let Some(Item::Const(const_item)) = ast.items.first() else {
+            panic!("expected a constant");
+        };
+        const_int_value(const_item, proc_macro2::Span::call_site())
+    }
+
+    #[test]
+    fn string_option_from_byte_string() {
+        let bindings = r#"pub const CONFIG_ARCH_BOARD: &[u8; 4] = b"sim\0";"#;
+
+        let ast = parse_file(bindings).unwrap();
+        let const_item = find_kconfig_option(&ast, "CONFIG_ARCH_BOARD").unwrap();
+        assert_eq!(const_str_value(const_item).as_deref(), Some("sim"));
+
+        assert!(evaluate(r#"CONFIG_ARCH_BOARD = "sim""#, bindings));
+        assert!(!evaluate(r#"CONFIG_ARCH_BOARD = "esp32""#, bindings));
+        assert!(!evaluate(r#"CONFIG_ARCH_BOARD = "n""#, bindings));
+    }
+
+    #[test]
+    fn string_option_from_str() {
+        let bindings = r#"pub const CONFIG_ARCH_CHIP: &str = "qemu";"#;
+
+        assert!(evaluate(r#"CONFIG_ARCH_CHIP = "qemu""#, bindings));
+        assert!(!evaluate(r#"CONFIG_ARCH_CHIP = "qemu\0""#, bindings));
+        assert!(!evaluate(r#"CONFIG_ARCH_CHIP = "y""#, bindings));
+    }
+
+    #[test]
+    fn int_option_literals() {
+        assert_eq!(int_value("const FOO: u32 = 0x10;").unwrap(), Some(16));
+        assert_eq!(int_value("const FOO: u32 = 0b1010;").unwrap(), Some(10));
+        assert_eq!(int_value("const FOO: u32 = 0o17;").unwrap(), Some(15));
+        assert_eq!(int_value("const FOO: u32 = 16u32;").unwrap(), Some(16));
+        assert_eq!(int_value("const FOO: i32 = -0x10i32;").unwrap(), Some(-16));
+        assert_eq!(int_value(r#"const FOO: &str = "16";"#).unwrap(), None);
+    }
+
+    #[test]
+    fn int_option_overflow() {
+        let bindings = "const FOO: u128 = 0x1_0000_0000_0000_0000_0000_0000_0000_0000;";
+        let error = int_value(bindings).unwrap_err();
+        assert!(error.to_string().starts_with("invalid value for FOO"));
+    }
+
+    #[test]
+    fn tristate_option() {
+        let module = "pub const CONFIG_X: u32 = 2;";
+        assert!(evaluate(r#"CONFIG_X = "m""#, module));
+        assert!(!evaluate(r#"CONFIG_X = "y""#, module));
+        assert!(!evaluate(r#"CONFIG_X = "n""#, module));
+
+        let builtin = "pub const CONFIG_X: u32 = 1;";
+        assert!(evaluate(r#"CONFIG_X = "y""#, builtin));
+        assert!(!evaluate(r#"CONFIG_X = "m""#, builtin));
+
+        assert!(evaluate(r#"CONFIG_X = "n""#, ""));
+    }
+
+    #[test]
+    fn nested_predicates() {
+        let bindings = "pub const CONFIG_A: u32 = 1; pub const CONFIG_B: u32 = 2;";
+        let holds = |attr| evaluate_attr(attr, bindings).unwrap();
+
+        assert!(holds(
+            r#"any(CONFIG_C = "y", all(CONFIG_A = "y", CONFIG_B = "m"))"#
+        ));
+        assert!(!holds(
+            r#"any(CONFIG_C = "y", all(CONFIG_A = "y", CONFIG_B = "y"))"#
+        ));
+        assert!(holds(
+            r#"all(not(CONFIG_C = "y"), any(not(CONFIG_A = "n")))"#
+        ));
+        assert!(holds(r#"not(not(CONFIG_A = "y"))"#));
+        assert!(!holds(
+            r#"CONFIG_A = "y", not(any(CONFIG_B = "m", CONFIG_C = "y"))"#
+        ));
+        assert!(holds(r#"not(all(CONFIG_A = "y", CONFIG_C = "y"),)"#));
+
+        // Empty groups behave like those of cfg
+        assert!(holds("all()"));
+        assert!(!holds("any()"));
+        assert!(holds(""));
+    }
+
+    #[test]
+    fn nested_predicates_short_circuit() {
+        // The invalid comparison is never evaluated
+        let invalid = r#"CONFIG_A > "many""#;
+        let bindings = "pub const CONFIG_A: u32 = 1;";
+
+        assert!(evaluate_attr(&format!(r#"any(CONFIG_A = "y", {invalid})"#), bindings).unwrap());
+        assert!(!evaluate_attr(&format!(r#"all(CONFIG_A = "n", {invalid})"#), bindings).unwrap());
+        assert!(evaluate_attr(&format!(r#"all(CONFIG_A = "y", {invalid})"#), bindings).is_err());
+    }
+
+    #[test]
+    fn malformed_predicates() {
+        assert_eq!(
+            parse_error(r#"one(CONFIG_A = "y")"#),
+            "expected `all`, `any` or `not`"
+        );
+        assert_eq!(
+            parse_error(r#"not(CONFIG_A = "y", CONFIG_B = "y")"#),
+            "`not` takes a single condition"
+        );
+        assert_eq!(
+            parse_error("not()"),
+            "unexpected end of input, expected identifier"
+        );
+
+        // Options need a comparison and a string value
+        assert!(parse_error("CONFIG_A").starts_with("unexpected end of input"));
+        assert_eq!(parse_error("CONFIG_A = y"), "expected string literal");
+        assert_eq!(parse_error("CONFIG_A = 1"), "expected string literal");
+        assert!(parse_error(r#"CONFIG_A != "y""#).starts_with("expected one of"));
+        assert!(parse_error(r#"CONFIG_A = "y" CONFIG_B = "y""#).starts_with("expected `,`"));
+        assert!(parse_error(r#"any(CONFIG_A = "y"; CONFIG_B = "y")"#).starts_with("expected `,`"));
+    }
+
+    #[test]
+    fn int_comparisons() {
+        let bindings = "pub const CONFIG_RR_INTERVAL: u32 = 200;";
+        let holds = |option| evaluate(option, bindings);
+
+        assert!(holds(r#"CONFIG_RR_INTERVAL == "200""#));
+        assert!(!holds(r#"CONFIG_RR_INTERVAL == "199""#));
+        assert!(holds(r#"CONFIG_RR_INTERVAL > "199""#));
+        assert!(!holds(r#"CONFIG_RR_INTERVAL > "200""#));
+        assert!(holds(r#"CONFIG_RR_INTERVAL >= "200""#));
+        assert!(!holds(r#"CONFIG_RR_INTERVAL >= "201""#));
+        assert!(holds(r#"CONFIG_RR_INTERVAL < "201""#));
+        assert!(!holds(r#"CONFIG_RR_INTERVAL < "200""#));
+        assert!(holds(r#"CONFIG_RR_INTERVAL <= "200""#));
+        assert!(!holds(r#"CONFIG_RR_INTERVAL <= "199""#));
+
+        // Expected values may use any radix, and negative constants compare below zero
+        assert!(holds(r#"CONFIG_RR_INTERVAL == "0xc8""#));
+        assert!(holds(r#"CONFIG_RR_INTERVAL > "0b1100_0111""#));
+        assert!(evaluate(
+            r#"CONFIG_OFFSET < "0""#,
+            "pub const CONFIG_OFFSET: i32 = -4;"
+        ));
+        assert!(evaluate(
+            r#"CONFIG_OFFSET >= "-4""#,
+            "pub const CONFIG_OFFSET: i32 = -4;"
+        ));
+    }
+
+    #[test]
+    fn int_comparisons_of_missing_option() {
+        // Only an equality test against zero holds for an undefined option
+        assert!(evaluate(r#"CONFIG_RR_INTERVAL == "0""#, ""));
+        assert!(!evaluate(r#"CONFIG_RR_INTERVAL == "1""#, ""));
+        assert!(!evaluate(r#"CONFIG_RR_INTERVAL < "1""#, ""));
+        assert!(!evaluate(r#"CONFIG_RR_INTERVAL >= "0""#, ""));
+    }
+
+    #[test]
+    fn int_comparisons_of_tristate() {
+        let module = "pub const CONFIG_X: u32 = 2;";
+        assert!(evaluate(r#"CONFIG_X == "2""#, module));
+        assert!(evaluate(r#"CONFIG_X > "1""#, module));
+        assert!(!evaluate(r#"CONFIG_X <= "1""#, module));
+    }
+
+    #[test]
+    fn string_comparisons() {
+        let bindings = r#"pub const CONFIG_ARCH: &[u8; 4] = b"arm\0";"#;
+
+        // Strings are only matched with `=`, numeric comparisons never hold
+        assert!(evaluate(r#"CONFIG_ARCH = "arm""#, bindings));
+        assert!(!evaluate(r#"CONFIG_ARCH = "ARM""#, bindings));
+        assert!(!evaluate(r#"CONFIG_ARCH == "0""#, bindings));
+        assert!(!evaluate(r#"CONFIG_ARCH > "0""#, bindings));
+
+        // Integer options are not matched as strings
+        assert!(!evaluate(
+            r#"CONFIG_X = "200""#,
+            "pub const CONFIG_X: u32 = 200;"
+        ));
+    }
+
+    #[test]
+    fn comparison_type_mismatch() {
+        let bindings = "pub const CONFIG_RR_INTERVAL: u32 = 200;";
+
+        for op in ["==", ">", ">=", "<", "<="] {
+            for value in ["y", "fast", "", "0x", "1.5", "--1"] {
+                assert_eq!(
+                    evaluate_error(&format!(r#"CONFIG_RR_INTERVAL {op} "{value}""#), bindings),
+                    "expected an integer value for CONFIG_RR_INTERVAL"
+                );
             }
         }
+
+        // The value is checked even if the option is undefined
+        assert_eq!(
+            evaluate_error(r#"CONFIG_MISSING > "y""#, ""),
+            "expected an integer value for CONFIG_MISSING"
+        );
     }
 
-    if include_item {
-        quote! { #target_item }.into()
-    } else {
-        quote! {}.into()
+    /// Runs an expansion on source text, returning the expanded tokens as a string
+    fn expand(
+        expand: fn(proc_macro2::TokenStream, &File) -> syn::Result<proc_macro2::TokenStream>,
+        input: &str,
+        bindings: &str,
+    ) -> syn::Result<String> {
+        let input = input.parse().unwrap();
+        expand(input, &parse_file(bindings).unwrap()).map(|tokens| tokens.to_string())
+    }
+
+    #[test]
+    fn kconfig_if_expansion() {
+        let bindings = "pub const CONFIG_A: u32 = 1;";
+        let input = r#"CONFIG_A = "y", CONFIG_B = "n" { fn a() {} } else { fn b() {} }"#;
+        assert_eq!(
+            expand(expand_kconfig_if, input, bindings).unwrap(),
+            "fn a () { }"
+        );
+        assert_eq!(expand(expand_kconfig_if, input, "").unwrap(), "fn b () { }");
+
+        // Without an else branch, nothing is emitted
+        let input = r#"any(CONFIG_B = "y") { pub mod b; }"#;
+        assert_eq!(expand(expand_kconfig_if, input, bindings).unwrap(), "");
+        let input = r#"not(CONFIG_B = "y") { pub mod b; }"#;
+        assert_eq!(
+            expand(expand_kconfig_if, input, bindings).unwrap(),
+            "pub mod b ;"
+        );
+    }
+
+    #[test]
+    fn kconfig_enabled_expansion() {
+        let bindings = "pub const CONFIG_A: u32 = 1; pub const CONFIG_M: u32 = 2;";
+        let enabled = |input| expand(expand_kconfig_enabled, input, bindings).unwrap();
+
+        // A bare name holds for built-in and module options
+        assert_eq!(enabled("CONFIG_A"), "true");
+        assert_eq!(enabled("CONFIG_M"), "true");
+        assert_eq!(enabled("CONFIG_B"), "false");
+
+        assert_eq!(enabled(r#"CONFIG_A = "y", CONFIG_M = "m""#), "true");
+        assert_eq!(enabled(r#"CONFIG_A = "y", CONFIG_M = "y""#), "false");
+        assert_eq!(enabled(r#"any(CONFIG_B = "y", CONFIG_A > "0")"#), "true");
+    }
+
+    #[test]
+    fn kconfig_attribute_expansion() {
+        let bindings = "pub const CONFIG_A: u32 = 1;";
+        let item: proc_macro2::TokenStream = "fn a() {}".parse().unwrap();
+        let attr = |attr: &str| {
+            expand_kconfig(
+                attr.parse().unwrap(),
+                item.clone(),
+                &parse_file(bindings).unwrap(),
+            )
+            .unwrap()
+            .to_string()
+        };
+
+        assert_eq!(attr(r#"CONFIG_A = "y""#), "fn a () { }");
+        assert_eq!(attr(r#"CONFIG_A = "y", CONFIG_B = "y""#), "");
+    }
+
+    #[test]
+    fn kconfig_value_expansion() {
+        let bindings = r#"
+            pub const CONFIG_SIZE: u32 = 64u32;
+            pub const CONFIG_OFFSET: i32 = -4;
+            pub const CONFIG_BOARD: &[u8; 4] = b"sim\0";
+            pub const CONFIG_PTR: *const u8 = 0 as *const u8;
+        "#;
+        let value = |input| expand(expand_kconfig_value, input, bindings);
+
+        assert_eq!(value("CONFIG_SIZE").unwrap(), "64");
+        assert_eq!(value("CONFIG_OFFSET").unwrap(), "- 4");
+        assert_eq!(value("CONFIG_BOARD").unwrap(), r#""sim""#);
+        assert_eq!(
+            value("CONFIG_PTR").unwrap_err().to_string(),
+            "CONFIG_PTR is not an integer or string option"
+        );
+    }
+
+    #[test]
+    fn unknown_option() {
+        let error = expand(expand_kconfig_value, "CONFIG_MISSING", "").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "CONFIG_MISSING is not defined in the Kconfig bindings"
+        );
+
+        // Invalid conditions are reported by every macro
+        let input = r#"CONFIG_A > "y" { fn a() {} }"#;
+        let error = expand(expand_kconfig_if, input, "").unwrap_err();
+        assert_eq!(error.to_string(), "expected an integer value for CONFIG_A");
+        let error = expand(expand_kconfig_enabled, r#"CONFIG_A > "y""#, "").unwrap_err();
+        assert_eq!(error.to_string(), "expected an integer value for CONFIG_A");
+    }
+
+    #[test]
+    fn scan_kconfig_file() {
+        let source = "
+menu \"Drivers\"
+
+config FB_UPDATE
+\tbool \"Update support\"
+\tdefault n
+\t---help---
+\t\tThe config entries below update the display.
+
+menuconfig PWM
+\tbool \"PWM support\"
+
+if PWM
+  config PWM_MULTICHAN
+\tbool \"Multiple channels\"
+endif
+
+# config COMMENTED_OUT
+choice
+\tprompt \"Board\"
+endchoice
+endmenu
+";
+        let mut options = HashSet::new();
+        scan_kconfig_options(source, &mut options);
+
+        let mut options: Vec<_> = options.into_iter().collect();
+        options.sort();
+        assert_eq!(
+            options,
+            ["CONFIG_FB_UPDATE", "CONFIG_PWM", "CONFIG_PWM_MULTICHAN"]
+        );
+    }
+
+    #[test]
+    fn strict_accepts_declared_option() {
+        let mut options = HashSet::new();
+        scan_kconfig_options("config PWM_MULTICHAN\n\tbool", &mut options);
+        let bindings = parse_file("pub const CONFIG_PWM: u32 = 1;").unwrap();
+
+        // A declared option missing from the bindings is disabled
+        let name = Ident::new("CONFIG_PWM_MULTICHAN", proc_macro2::Span::call_site());
+        assert!(check_declared(&name, &bindings, &options).is_ok());
+    }
+
+    #[test]
+    fn strict_rejects_unknown_option() {
+        let mut options = HashSet::new();
+        scan_kconfig_options("config PWM_MULTICHAN\n\tbool", &mut options);
+        let bindings = parse_file("pub const CONFIG_PWM: u32 = 1;").unwrap();
+
+        // A misspelled option is reported rather than treated as disabled
+        let name = Ident::new("CONFIG_PWM_MULTICHANNEL", proc_macro2::Span::call_site());
+        let error = check_declared(&name, &bindings, &options).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "unknown Kconfig option CONFIG_PWM_MULTICHANNEL"
+        );
     }
 }
@@ -0,0 +1,219 @@
+//! ADC character driver interface
+//!
+//! This module provides Rust bindings for the NuttX ADC driver interface.
+//! It allows triggering conversions and reading the converted samples of
+//! each channel.
+//!
+//! The implementation matches the NuttX ADC interface defined in
+//! `nuttx/include/nuttx/analog/adc.h`.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use core::ffi::CStr;
+//! use nuttx::analog::adc::{Adc, AdcSample};
+//!
+//! let mut adc = Adc::open(CStr::from_bytes_with_nul(b"/dev/adc0\0").unwrap()).unwrap();
+//! adc.trigger().unwrap();
+//!
+//! let mut samples = [AdcSample::default(); 4];
+//! let count = adc.read_samples(&mut samples).unwrap();
+//! ```
+
+use core::ffi::CStr;
+use core::mem::size_of;
+use core::ptr;
+use libc::{EIO, O_RDONLY, c_int, c_void, ioctl, open, read};
+
+use crate::bindings::adc_msg_s;
+use crate::errno::errno;
+use crate::fd::{AsRawFd, RawFd};
+use crate::io::ioctl::IoctlRequest;
+
+/// IOCTL command to trigger a conversion
+///
+/// Matches C's ANIOC_TRIGGER
+const ANIOC_TRIGGER: IoctlRequest = crate::bindings::ANIOC_TRIGGER as IoctlRequest;
+
+/// Size of a packed `adc_msg_s` as delivered by the driver
+const MSG_SIZE: usize = size_of::<adc_msg_s>();
+
+/// Maximum number of messages received by a single read
+const READ_MSGS: usize = 32;
+
+/// Result type for ADC operations, errors hold the negative errno
+pub type AdcResult<T> = Result<T, i32>;
+
+/// A converted sample of a single ADC channel
+///
+/// Decoded from the packed C `adc_msg_s` structure delivered by the driver.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AdcSample {
+    /// The channel the sample was converted on
+    pub channel: u8,
+    /// The converted value
+    pub data: i32,
+}
+
+impl From<adc_msg_s> for AdcSample {
+    fn from(msg: adc_msg_s) -> Self {
+        AdcSample {
+            channel: msg.am_channel,
+            data: msg.am_data,
+        }
+    }
+}
+
+/// Decodes a packed message as delivered by the driver
+fn decode(msg: &[u8; MSG_SIZE]) -> AdcSample {
+    // SAFETY: `msg` holds MSG_SIZE bytes, and adc_msg_s is a packed C
+    // structure valid for any byte values
+    AdcSample::from(unsafe { ptr::read_unaligned(msg.as_ptr().cast::<adc_msg_s>()) })
+}
+
+/// Decodes the messages received by a read
+///
+/// # Returns
+/// The number of samples stored in `buf`
+///
+/// # Errors
+/// Returns `-EIO` if `bytes` ends within a message
+///
+/// # Panics
+/// Panics if `buf` cannot hold all messages of `bytes`
+fn decode_all(bytes: &[u8], buf: &mut [AdcSample]) -> AdcResult<usize> {
+    let msgs = bytes.chunks_exact(MSG_SIZE);
+    if !msgs.remainder().is_empty() {
+        return Err(-EIO);
+    }
+
+    let count = msgs.len();
+    for (sample, msg) in buf.iter_mut().zip(msgs) {
+        *sample = decode(msg.try_into().unwrap());
+    }
+    Ok(count)
+}
+
+/// Represents an open ADC device
+///
+/// The underlying device is closed when the Adc is dropped.
+#[derive(Debug)]
+pub struct Adc {
+    fd: c_int,
+}
+
+impl Adc {
+    /// Opens an ADC device at the specified path
+    ///
+    /// # Arguments
+    /// * `path` - Path to the ADC device as a C string (e.g. "/dev/adc0")
+    ///
+    /// # Errors
+    /// Returns the negative errno if the device could not be opened
+    pub fn open(path: &CStr) -> AdcResult<Self> {
+        let fd = unsafe { open(path.as_ptr(), O_RDONLY) };
+        if fd < 0 {
+            return Err(errno());
+        }
+        Ok(Adc { fd })
+    }
+
+    /// Reads converted samples from the device
+    ///
+    /// The driver only delivers whole messages, at most 32 samples are read
+    /// per call.
+    ///
+    /// # Arguments
+    /// * `buf` - Buffer receiving the decoded samples
+    ///
+    /// # Returns
+    /// The number of samples stored in `buf`
+    ///
+    /// # Errors
+    /// - `-EIO` if the read ended within a message
+    /// - The negative errno if the read fails
+    pub fn read_samples(&mut self, buf: &mut [AdcSample]) -> AdcResult<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        let mut bytes = [0u8; READ_MSGS * MSG_SIZE];
+        let len = buf.len().min(READ_MSGS) * MSG_SIZE;
+
+        // SAFETY: `bytes` is valid for writes of `len` bytes
+        let ret = unsafe { read(self.fd, bytes.as_mut_ptr() as *mut c_void, len) };
+        if ret < 0 {
+            return Err(errno());
+        }
+
+        decode_all(&bytes[..ret as usize], buf)
+    }
+
+    /// Triggers a conversion on all channels of the device
+    ///
+    /// Only needed when the ADC is not driven by a timer or another trigger.
+    ///
+    /// # Errors
+    /// Returns the negative errno if the ioctl fails
+    pub fn trigger(&self) -> AdcResult<()> {
+        // SAFETY: ANIOC_TRIGGER takes no argument
+        let result = unsafe { ioctl(self.fd, ANIOC_TRIGGER as _, 0) };
+
+        if result < 0 { Err(errno()) } else { Ok(()) }
+    }
+}
+
+impl AsRawFd for Adc {
+    /// Returns the file descriptor of the ADC device, e.g. for use in an
+    /// external poll loop. The descriptor stays owned by the Adc.
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd
+    }
+}
+
+impl Drop for Adc {
+    /// Closes the ADC device when the Adc goes out of scope
+    fn drop(&mut self) {
+        unsafe { libc::close(self.fd) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::vec::Vec;
+
+    /// Packs messages as the driver delivers them
+    fn stream(samples: &[AdcSample]) -> Vec<u8> {
+        samples
+            .iter()
+            .flat_map(|sample| {
+                let mut msg = [0; MSG_SIZE];
+                msg[0] = sample.channel;
+                msg[1..].copy_from_slice(&sample.data.to_ne_bytes());
+                msg
+            })
+            .collect()
+    }
+
+    #[test]
+    fn decode_messages() {
+        let samples: Vec<AdcSample> = (0..4)
+            .map(|i| AdcSample {
+                channel: i as u8,
+                data: i * 1000 - 3000,
+            })
+            .collect();
+        let bytes = stream(&samples);
+
+        let mut buf = [AdcSample::default(); 4];
+        assert_eq!(decode_all(&bytes, &mut buf), Ok(4));
+        assert_eq!(buf[..], samples[..]);
+        assert_eq!(decode_all(&[], &mut buf), Ok(0));
+
+        // A read ending within a message is not spliced onto the next one
+        for len in [1, MSG_SIZE - 1, MSG_SIZE + 2] {
+            assert_eq!(decode_all(&bytes[..len], &mut buf), Err(-EIO));
+        }
+    }
+}
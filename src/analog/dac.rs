@@ -0,0 +1,118 @@
+//! DAC character driver interface
+//!
+//! This module provides Rust bindings for the NuttX DAC driver interface.
+//! It allows queueing output values for each channel and triggering their
+//! conversion.
+//!
+//! The implementation matches the NuttX DAC interface defined in
+//! `nuttx/include/nuttx/analog/dac.h`.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use core::ffi::CStr;
+//! use nuttx::analog::dac::Dac;
+//!
+//! let mut dac = Dac::open(CStr::from_bytes_with_nul(b"/dev/dac0\0").unwrap()).unwrap();
+//! dac.write_channel(0, 2048).unwrap();
+//! ```
+
+use core::ffi::CStr;
+use core::mem::size_of;
+use libc::{EIO, O_WRONLY, c_int, c_void, ioctl, open, write};
+
+use crate::bindings::dac_msg_s;
+use crate::errno::errno;
+use crate::fd::{AsRawFd, RawFd};
+use crate::io::ioctl::IoctlRequest;
+
+/// IOCTL command to trigger a conversion
+///
+/// Matches C's ANIOC_TRIGGER
+const ANIOC_TRIGGER: IoctlRequest = crate::bindings::ANIOC_TRIGGER as IoctlRequest;
+
+/// Result type for DAC operations, errors hold the negative errno
+pub type DacResult<T> = Result<T, i32>;
+
+/// Represents an open DAC device
+///
+/// The underlying device is closed when the Dac is dropped.
+#[derive(Debug)]
+pub struct Dac {
+    fd: c_int,
+}
+
+impl Dac {
+    /// Opens a DAC device at the specified path
+    ///
+    /// # Arguments
+    /// * `path` - Path to the DAC device as a C string (e.g. "/dev/dac0")
+    ///
+    /// # Errors
+    /// Returns the negative errno if the device could not be opened
+    pub fn open(path: &CStr) -> DacResult<Self> {
+        let fd = unsafe { open(path.as_ptr(), O_WRONLY) };
+        if fd < 0 {
+            return Err(errno());
+        }
+        Ok(Dac { fd })
+    }
+
+    /// Queues a value for output on a channel
+    ///
+    /// Depending on the driver the value is converted immediately, or once
+    /// the conversion is started with [`Dac::trigger`].
+    ///
+    /// # Arguments
+    /// * `channel` - The channel to drive
+    /// * `value` - The value to convert
+    ///
+    /// # Errors
+    /// - `-EIO` if the driver accepted less than the whole message
+    /// - The negative errno if the write fails
+    pub fn write_channel(&mut self, channel: u8, value: u32) -> DacResult<()> {
+        let msg = dac_msg_s {
+            am_channel: channel,
+            am_data: value as _,
+        };
+        let size = size_of::<dac_msg_s>();
+
+        let ret = unsafe { write(self.fd, &msg as *const dac_msg_s as *const c_void, size) };
+
+        if ret < 0 {
+            return Err(errno());
+        }
+        if (ret as usize) < size {
+            return Err(-EIO);
+        }
+        Ok(())
+    }
+
+    /// Triggers the conversion of the queued values
+    ///
+    /// Allows updating several channels synchronously on DACs that support it.
+    ///
+    /// # Errors
+    /// Returns the negative errno if the ioctl fails
+    pub fn trigger(&self) -> DacResult<()> {
+        // SAFETY: ANIOC_TRIGGER takes no argument
+        let result = unsafe { ioctl(self.fd, ANIOC_TRIGGER as _, 0) };
+
+        if result < 0 { Err(errno()) } else { Ok(()) }
+    }
+}
+
+impl AsRawFd for Dac {
+    /// Returns the file descriptor of the DAC device, e.g. for use in an
+    /// external poll loop. The descriptor stays owned by the Dac.
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd
+    }
+}
+
+impl Drop for Dac {
+    /// Closes the DAC device when the Dac goes out of scope
+    fn drop(&mut self) {
+        unsafe { libc::close(self.fd) };
+    }
+}
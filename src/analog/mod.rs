@@ -0,0 +1,2 @@
+pub mod adc;
+pub mod dac;
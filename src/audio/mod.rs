@@ -0,0 +1,7 @@
+use kconfig::kconfig_if;
+
+// Attributes cannot be applied to file modules, so the module is gated with
+// kconfig_if! rather than #[kconfig]
+kconfig_if!(CONFIG_AUDIO = "y" {
+    pub mod pcm;
+});
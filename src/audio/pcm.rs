@@ -0,0 +1,558 @@
+//! PCM audio output interface
+//!
+//! This module provides Rust bindings for the NuttX audio subsystem. It allows
+//! playing 16-bit PCM samples through an audio device such as
+//! `/dev/audio/pcm0`.
+//!
+//! Audio buffers are allocated by the driver and exchanged through a message
+//! queue: filled buffers are enqueued for playback, and the driver reports each
+//! buffer it has played so it can be refilled.
+//!
+//! The implementation matches the NuttX audio interface defined in
+//! `nuttx/include/nuttx/audio/audio.h`. The module is only available with
+//! `CONFIG_AUDIO`.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use core::ffi::CStr;
+//! use nuttx::audio::pcm::AudioOut;
+//!
+//! let path = CStr::from_bytes_with_nul(b"/dev/audio/pcm0\0").unwrap();
+//! let mut audio = AudioOut::open(path).unwrap();
+//! audio.configure(16_000, 1).unwrap();
+//!
+//! let beep = [0i16; 1600];
+//! audio.write_all(&beep).unwrap();
+//! audio.drain().unwrap();
+//! ```
+
+use core::ffi::CStr;
+use core::fmt::{self, Write};
+use core::mem::{size_of, zeroed};
+use core::ptr;
+use kconfig::{kconfig, kconfig_value};
+use libc::{EINVAL, ENOMEM, O_CREAT, O_RDWR, c_int, c_uint, c_ulong, c_void, ioctl, open};
+
+use crate::bindings::{
+    AUDIO_FMT_PCM, AUDIO_MSG_COMPLETE, AUDIO_MSG_DEQUEUE, AUDIO_TYPE_OUTPUT, ap_buffer_s,
+    audio_buf_desc_s, audio_caps_desc_s, audio_msg_s, mq_attr, mq_close, mq_open, mq_receive,
+    mq_unlink, mqd_t,
+};
+use crate::errno::errno;
+use crate::fd::{AsRawFd, RawFd};
+use crate::io::ioctl::IoctlRequest;
+
+/// IOCTL command to configure the audio stream
+///
+/// Matches C's AUDIOIOC_CONFIGURE
+const AUDIOIOC_CONFIGURE: IoctlRequest = crate::bindings::AUDIOIOC_CONFIGURE as IoctlRequest;
+
+/// IOCTL command to start playback
+///
+/// Matches C's AUDIOIOC_START
+const AUDIOIOC_START: IoctlRequest = crate::bindings::AUDIOIOC_START as IoctlRequest;
+
+/// IOCTL command to stop playback
+///
+/// Matches C's AUDIOIOC_STOP
+#[kconfig(CONFIG_AUDIO_EXCLUDE_STOP = "n")]
+const AUDIOIOC_STOP: IoctlRequest = crate::bindings::AUDIOIOC_STOP as IoctlRequest;
+
+/// IOCTL command to pause playback
+///
+/// Matches C's AUDIOIOC_PAUSE
+#[kconfig(CONFIG_AUDIO_EXCLUDE_PAUSE_RESUME = "n")]
+const AUDIOIOC_PAUSE: IoctlRequest = crate::bindings::AUDIOIOC_PAUSE as IoctlRequest;
+
+/// IOCTL command to resume paused playback
+///
+/// Matches C's AUDIOIOC_RESUME
+#[kconfig(CONFIG_AUDIO_EXCLUDE_PAUSE_RESUME = "n")]
+const AUDIOIOC_RESUME: IoctlRequest = crate::bindings::AUDIOIOC_RESUME as IoctlRequest;
+
+/// IOCTL command to get the preferred number and size of buffers
+///
+/// Matches C's AUDIOIOC_GETBUFFERINFO
+#[kconfig(CONFIG_AUDIO_DRIVER_SPECIFIC_BUFFERS = "y")]
+const AUDIOIOC_GETBUFFERINFO: IoctlRequest = crate::bindings::AUDIOIOC_GETBUFFERINFO as IoctlRequest;
+
+/// IOCTL command to allocate an audio buffer
+///
+/// Matches C's AUDIOIOC_ALLOCBUFFER
+const AUDIOIOC_ALLOCBUFFER: IoctlRequest = crate::bindings::AUDIOIOC_ALLOCBUFFER as IoctlRequest;
+
+/// IOCTL command to free an audio buffer
+///
+/// Matches C's AUDIOIOC_FREEBUFFER
+const AUDIOIOC_FREEBUFFER: IoctlRequest = crate::bindings::AUDIOIOC_FREEBUFFER as IoctlRequest;
+
+/// IOCTL command to enqueue a filled buffer for playback
+///
+/// Matches C's AUDIOIOC_ENQUEUEBUFFER
+const AUDIOIOC_ENQUEUEBUFFER: IoctlRequest = crate::bindings::AUDIOIOC_ENQUEUEBUFFER as IoctlRequest;
+
+/// IOCTL command to register the message queue receiving driver messages
+///
+/// Matches C's AUDIOIOC_REGISTERMQ
+const AUDIOIOC_REGISTERMQ: IoctlRequest = crate::bindings::AUDIOIOC_REGISTERMQ as IoctlRequest;
+
+/// IOCTL command to unregister the message queue
+///
+/// Matches C's AUDIOIOC_UNREGISTERMQ
+const AUDIOIOC_UNREGISTERMQ: IoctlRequest = crate::bindings::AUDIOIOC_UNREGISTERMQ as IoctlRequest;
+
+/// Maximum number of buffers used by an AudioOut
+pub const MAX_BUFFERS: usize = 8;
+
+/// Result type for audio operations, errors hold the negative errno
+pub type AudioResult<T> = Result<T, i32>;
+
+/// Represents an open audio output device
+///
+/// The device plays 16-bit signed samples in native byte order, with the
+/// channels of each frame interleaved.
+#[derive(Debug)]
+pub struct AudioOut {
+    fd: c_int,
+    mq: mqd_t,
+    mq_name: [u8; 32],
+    buffers: [*mut ap_buffer_s; MAX_BUFFERS],
+    queued: [bool; MAX_BUFFERS],
+    nbuffers: usize,
+    channels: u8,
+    started: bool,
+}
+
+impl AudioOut {
+    /// Opens an audio device at the specified path
+    ///
+    /// This also allocates the audio buffers and registers the message queue
+    /// through which the driver returns played buffers.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the audio device as a C string (e.g. "/dev/audio/pcm0")
+    ///
+    /// # Errors
+    /// Returns the negative errno if the device could not be opened, or the
+    /// message queue or buffers could not be set up
+    pub fn open(path: &CStr) -> AudioResult<Self> {
+        let fd = unsafe { open(path.as_ptr(), O_RDWR) };
+        if fd < 0 {
+            return Err(errno());
+        }
+
+        // The queue is named after the task and descriptor, which are unique
+        // while the device is open
+        let mut mq_name = [0u8; 32];
+        let mut name = NameWriter {
+            buf: &mut mq_name,
+            len: 0,
+        };
+        let _ = write!(name, "/tmp/pcm{}_{}\0", unsafe { libc::getpid() }, fd);
+
+        // SAFETY: The attributes outlive the call and the name is NUL terminated,
+        // as it is far shorter than the buffer
+        let mq = unsafe {
+            let mut attr: mq_attr = zeroed();
+            attr.mq_maxmsg = (MAX_BUFFERS + 2) as _;
+            attr.mq_msgsize = size_of::<audio_msg_s>() as _;
+            mq_open(
+                mq_name.as_ptr() as *const _,
+                O_RDWR | O_CREAT,
+                0o644 as c_uint,
+                &mut attr as *mut mq_attr,
+            )
+        };
+        if mq < 0 {
+            let err = errno();
+            unsafe { libc::close(fd) };
+            return Err(err);
+        }
+
+        // From here on, Drop releases whatever has been set up
+        let mut audio = AudioOut {
+            fd,
+            mq,
+            mq_name,
+            buffers: [ptr::null_mut(); MAX_BUFFERS],
+            queued: [false; MAX_BUFFERS],
+            nbuffers: 0,
+            channels: 1,
+            started: false,
+        };
+
+        // SAFETY: AUDIOIOC_REGISTERMQ takes the queue descriptor itself
+        let result = unsafe {
+            ioctl(
+                audio.fd,
+                AUDIOIOC_REGISTERMQ as _,
+                audio.mq as c_ulong,
+            )
+        };
+        if result < 0 {
+            return Err(errno());
+        }
+
+        let (nbuffers, buffer_size) = Self::buffer_info(fd);
+        for index in 0..nbuffers.min(MAX_BUFFERS) {
+            // SAFETY: A zeroed audio_buf_desc_s is valid, the driver stores the
+            // buffer address through the passed pointer
+            let result = unsafe {
+                let mut desc: audio_buf_desc_s = zeroed();
+                desc.numbytes = buffer_size as _;
+                desc.u.pbuffer = &mut audio.buffers[index];
+                ioctl(
+                    audio.fd,
+                    AUDIOIOC_ALLOCBUFFER as _,
+                    &mut desc as *mut audio_buf_desc_s as *mut c_void,
+                )
+            };
+            if result < 0 {
+                return Err(errno());
+            }
+            audio.nbuffers += 1;
+        }
+
+        if audio.nbuffers == 0 {
+            return Err(-ENOMEM);
+        }
+        Ok(audio)
+    }
+
+    /// Configures the stream for 16-bit PCM samples
+    ///
+    /// # Arguments
+    /// * `sample_rate` - The number of frames per second, e.g. 44100
+    /// * `channels` - The number of interleaved channels, e.g. 2 for stereo
+    ///
+    /// # Errors
+    /// - `-EINVAL` if `channels` is 0
+    /// - The negative errno if the ioctl fails, e.g. if the device does not
+    ///   support the configuration
+    pub fn configure(&mut self, sample_rate: u32, channels: u8) -> AudioResult<()> {
+        if channels == 0 {
+            return Err(-EINVAL);
+        }
+
+        // SAFETY: A zeroed audio_caps_desc_s is valid, the control bytes are
+        // plain integers laid out as expected by the driver
+        let result = unsafe {
+            let mut desc: audio_caps_desc_s = zeroed();
+            desc.caps.ac_len = size_of_caps();
+            desc.caps.ac_type = AUDIO_TYPE_OUTPUT as u8;
+            desc.caps.ac_subtype = AUDIO_FMT_PCM as u8;
+            desc.caps.ac_channels = channels;
+            desc.caps.ac_controls.hw[0] = sample_rate as u16;
+            desc.caps.ac_controls.b[3] = (sample_rate >> 16) as u8;
+            desc.caps.ac_controls.b[2] = 16;
+            ioctl(
+                self.fd,
+                AUDIOIOC_CONFIGURE as _,
+                &mut desc as *mut audio_caps_desc_s as *mut c_void,
+            )
+        };
+
+        if result < 0 {
+            return Err(errno());
+        }
+        self.channels = channels;
+        Ok(())
+    }
+
+    /// Starts playback of the enqueued buffers
+    ///
+    /// [`AudioOut::write_all`] starts playback on its own, this is only needed
+    /// after [`AudioOut::stop`].
+    ///
+    /// # Errors
+    /// Returns the negative errno if the ioctl fails
+    pub fn start(&mut self) -> AudioResult<()> {
+        self.command(AUDIOIOC_START)?;
+        self.started = true;
+        Ok(())
+    }
+
+    /// Stops playback
+    ///
+    /// The driver returns the enqueued buffers without playing them.
+    ///
+    /// # Errors
+    /// - `-ENOTSUP` if stopping is excluded by `CONFIG_AUDIO_EXCLUDE_STOP`
+    /// - The negative errno if the ioctl fails
+    #[kconfig(CONFIG_AUDIO_EXCLUDE_STOP = "n")]
+    pub fn stop(&mut self) -> AudioResult<()> {
+        self.command(AUDIOIOC_STOP)?;
+        self.started = false;
+        Ok(())
+    }
+
+    #[kconfig(CONFIG_AUDIO_EXCLUDE_STOP = "y")]
+    pub fn stop(&mut self) -> AudioResult<()> {
+        Err(-libc::ENOTSUP)
+    }
+
+    /// Pauses playback
+    ///
+    /// # Errors
+    /// - `-ENOTSUP` if pausing is excluded by `CONFIG_AUDIO_EXCLUDE_PAUSE_RESUME`
+    /// - The negative errno if the ioctl fails
+    #[kconfig(CONFIG_AUDIO_EXCLUDE_PAUSE_RESUME = "n")]
+    pub fn pause(&self) -> AudioResult<()> {
+        self.command(AUDIOIOC_PAUSE)
+    }
+
+    #[kconfig(CONFIG_AUDIO_EXCLUDE_PAUSE_RESUME = "y")]
+    pub fn pause(&self) -> AudioResult<()> {
+        Err(-libc::ENOTSUP)
+    }
+
+    /// Resumes paused playback
+    ///
+    /// # Errors
+    /// - `-ENOTSUP` if pausing is excluded by `CONFIG_AUDIO_EXCLUDE_PAUSE_RESUME`
+    /// - The negative errno if the ioctl fails
+    #[kconfig(CONFIG_AUDIO_EXCLUDE_PAUSE_RESUME = "n")]
+    pub fn resume(&self) -> AudioResult<()> {
+        self.command(AUDIOIOC_RESUME)
+    }
+
+    #[kconfig(CONFIG_AUDIO_EXCLUDE_PAUSE_RESUME = "y")]
+    pub fn resume(&self) -> AudioResult<()> {
+        Err(-libc::ENOTSUP)
+    }
+
+    /// Queues samples for playback, blocking until they all fit in a buffer
+    ///
+    /// Playback starts once all buffers are filled, or at the latest when the
+    /// call returns. Use [`AudioOut::drain`] to wait until the samples have been
+    /// played.
+    ///
+    /// # Arguments
+    /// * `samples` - Interleaved samples, a multiple of the channel count
+    ///
+    /// # Errors
+    /// - `-EINVAL` if the samples do not form whole frames, or a buffer cannot
+    ///   hold a single frame
+    /// - The negative errno if enqueueing or waiting for a buffer fails
+    pub fn write_all(&mut self, samples: &[i16]) -> AudioResult<()> {
+        let channels = self.channels as usize;
+        if !samples.len().is_multiple_of(channels) {
+            return Err(-EINVAL);
+        }
+
+        let mut rest = samples;
+        while !rest.is_empty() {
+            let index = self.free_buffer()?;
+            let apb = self.buffers[index];
+
+            // SAFETY: The buffer was allocated by the driver and is not queued,
+            // so it is owned by this side until it is enqueued again
+            unsafe {
+                let capacity = (*apb).nmaxbytes as usize / (2 * channels) * channels;
+                if capacity == 0 {
+                    return Err(-EINVAL);
+                }
+
+                let count = capacity.min(rest.len());
+                ptr::copy_nonoverlapping(rest.as_ptr() as *const u8, (*apb).samp, count * 2);
+                (*apb).nbytes = (count * 2) as _;
+                (*apb).curbyte = 0;
+                (*apb).flags = 0;
+                rest = &rest[count..];
+            }
+
+            self.enqueue(index)?;
+            if !self.started && self.queued[..self.nbuffers].iter().all(|&queued| queued) {
+                self.start()?;
+            }
+        }
+
+        if !self.started {
+            self.start()?;
+        }
+        Ok(())
+    }
+
+    /// Blocks until all queued samples have been played
+    ///
+    /// # Errors
+    /// Returns the negative errno if receiving a driver message fails
+    pub fn drain(&mut self) -> AudioResult<()> {
+        while self.queued.iter().any(|&queued| queued) {
+            self.receive()?;
+        }
+        Ok(())
+    }
+
+    /// Returns the index of a buffer not queued in the driver, waiting for one
+    /// to be played if needed
+    fn free_buffer(&mut self) -> AudioResult<usize> {
+        loop {
+            if let Some(index) = self.queued[..self.nbuffers].iter().position(|&queued| !queued) {
+                return Ok(index);
+            }
+            self.receive()?;
+        }
+    }
+
+    /// Hands a filled buffer to the driver
+    fn enqueue(&mut self, index: usize) -> AudioResult<()> {
+        // SAFETY: A zeroed audio_buf_desc_s is valid, the buffer was allocated
+        // by the driver
+        let result = unsafe {
+            let mut desc: audio_buf_desc_s = zeroed();
+            desc.u.buffer = self.buffers[index];
+            ioctl(
+                self.fd,
+                AUDIOIOC_ENQUEUEBUFFER as _,
+                &mut desc as *mut audio_buf_desc_s as *mut c_void,
+            )
+        };
+
+        if result < 0 {
+            return Err(errno());
+        }
+        self.queued[index] = true;
+        Ok(())
+    }
+
+    /// Waits for the next driver message and records returned buffers
+    fn receive(&mut self) -> AudioResult<()> {
+        // SAFETY: The message is a plain C structure and the receive is bounded
+        // by its size
+        let mut msg: audio_msg_s = unsafe { zeroed() };
+        let result = unsafe {
+            mq_receive(
+                self.mq,
+                &mut msg as *mut audio_msg_s as *mut _,
+                size_of::<audio_msg_s>(),
+                ptr::null_mut(),
+            )
+        };
+        if result < 0 {
+            return Err(errno());
+        }
+
+        match msg.msg_id as u32 {
+            AUDIO_MSG_DEQUEUE => {
+                // SAFETY: Dequeue messages carry the buffer address
+                let apb = unsafe { msg.u.ptr } as *mut ap_buffer_s;
+                if let Some(index) = self.buffers.iter().position(|&buffer| buffer == apb) {
+                    self.queued[index] = false;
+                }
+            }
+            AUDIO_MSG_COMPLETE => {
+                self.started = false;
+                self.queued = [false; MAX_BUFFERS];
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Issues an ioctl without argument
+    fn command(&self, cmd: IoctlRequest) -> AudioResult<()> {
+        let result = unsafe { ioctl(self.fd, cmd as _, 0) };
+
+        if result < 0 { Err(errno()) } else { Ok(()) }
+    }
+
+    /// Returns the number and size of buffers to allocate
+    #[kconfig(CONFIG_AUDIO_DRIVER_SPECIFIC_BUFFERS = "y")]
+    fn buffer_info(fd: c_int) -> (usize, usize) {
+        // SAFETY: A zeroed ap_buffer_info_s is valid, the driver fills it
+        let mut info: crate::bindings::ap_buffer_info_s = unsafe { zeroed() };
+        let result = unsafe {
+            ioctl(
+                fd,
+                AUDIOIOC_GETBUFFERINFO as _,
+                &mut info as *mut crate::bindings::ap_buffer_info_s as *mut c_void,
+            )
+        };
+
+        if result < 0 {
+            (
+                kconfig_value!(CONFIG_AUDIO_NUM_BUFFERS),
+                kconfig_value!(CONFIG_AUDIO_BUFFER_NUMBYTES),
+            )
+        } else {
+            (info.nbuffers as usize, info.buffer_size as usize)
+        }
+    }
+
+    #[kconfig(CONFIG_AUDIO_DRIVER_SPECIFIC_BUFFERS = "n")]
+    fn buffer_info(_fd: c_int) -> (usize, usize) {
+        (
+            kconfig_value!(CONFIG_AUDIO_NUM_BUFFERS),
+            kconfig_value!(CONFIG_AUDIO_BUFFER_NUMBYTES),
+        )
+    }
+}
+
+/// Returns the size of C's `audio_caps_s`, as stored in its `ac_len` field
+fn size_of_caps() -> u8 {
+    size_of::<crate::bindings::audio_caps_s>() as u8
+}
+
+/// Formats the message queue name into a fixed buffer
+struct NameWriter<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+}
+
+impl Write for NameWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len + s.len();
+        if end > self.buf.len() {
+            return Err(fmt::Error);
+        }
+        self.buf[self.len..end].copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
+impl AsRawFd for AudioOut {
+    /// Returns the file descriptor of the audio device. The descriptor stays
+    /// owned by the AudioOut.
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd
+    }
+}
+
+impl Drop for AudioOut {
+    /// Stops playback and releases the buffers, the message queue and the
+    /// device when the AudioOut goes out of scope
+    fn drop(&mut self) {
+        // Wait for the driver to return the buffers before freeing them, a
+        // stream that was never started would never return them
+        if self.started {
+            let _ = self.stop();
+            let _ = self.drain();
+        }
+
+        unsafe {
+            // Buffers still queued may be in use by the driver, leaking them is
+            // safer than freeing them under its feet
+            for index in (0..self.nbuffers).filter(|&index| !self.queued[index]) {
+                let mut desc: audio_buf_desc_s = zeroed();
+                desc.u.buffer = self.buffers[index];
+                ioctl(
+                    self.fd,
+                    AUDIOIOC_FREEBUFFER as _,
+                    &mut desc as *mut audio_buf_desc_s as *mut c_void,
+                );
+            }
+
+            ioctl(
+                self.fd,
+                AUDIOIOC_UNREGISTERMQ as _,
+                self.mq as c_ulong,
+            );
+            mq_close(self.mq);
+            mq_unlink(self.mq_name.as_ptr() as *const _);
+            libc::close(self.fd);
+        }
+    }
+}
@@ -0,0 +1,587 @@
+//! CAN bus interface
+//!
+//! This module provides Rust bindings for the NuttX CAN character driver.
+//! It allows sending and receiving frames on a bus exposed as `/dev/canN`,
+//! with standard or extended identifiers, and configuring hardware filters.
+//!
+//! The implementation matches the NuttX CAN interface defined in
+//! `nuttx/include/nuttx/can/can.h`. Extended identifiers require
+//! `CONFIG_CAN_EXTID`, without it they are rejected with `-ENOTSUP`.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use core::ffi::CStr;
+//! use nuttx::bus::can::{CanBus, CanFrame, CanId};
+//!
+//! let mut can = CanBus::open(CStr::from_bytes_with_nul(b"/dev/can0\0").unwrap()).unwrap();
+//!
+//! let frame = CanFrame::new(CanId::Standard(0x123), &[0xde, 0xad, 0xbe, 0xef]).unwrap();
+//! can.send(&frame).unwrap();
+//!
+//! let reply = can.recv().unwrap();
+//! let payload = reply.data();
+//! ```
+
+use core::ffi::CStr;
+use core::mem::{size_of, zeroed};
+use core::ptr;
+use core::time::Duration;
+use kconfig::kconfig;
+use libc::{
+    EINVAL, EIO, F_GETFL, F_SETFL, O_NONBLOCK, O_RDWR, POLLIN, c_int, c_void, fcntl, ioctl, open,
+    poll, pollfd, read, write,
+};
+
+use crate::bindings::{
+    CAN_FILTER_DUAL, CAN_FILTER_MASK, CAN_FILTER_RANGE, CAN_MAXDATALEN, can_hdr_s, can_msg_s,
+    canioc_stdfilter_s,
+};
+use crate::errno::errno;
+use crate::fd::{AsRawFd, RawFd};
+use crate::io::ioctl::IoctlRequest;
+
+/// IOCTL command to add a filter for standard identifiers
+///
+/// Matches C's CANIOC_ADD_STDFILTER
+const CANIOC_ADD_STDFILTER: IoctlRequest = crate::bindings::CANIOC_ADD_STDFILTER as IoctlRequest;
+
+/// IOCTL command to add a filter for extended identifiers
+///
+/// Matches C's CANIOC_ADD_EXTFILTER
+#[kconfig(CONFIG_CAN_EXTID = "y")]
+const CANIOC_ADD_EXTFILTER: IoctlRequest = crate::bindings::CANIOC_ADD_EXTFILTER as IoctlRequest;
+
+/// IOCTL command to remove a filter for standard identifiers
+///
+/// Matches C's CANIOC_DEL_STDFILTER
+const CANIOC_DEL_STDFILTER: IoctlRequest = crate::bindings::CANIOC_DEL_STDFILTER as IoctlRequest;
+
+/// IOCTL command to remove a filter for extended identifiers
+///
+/// Matches C's CANIOC_DEL_EXTFILTER
+#[kconfig(CONFIG_CAN_EXTID = "y")]
+const CANIOC_DEL_EXTFILTER: IoctlRequest = crate::bindings::CANIOC_DEL_EXTFILTER as IoctlRequest;
+
+/// Largest standard (11-bit) identifier
+pub const MAX_STANDARD_ID: u16 = 0x7ff;
+
+/// Largest extended (29-bit) identifier
+pub const MAX_EXTENDED_ID: u32 = 0x1fff_ffff;
+
+/// Maximum payload of a frame, 8 bytes or 64 bytes with `CONFIG_CAN_FD`
+pub const MAX_DATA_LEN: usize = CAN_MAXDATALEN as usize;
+
+/// Result type for CAN operations, errors hold the negative errno
+pub type CanResult<T> = Result<T, i32>;
+
+/// Identifier of a CAN frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CanId {
+    /// Standard 11-bit identifier
+    Standard(u16),
+    /// Extended 29-bit identifier
+    Extended(u32),
+}
+
+impl CanId {
+    /// Checks if the identifier fits in its format
+    pub fn is_valid(&self) -> bool {
+        match *self {
+            CanId::Standard(id) => id <= MAX_STANDARD_ID,
+            CanId::Extended(id) => id <= MAX_EXTENDED_ID,
+        }
+    }
+}
+
+/// Type of a hardware acceptance filter
+///
+/// Mirrors C's `CAN_FILTER_*` definitions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterKind {
+    /// Accepts identifiers matching `id1` in the bits set in `id2`
+    Mask,
+    /// Accepts the identifiers `id1` and `id2`
+    Dual,
+    /// Accepts the identifiers from `id1` to `id2`
+    Range,
+}
+
+impl FilterKind {
+    /// Returns the C filter type
+    fn raw(self) -> u8 {
+        (match self {
+            FilterKind::Mask => CAN_FILTER_MASK,
+            FilterKind::Dual => CAN_FILTER_DUAL,
+            FilterKind::Range => CAN_FILTER_RANGE,
+        }) as u8
+    }
+}
+
+/// A CAN frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CanFrame {
+    id: CanId,
+    remote: bool,
+    len: u8,
+    data: [u8; MAX_DATA_LEN],
+}
+
+impl CanFrame {
+    /// Creates a data frame
+    ///
+    /// # Arguments
+    /// * `id` - The identifier of the frame
+    /// * `data` - The payload. Up to 8 bytes, or with `CONFIG_CAN_FD` one of the
+    ///   lengths 12, 16, 20, 24, 32, 48 or 64
+    ///
+    /// # Returns
+    /// None if the identifier is out of range or the payload length cannot be
+    /// encoded
+    pub fn new(id: CanId, data: &[u8]) -> Option<Self> {
+        if !id.is_valid() || data.len() > MAX_DATA_LEN || len_to_dlc(data.len()).is_none() {
+            return None;
+        }
+
+        let mut frame = CanFrame {
+            id,
+            remote: false,
+            len: data.len() as u8,
+            data: [0; MAX_DATA_LEN],
+        };
+        frame.data[..data.len()].copy_from_slice(data);
+        Some(frame)
+    }
+
+    /// Creates a remote transmission request
+    ///
+    /// # Arguments
+    /// * `id` - The identifier of the requested frame
+    /// * `len` - The payload length of the requested frame, up to 8 bytes
+    ///
+    /// # Returns
+    /// None if the identifier or the length is out of range
+    pub fn remote(id: CanId, len: u8) -> Option<Self> {
+        if !id.is_valid() || len > 8 {
+            return None;
+        }
+
+        Some(CanFrame {
+            id,
+            remote: true,
+            len,
+            data: [0; MAX_DATA_LEN],
+        })
+    }
+
+    /// Returns the identifier of the frame
+    pub fn id(&self) -> CanId {
+        self.id
+    }
+
+    /// Checks if the frame is a remote transmission request
+    pub fn is_remote(&self) -> bool {
+        self.remote
+    }
+
+    /// Returns the payload length, the requested length for remote frames
+    pub fn len(&self) -> usize {
+        self.len as usize
+    }
+
+    /// Checks if the frame has no payload
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the payload, empty for remote frames
+    pub fn data(&self) -> &[u8] {
+        if self.remote {
+            &[]
+        } else {
+            &self.data[..self.len as usize]
+        }
+    }
+
+    /// Stores the identifier in a message header
+    #[kconfig(CONFIG_CAN_EXTID = "y")]
+    fn encode_id(&self, hdr: &mut can_hdr_s) -> CanResult<()> {
+        match self.id {
+            CanId::Standard(id) => {
+                hdr.ch_id = id.into();
+                hdr.set_ch_extid(0);
+            }
+            CanId::Extended(id) => {
+                hdr.ch_id = id;
+                hdr.set_ch_extid(1);
+            }
+        }
+        Ok(())
+    }
+
+    #[kconfig(CONFIG_CAN_EXTID = "n")]
+    fn encode_id(&self, hdr: &mut can_hdr_s) -> CanResult<()> {
+        match self.id {
+            CanId::Standard(id) => {
+                hdr.ch_id = id;
+                Ok(())
+            }
+            CanId::Extended(_) => Err(-libc::ENOTSUP),
+        }
+    }
+
+    /// Reads the identifier from a message header
+    #[kconfig(CONFIG_CAN_EXTID = "y")]
+    fn decode_id(hdr: &can_hdr_s) -> CanId {
+        if hdr.ch_extid() != 0 {
+            CanId::Extended(hdr.ch_id)
+        } else {
+            CanId::Standard(hdr.ch_id as u16)
+        }
+    }
+
+    #[kconfig(CONFIG_CAN_EXTID = "n")]
+    fn decode_id(hdr: &can_hdr_s) -> CanId {
+        CanId::Standard(hdr.ch_id)
+    }
+}
+
+/// Represents an open CAN device
+///
+/// Received messages are buffered, as a single read may return several of
+/// them.
+#[derive(Debug)]
+pub struct CanBus {
+    fd: c_int,
+    raw: [u8; size_of::<can_msg_s>()],
+    start: usize,
+    end: usize,
+}
+
+impl CanBus {
+    /// Opens a CAN device at the specified path
+    ///
+    /// # Arguments
+    /// * `path` - Path to the CAN device as a C string (e.g. "/dev/can0")
+    ///
+    /// # Errors
+    /// Returns the negative errno if the device could not be opened
+    pub fn open(path: &CStr) -> CanResult<Self> {
+        let fd = unsafe { open(path.as_ptr(), O_RDWR) };
+        if fd < 0 {
+            return Err(errno());
+        }
+        Ok(CanBus {
+            fd,
+            raw: [0; size_of::<can_msg_s>()],
+            start: 0,
+            end: 0,
+        })
+    }
+
+    /// Queues a frame for transmission
+    ///
+    /// # Errors
+    /// - `-ENOTSUP` for extended identifiers without `CONFIG_CAN_EXTID`
+    /// - The negative errno if the write fails, e.g. `-EAGAIN` in non-blocking
+    ///   mode when the transmit queue is full
+    pub fn send(&self, frame: &CanFrame) -> CanResult<()> {
+        // SAFETY: A zeroed can_msg_s is valid
+        let mut msg: can_msg_s = unsafe { zeroed() };
+        frame.encode_id(&mut msg.cm_hdr)?;
+        msg.cm_hdr.set_ch_rtr(frame.remote as u8);
+        // The length was validated when the frame was created
+        msg.cm_hdr.set_ch_dlc(len_to_dlc(frame.len()).unwrap_or(0));
+        msg.cm_data[..frame.len()].copy_from_slice(&frame.data[..frame.len()]);
+
+        // The driver derives the message length from the DLC, also for remote frames
+        let len = size_of::<can_hdr_s>() + frame.len();
+        let ret = unsafe { write(self.fd, &msg as *const can_msg_s as *const c_void, len) };
+
+        if ret < 0 {
+            Err(errno())
+        } else if ret as usize != len {
+            Err(-EIO)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Receives the next frame
+    ///
+    /// # Errors
+    /// - The negative errno if the read fails, e.g. `-EAGAIN` in non-blocking
+    ///   mode when no frame is available
+    /// - `-EIO` if the driver returned a truncated message
+    pub fn recv(&mut self) -> CanResult<CanFrame> {
+        if self.start == self.end {
+            let ret = unsafe {
+                read(
+                    self.fd,
+                    self.raw.as_mut_ptr() as *mut c_void,
+                    self.raw.len(),
+                )
+            };
+            if ret < 0 {
+                return Err(errno());
+            }
+            self.start = 0;
+            self.end = ret as usize;
+        }
+
+        let hdr_len = size_of::<can_hdr_s>();
+        let available = &self.raw[self.start..self.end];
+        if available.len() < hdr_len {
+            self.start = self.end;
+            return Err(-EIO);
+        }
+
+        // SAFETY: The buffer holds at least a complete header, which is a plain
+        // C structure read without alignment requirements
+        let hdr: can_hdr_s = unsafe { ptr::read_unaligned(available.as_ptr() as *const can_hdr_s) };
+        let len = dlc_to_len(hdr.ch_dlc()).min(MAX_DATA_LEN);
+        if available.len() < hdr_len + len {
+            self.start = self.end;
+            return Err(-EIO);
+        }
+
+        let mut frame = CanFrame {
+            id: CanFrame::decode_id(&hdr),
+            remote: hdr.ch_rtr() != 0,
+            len: len as u8,
+            data: [0; MAX_DATA_LEN],
+        };
+        frame.data[..len].copy_from_slice(&available[hdr_len..hdr_len + len]);
+
+        self.start += hdr_len + len;
+        Ok(frame)
+    }
+
+    /// Switches the device between blocking and non-blocking mode
+    ///
+    /// # Arguments
+    /// * `nonblock` - true to return `-EAGAIN` from [`CanBus::send`] and
+    ///   [`CanBus::recv`] instead of waiting
+    ///
+    /// # Errors
+    /// Returns the negative errno if the file flags could not be changed
+    pub fn set_nonblocking(&self, nonblock: bool) -> CanResult<()> {
+        let flags = unsafe { fcntl(self.fd, F_GETFL) };
+        if flags < 0 {
+            return Err(errno());
+        }
+
+        let flags = if nonblock {
+            flags | O_NONBLOCK
+        } else {
+            flags & !O_NONBLOCK
+        };
+
+        if unsafe { fcntl(self.fd, F_SETFL, flags) } < 0 {
+            return Err(errno());
+        }
+        Ok(())
+    }
+
+    /// Waits until a frame can be received
+    ///
+    /// # Arguments
+    /// * `timeout` - Maximum time to wait, or None to wait indefinitely. Timeouts
+    ///   are rounded up to whole milliseconds.
+    ///
+    /// # Returns
+    /// - Ok(true) if a frame is ready to be received
+    /// - Ok(false) if the timeout expired first
+    ///
+    /// # Errors
+    /// Returns the negative errno if polling fails
+    pub fn wait_readable(&self, timeout: Option<Duration>) -> CanResult<bool> {
+        if self.start != self.end {
+            return Ok(true);
+        }
+
+        let timeout_ms = match timeout {
+            None => -1,
+            Some(timeout) => {
+                let ms = timeout.as_nanos().div_ceil(1_000_000);
+                ms.min(c_int::MAX as u128) as c_int
+            }
+        };
+
+        let mut fd = pollfd {
+            fd: self.fd,
+            events: POLLIN,
+            revents: 0,
+        };
+
+        let ret = unsafe { poll(&mut fd, 1, timeout_ms) };
+        if ret < 0 {
+            return Err(errno());
+        }
+
+        Ok(ret > 0)
+    }
+
+    /// Adds a hardware filter for standard identifiers
+    ///
+    /// # Arguments
+    /// * `kind` - How the identifiers are matched
+    /// * `id1`, `id2` - The identifiers, see [`FilterKind`]
+    ///
+    /// # Returns
+    /// The index of the filter, for [`CanBus::remove_std_filter`]
+    ///
+    /// # Errors
+    /// - `-EINVAL` if an identifier is out of range
+    /// - The negative errno if the ioctl fails, e.g. `-ENOTTY` if the driver
+    ///   has no hardware filters
+    pub fn add_std_filter(&self, kind: FilterKind, id1: u16, id2: u16) -> CanResult<usize> {
+        if id1 > MAX_STANDARD_ID || id2 > MAX_STANDARD_ID {
+            return Err(-EINVAL);
+        }
+
+        // SAFETY: A zeroed canioc_stdfilter_s is valid
+        let mut filter: canioc_stdfilter_s = unsafe { zeroed() };
+        filter.sf_id1 = id1;
+        filter.sf_id2 = id2;
+        filter.set_sf_type(kind.raw());
+
+        let result = unsafe {
+            ioctl(
+                self.fd,
+                CANIOC_ADD_STDFILTER as _,
+                &mut filter as *mut canioc_stdfilter_s as *mut c_void,
+            )
+        };
+
+        if result < 0 {
+            Err(errno())
+        } else {
+            Ok(result as usize)
+        }
+    }
+
+    /// Removes a hardware filter for standard identifiers
+    ///
+    /// # Arguments
+    /// * `index` - The index returned by [`CanBus::add_std_filter`]
+    ///
+    /// # Errors
+    /// Returns the negative errno if the ioctl fails
+    pub fn remove_std_filter(&self, index: usize) -> CanResult<()> {
+        // SAFETY: CANIOC_DEL_STDFILTER takes the filter index itself rather than
+        // a pointer
+        let result = unsafe { ioctl(self.fd, CANIOC_DEL_STDFILTER as _, index as libc::c_ulong) };
+
+        if result < 0 { Err(errno()) } else { Ok(()) }
+    }
+
+    /// Adds a hardware filter for extended identifiers
+    ///
+    /// # Arguments
+    /// * `kind` - How the identifiers are matched
+    /// * `id1`, `id2` - The identifiers, see [`FilterKind`]
+    ///
+    /// # Returns
+    /// The index of the filter, for [`CanBus::remove_ext_filter`]
+    ///
+    /// # Errors
+    /// - `-EINVAL` if an identifier is out of range
+    /// - The negative errno if the ioctl fails
+    #[kconfig(CONFIG_CAN_EXTID = "y")]
+    pub fn add_ext_filter(&self, kind: FilterKind, id1: u32, id2: u32) -> CanResult<usize> {
+        if id1 > MAX_EXTENDED_ID || id2 > MAX_EXTENDED_ID {
+            return Err(-EINVAL);
+        }
+
+        // SAFETY: A zeroed canioc_extfilter_s is valid
+        let mut filter: crate::bindings::canioc_extfilter_s = unsafe { zeroed() };
+        filter.xf_id1 = id1;
+        filter.xf_id2 = id2;
+        filter.set_xf_type(kind.raw());
+
+        let result = unsafe {
+            ioctl(
+                self.fd,
+                CANIOC_ADD_EXTFILTER as _,
+                &mut filter as *mut crate::bindings::canioc_extfilter_s as *mut c_void,
+            )
+        };
+
+        if result < 0 {
+            Err(errno())
+        } else {
+            Ok(result as usize)
+        }
+    }
+
+    #[kconfig(CONFIG_CAN_EXTID = "n")]
+    pub fn add_ext_filter(&self, _kind: FilterKind, _id1: u32, _id2: u32) -> CanResult<usize> {
+        Err(-libc::ENOTSUP)
+    }
+
+    /// Removes a hardware filter for extended identifiers
+    ///
+    /// # Arguments
+    /// * `index` - The index returned by [`CanBus::add_ext_filter`]
+    ///
+    /// # Errors
+    /// Returns the negative errno if the ioctl fails
+    #[kconfig(CONFIG_CAN_EXTID = "y")]
+    pub fn remove_ext_filter(&self, index: usize) -> CanResult<()> {
+        // SAFETY: CANIOC_DEL_EXTFILTER takes the filter index itself rather than
+        // a pointer
+        let result = unsafe { ioctl(self.fd, CANIOC_DEL_EXTFILTER as _, index as libc::c_ulong) };
+
+        if result < 0 { Err(errno()) } else { Ok(()) }
+    }
+
+    #[kconfig(CONFIG_CAN_EXTID = "n")]
+    pub fn remove_ext_filter(&self, _index: usize) -> CanResult<()> {
+        Err(-libc::ENOTSUP)
+    }
+}
+
+/// Converts a data length code into a payload length
+fn dlc_to_len(dlc: u8) -> usize {
+    match dlc {
+        0..=8 => dlc as usize,
+        9 => 12,
+        10 => 16,
+        11 => 20,
+        12 => 24,
+        13 => 32,
+        14 => 48,
+        _ => 64,
+    }
+}
+
+/// Converts a payload length into a data length code, if it can be encoded
+fn len_to_dlc(len: usize) -> Option<u8> {
+    match len {
+        0..=8 => Some(len as u8),
+        12 => Some(9),
+        16 => Some(10),
+        20 => Some(11),
+        24 => Some(12),
+        32 => Some(13),
+        48 => Some(14),
+        64 => Some(15),
+        _ => None,
+    }
+}
+
+impl AsRawFd for CanBus {
+    /// Returns the file descriptor of the CAN device, e.g. for use in an external
+    /// poll loop. The descriptor stays owned by the CanBus.
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd
+    }
+}
+
+impl Drop for CanBus {
+    /// Closes the CAN device when the CanBus goes out of scope
+    fn drop(&mut self) {
+        unsafe { libc::close(self.fd) };
+    }
+}
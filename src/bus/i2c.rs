@@ -0,0 +1,190 @@
+//! I2C character driver interface
+//!
+//! This module provides Rust bindings for the NuttX I2C character driver,
+//! which exposes an I2C bus as `/dev/i2cN`. It allows performing write, read
+//! and combined write-then-read transactions with devices on the bus.
+//!
+//! The implementation matches the NuttX I2C interface defined in
+//! `nuttx/include/nuttx/i2c/i2c_master.h`.
+//!
+//! # Errors
+//!
+//! A transaction is submitted to the driver as a whole, which reports a single
+//! negative errno for it. The most common ones are:
+//! - `-ENXIO`: the device did not acknowledge its address
+//! - `-ETIMEDOUT`: the transaction did not complete in time
+//! - `-EINVAL`: a message was rejected by the driver, e.g. an empty buffer
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use core::ffi::CStr;
+//! use nuttx::bus::i2c::I2cMaster;
+//!
+//! let i2c = I2cMaster::open(CStr::from_bytes_with_nul(b"/dev/i2c0\0").unwrap()).unwrap();
+//! let mut id = [0u8; 1];
+//! i2c.write_read(0x76, &[0xd0], &mut id).unwrap();
+//! ```
+
+use core::ffi::CStr;
+use libc::{O_RDONLY, c_int, c_void, ioctl, open};
+
+use crate::bindings::{I2C_M_READ, i2c_msg_s, i2c_transfer_s};
+use crate::errno::errno;
+use crate::fd::{AsRawFd, RawFd};
+use crate::io::ioctl::IoctlRequest;
+
+/// IOCTL command to perform a sequence of I2C messages
+///
+/// Matches C's I2CIOC_TRANSFER
+const I2CIOC_TRANSFER: IoctlRequest = crate::bindings::I2CIOC_TRANSFER as IoctlRequest;
+
+/// Bus frequency used until changed with [`I2cMaster::set_frequency`]
+pub const DEFAULT_FREQUENCY: u32 = 100_000;
+
+/// Result type for I2C operations, errors hold the negative errno
+pub type I2cResult<T> = Result<T, i32>;
+
+/// Represents an open I2C bus
+///
+/// The underlying device is closed when the I2cMaster is dropped.
+#[derive(Debug)]
+pub struct I2cMaster {
+    fd: c_int,
+    frequency: u32,
+}
+
+impl I2cMaster {
+    /// Opens an I2C bus at the specified path
+    ///
+    /// Transfers use [`DEFAULT_FREQUENCY`] until changed.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the I2C device as a C string (e.g. "/dev/i2c0")
+    ///
+    /// # Errors
+    /// Returns the negative errno if the device could not be opened
+    pub fn open(path: &CStr) -> I2cResult<Self> {
+        let fd = unsafe { open(path.as_ptr(), O_RDONLY) };
+        if fd < 0 {
+            return Err(errno());
+        }
+
+        Ok(I2cMaster {
+            fd,
+            frequency: DEFAULT_FREQUENCY,
+        })
+    }
+
+    /// Sets the bus frequency used by the following transfers
+    ///
+    /// # Arguments
+    /// * `frequency` - The bus frequency in Hz, e.g. 400000 for fast mode
+    pub fn set_frequency(&mut self, frequency: u32) {
+        self.frequency = frequency;
+    }
+
+    /// Returns the bus frequency used by transfers
+    pub fn frequency(&self) -> u32 {
+        self.frequency
+    }
+
+    /// Writes bytes to a device
+    ///
+    /// # Arguments
+    /// * `addr` - The 7-bit address of the device
+    /// * `data` - The bytes to write
+    ///
+    /// # Errors
+    /// Returns the negative errno if the transfer fails
+    pub fn write(&self, addr: u16, data: &[u8]) -> I2cResult<()> {
+        let mut msgs = [self.write_msg(addr, data)];
+        self.transfer(&mut msgs)
+    }
+
+    /// Reads bytes from a device
+    ///
+    /// # Arguments
+    /// * `addr` - The 7-bit address of the device
+    /// * `buf` - Buffer receiving the bytes, its length sets the number of
+    ///   bytes to read
+    ///
+    /// # Errors
+    /// Returns the negative errno if the transfer fails
+    pub fn read(&self, addr: u16, buf: &mut [u8]) -> I2cResult<()> {
+        let mut msgs = [self.read_msg(addr, buf)];
+        self.transfer(&mut msgs)
+    }
+
+    /// Writes bytes to a device, then reads its response
+    ///
+    /// Both messages are sent as a single transaction with a repeated start,
+    /// as is commonly required to read a device register.
+    ///
+    /// # Arguments
+    /// * `addr` - The 7-bit address of the device
+    /// * `data` - The bytes to write, e.g. a register address
+    /// * `buf` - Buffer receiving the response
+    ///
+    /// # Errors
+    /// Returns the negative errno if the transfer fails
+    pub fn write_read(&self, addr: u16, data: &[u8], buf: &mut [u8]) -> I2cResult<()> {
+        let mut msgs = [self.write_msg(addr, data), self.read_msg(addr, buf)];
+        self.transfer(&mut msgs)
+    }
+
+    fn write_msg(&self, addr: u16, data: &[u8]) -> i2c_msg_s {
+        i2c_msg_s {
+            frequency: self.frequency,
+            addr,
+            flags: 0,
+            // The driver only reads from the buffer of a write message
+            buffer: data.as_ptr() as *mut u8,
+            length: data.len() as _,
+        }
+    }
+
+    fn read_msg(&self, addr: u16, buf: &mut [u8]) -> i2c_msg_s {
+        i2c_msg_s {
+            frequency: self.frequency,
+            addr,
+            flags: I2C_M_READ as _,
+            buffer: buf.as_mut_ptr(),
+            length: buf.len() as _,
+        }
+    }
+
+    /// Submits the messages to the driver as a single transaction
+    fn transfer(&self, msgs: &mut [i2c_msg_s]) -> I2cResult<()> {
+        let mut xfer = i2c_transfer_s {
+            msgv: msgs.as_mut_ptr(),
+            msgc: msgs.len(),
+        };
+
+        // SAFETY: The messages and their buffers outlive the ioctl
+        let result = unsafe {
+            ioctl(
+                self.fd,
+                I2CIOC_TRANSFER as _,
+                &mut xfer as *mut i2c_transfer_s as *mut c_void,
+            )
+        };
+
+        if result < 0 { Err(errno()) } else { Ok(()) }
+    }
+}
+
+impl AsRawFd for I2cMaster {
+    /// Returns the file descriptor of the I2C device. The descriptor stays
+    /// owned by the I2cMaster.
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd
+    }
+}
+
+impl Drop for I2cMaster {
+    /// Closes the I2C device when the I2cMaster goes out of scope
+    fn drop(&mut self) {
+        unsafe { libc::close(self.fd) };
+    }
+}
@@ -0,0 +1,10 @@
+use kconfig::kconfig_if;
+
+pub mod i2c;
+pub mod spi;
+
+// Attributes cannot be applied to file modules, so the module is gated with
+// kconfig_if! rather than #[kconfig]
+kconfig_if!(CONFIG_CAN = "y" {
+    pub mod can;
+});
@@ -0,0 +1,205 @@
+//! SPI character driver interface
+//!
+//! This module provides Rust bindings for the NuttX SPI character driver,
+//! which exposes an SPI bus as `/dev/spiN`. It allows performing full-duplex,
+//! write-only and read-only transfers with a device on the bus.
+//!
+//! The implementation matches the NuttX SPI interface defined in
+//! `nuttx/include/nuttx/spi/spi_transfer.h`.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use core::ffi::CStr;
+//! use nuttx::bus::spi::SpiDevice;
+//!
+//! let mut spi = SpiDevice::open(CStr::from_bytes_with_nul(b"/dev/spi0\0").unwrap()).unwrap();
+//! spi.set_frequency(1_000_000);
+//!
+//! let mut rx = [0u8; 4];
+//! spi.transfer(&[0x9f, 0, 0, 0], &mut rx).unwrap();
+//! ```
+
+use core::ffi::CStr;
+use core::mem::zeroed;
+use core::ptr;
+use libc::{EINVAL, O_RDONLY, c_int, c_void, ioctl, open};
+
+use crate::bindings::{spi_sequence_s, spi_trans_s};
+use crate::errno::errno;
+use crate::fd::{AsRawFd, RawFd};
+use crate::io::ioctl::IoctlRequest;
+
+/// IOCTL command to perform a sequence of SPI transfers
+///
+/// Matches C's SPIIOC_TRANSFER
+const SPIIOC_TRANSFER: IoctlRequest = crate::bindings::SPIIOC_TRANSFER as IoctlRequest;
+
+/// Bus frequency used until changed with [`SpiDevice::set_frequency`]
+pub const DEFAULT_FREQUENCY: u32 = 1_000_000;
+
+/// Result type for SPI operations, errors hold the negative errno
+pub type SpiResult<T> = Result<T, i32>;
+
+/// Represents a device on an open SPI bus
+///
+/// Transfers use SPI mode 0 with 8 bits per word at [`DEFAULT_FREQUENCY`]
+/// until changed. The underlying device is closed when the SpiDevice is
+/// dropped.
+#[derive(Debug)]
+pub struct SpiDevice {
+    fd: c_int,
+    dev: u32,
+    mode: u8,
+    nbits: u8,
+    frequency: u32,
+}
+
+impl SpiDevice {
+    /// Opens an SPI bus at the specified path
+    ///
+    /// # Arguments
+    /// * `path` - Path to the SPI device as a C string (e.g. "/dev/spi0")
+    ///
+    /// # Errors
+    /// Returns the negative errno if the device could not be opened
+    pub fn open(path: &CStr) -> SpiResult<Self> {
+        let fd = unsafe { open(path.as_ptr(), O_RDONLY) };
+        if fd < 0 {
+            return Err(errno());
+        }
+
+        Ok(SpiDevice {
+            fd,
+            dev: 0,
+            mode: 0,
+            nbits: 8,
+            frequency: DEFAULT_FREQUENCY,
+        })
+    }
+
+    /// Selects the device on the bus
+    ///
+    /// # Arguments
+    /// * `dev` - The device ID passed to the board's chip select logic, as
+    ///   built by C's `SPIDEV_ID()`
+    pub fn set_device(&mut self, dev: u32) {
+        self.dev = dev;
+    }
+
+    /// Sets the SPI mode
+    ///
+    /// # Arguments
+    /// * `mode` - The mode, from 0 to 3, combining clock polarity and phase
+    ///
+    /// # Errors
+    /// Returns `-EINVAL` if the mode is out of range
+    pub fn set_mode(&mut self, mode: u8) -> SpiResult<()> {
+        if mode > 3 {
+            return Err(-EINVAL);
+        }
+        self.mode = mode;
+        Ok(())
+    }
+
+    /// Sets the number of bits per word
+    ///
+    /// Words wider than 8 bits occupy two bytes in the transfer buffers.
+    ///
+    /// # Arguments
+    /// * `nbits` - The number of bits per word, from 1 to 16
+    ///
+    /// # Errors
+    /// Returns `-EINVAL` if the number of bits is out of range
+    pub fn set_bits_per_word(&mut self, nbits: u8) -> SpiResult<()> {
+        if !(1..=16).contains(&nbits) {
+            return Err(-EINVAL);
+        }
+        self.nbits = nbits;
+        Ok(())
+    }
+
+    /// Sets the bus frequency used by the following transfers
+    ///
+    /// # Arguments
+    /// * `frequency` - The bus frequency in Hz
+    pub fn set_frequency(&mut self, frequency: u32) {
+        self.frequency = frequency;
+    }
+
+    /// Exchanges data with the device
+    ///
+    /// Either slice may be empty for a write-only or read-only transfer, in
+    /// which case the length of the other one sets the size of the transfer.
+    ///
+    /// # Arguments
+    /// * `tx` - The bytes to send
+    /// * `rx` - Buffer receiving the bytes clocked in during the transfer
+    ///
+    /// # Errors
+    /// - `-EINVAL` if both slices are non-empty but differ in length, or the
+    ///   length is not a whole number of words
+    /// - The negative errno if the transfer fails
+    pub fn transfer(&self, tx: &[u8], rx: &mut [u8]) -> SpiResult<()> {
+        let len = match (tx.len(), rx.len()) {
+            (0, len) | (len, 0) => len,
+            (tx_len, rx_len) if tx_len == rx_len => tx_len,
+            _ => return Err(-EINVAL),
+        };
+
+        let word_size = if self.nbits > 8 { 2 } else { 1 };
+        if len % word_size != 0 {
+            return Err(-EINVAL);
+        }
+
+        // SAFETY: A zeroed spi_trans_s is valid
+        let mut trans: spi_trans_s = unsafe { zeroed() };
+        trans.deselect = true;
+        trans.nwords = (len / word_size) as _;
+        trans.txbuffer = if tx.is_empty() {
+            ptr::null()
+        } else {
+            tx.as_ptr() as *const c_void
+        };
+        trans.rxbuffer = if rx.is_empty() {
+            ptr::null_mut()
+        } else {
+            rx.as_mut_ptr() as *mut c_void
+        };
+
+        // SAFETY: A zeroed spi_sequence_s is valid
+        let mut seq: spi_sequence_s = unsafe { zeroed() };
+        seq.dev = self.dev;
+        seq.mode = self.mode;
+        seq.nbits = self.nbits;
+        seq.ntrans = 1;
+        seq.frequency = self.frequency;
+        seq.trans = &mut trans;
+
+        // SAFETY: The sequence, transfer and buffers outlive the ioctl
+        let result = unsafe {
+            ioctl(
+                self.fd,
+                SPIIOC_TRANSFER as _,
+                &mut seq as *mut spi_sequence_s as *mut c_void,
+            )
+        };
+
+        if result < 0 { Err(errno()) } else { Ok(()) }
+    }
+}
+
+impl AsRawFd for SpiDevice {
+    /// Returns the file descriptor of the SPI device. The descriptor stays
+    /// owned by the SpiDevice.
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd
+    }
+}
+
+impl Drop for SpiDevice {
+    /// Closes the SPI device when the SpiDevice goes out of scope
+    fn drop(&mut self) {
+        unsafe { libc::close(self.fd) };
+    }
+}
@@ -0,0 +1,287 @@
+//! PWM character driver interface
+//!
+//! This module provides Rust bindings for the NuttX PWM driver interface.
+//! It allows configuring the frequency and duty cycle of a PWM output and
+//! starting or stopping the pulse train.
+//!
+//! Duty cycles use NuttX's `ub16_t` fixed-point format, where [`DUTY_FULL`]
+//! (0x10000) is a 100% duty cycle.
+//!
+//! When NuttX is built with `CONFIG_PWM_MULTICHAN`, several channels of the
+//! same timer are configured together through a [`PwmConfig`].
+//!
+//! The implementation matches the NuttX PWM interface defined in
+//! `nuttx/include/nuttx/timers/pwm.h`.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use core::ffi::CStr;
+//! use nuttx::drivers::pwm::{DUTY_FULL, Pwm};
+//!
+//! let mut pwm = Pwm::open(CStr::from_bytes_with_nul(b"/dev/pwm0\0").unwrap()).unwrap();
+//! pwm.set_frequency(1000).unwrap();
+//! pwm.set_duty(DUTY_FULL / 2).unwrap();
+//! pwm.start().unwrap();
+//! ```
+
+use core::ffi::CStr;
+use core::mem::zeroed;
+use kconfig::kconfig;
+use libc::{O_RDONLY, c_int, c_void, ioctl, open};
+
+use crate::bindings::pwm_info_s;
+use crate::errno::errno;
+use crate::fd::{AsRawFd, RawFd};
+use crate::io::ioctl::IoctlRequest;
+
+/// IOCTL command to set the frequency and duty cycle
+///
+/// Matches C's PWMIOC_SETCHARACTERISTICS
+const PWMIOC_SETCHARACTERISTICS: IoctlRequest = crate::bindings::PWMIOC_SETCHARACTERISTICS as IoctlRequest;
+
+/// IOCTL command to get the frequency and duty cycle
+///
+/// Matches C's PWMIOC_GETCHARACTERISTICS
+const PWMIOC_GETCHARACTERISTICS: IoctlRequest = crate::bindings::PWMIOC_GETCHARACTERISTICS as IoctlRequest;
+
+/// IOCTL command to start the pulse train
+///
+/// Matches C's PWMIOC_START
+const PWMIOC_START: IoctlRequest = crate::bindings::PWMIOC_START as IoctlRequest;
+
+/// IOCTL command to stop the pulse train
+///
+/// Matches C's PWMIOC_STOP
+const PWMIOC_STOP: IoctlRequest = crate::bindings::PWMIOC_STOP as IoctlRequest;
+
+/// A 100% duty cycle in `ub16_t` format
+pub const DUTY_FULL: u32 = 0x10000;
+
+/// Result type for PWM operations, errors hold the negative errno
+pub type PwmResult<T> = Result<T, i32>;
+
+/// Characteristics of several channels of a multi-channel PWM device
+///
+/// Channels are numbered from 1, as in NuttX. At most `CONFIG_PWM_NCHANNELS`
+/// channels can be configured.
+///
+/// # Examples
+///
+/// ```no_run
+/// use nuttx::drivers::pwm::{DUTY_FULL, PwmConfig};
+///
+/// let config = PwmConfig::new(50)
+///     .channel(1, DUTY_FULL / 20)
+///     .channel(2, DUTY_FULL / 10);
+/// ```
+#[kconfig(CONFIG_PWM_MULTICHAN = "y")]
+#[derive(Debug, Clone, Copy)]
+pub struct PwmConfig {
+    info: pwm_info_s,
+    nchannels: usize,
+    overflow: bool,
+}
+
+#[kconfig(CONFIG_PWM_MULTICHAN = "y")]
+impl PwmConfig {
+    /// Creates a configuration without channels
+    ///
+    /// # Arguments
+    /// * `frequency` - The frequency of the pulse train in Hz
+    pub fn new(frequency: u32) -> Self {
+        // SAFETY: A zeroed pwm_info_s is valid
+        let mut info: pwm_info_s = unsafe { zeroed() };
+        info.frequency = frequency;
+
+        // A channel number of -1 ends the list of configured channels
+        for chan in info.channels.iter_mut() {
+            chan.channel = -1;
+        }
+
+        PwmConfig {
+            info,
+            nchannels: 0,
+            overflow: false,
+        }
+    }
+
+    /// Adds a channel to the configuration
+    ///
+    /// Adding more than `CONFIG_PWM_NCHANNELS` channels makes
+    /// [`Pwm::configure`] fail with `-EINVAL`.
+    ///
+    /// # Arguments
+    /// * `channel` - The channel number, starting from 1
+    /// * `duty` - The duty cycle in `ub16_t` format
+    pub fn channel(mut self, channel: u8, duty: u32) -> Self {
+        match self.info.channels.get_mut(self.nchannels) {
+            Some(chan) => {
+                chan.channel = channel as _;
+                chan.duty = duty as _;
+                self.nchannels += 1;
+            }
+            None => self.overflow = true,
+        }
+        self
+    }
+}
+
+/// Represents an open PWM device
+///
+/// The device keeps its characteristics between calls, so that changing the
+/// frequency preserves the duty cycle and vice versa. The underlying device is
+/// closed when the Pwm is dropped, which does not stop the pulse train.
+#[derive(Debug)]
+pub struct Pwm {
+    fd: c_int,
+    info: pwm_info_s,
+}
+
+impl Pwm {
+    /// Opens a PWM device at the specified path
+    ///
+    /// The current characteristics of the device are read, so they are kept
+    /// until changed.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the PWM device as a C string (e.g. "/dev/pwm0")
+    ///
+    /// # Errors
+    /// Returns the negative errno if the device could not be opened or its
+    /// characteristics could not be read
+    pub fn open(path: &CStr) -> PwmResult<Self> {
+        let fd = unsafe { open(path.as_ptr(), O_RDONLY) };
+        if fd < 0 {
+            return Err(errno());
+        }
+
+        // SAFETY: A zeroed pwm_info_s is valid
+        let mut pwm = Pwm {
+            fd,
+            info: unsafe { zeroed() },
+        };
+
+        // SAFETY: PWMIOC_GETCHARACTERISTICS fills the pwm_info_s structure
+        let result = unsafe {
+            ioctl(
+                pwm.fd,
+                PWMIOC_GETCHARACTERISTICS as _,
+                &mut pwm.info as *mut pwm_info_s as *mut c_void,
+            )
+        };
+
+        if result < 0 { Err(errno()) } else { Ok(pwm) }
+    }
+
+    /// Sets the frequency of the pulse train
+    ///
+    /// # Arguments
+    /// * `frequency` - The frequency in Hz
+    ///
+    /// # Errors
+    /// Returns the negative errno if the ioctl fails
+    pub fn set_frequency(&mut self, frequency: u32) -> PwmResult<()> {
+        self.info.frequency = frequency;
+        self.apply()
+    }
+
+    /// Sets the duty cycle of the pulse train
+    ///
+    /// # Arguments
+    /// * `duty` - The duty cycle in `ub16_t` format
+    ///
+    /// # Errors
+    /// Returns the negative errno if the ioctl fails
+    #[kconfig(CONFIG_PWM_MULTICHAN = "n")]
+    pub fn set_duty(&mut self, duty: u32) -> PwmResult<()> {
+        self.info.duty = duty as _;
+        self.apply()
+    }
+
+    /// Sets the duty cycle of all configured channels
+    ///
+    /// # Arguments
+    /// * `duty` - The duty cycle in `ub16_t` format
+    ///
+    /// # Errors
+    /// Returns the negative errno if the ioctl fails
+    #[kconfig(CONFIG_PWM_MULTICHAN = "y")]
+    pub fn set_duty(&mut self, duty: u32) -> PwmResult<()> {
+        for chan in self.info.channels.iter_mut() {
+            if chan.channel == -1 {
+                break;
+            }
+            chan.duty = duty as _;
+        }
+        self.apply()
+    }
+
+    /// Applies the frequency and channel duty cycles of a configuration
+    ///
+    /// # Errors
+    /// - `-EINVAL` if the configuration holds more than `CONFIG_PWM_NCHANNELS`
+    ///   channels
+    /// - The negative errno if the ioctl fails
+    #[kconfig(CONFIG_PWM_MULTICHAN = "y")]
+    pub fn configure(&mut self, config: &PwmConfig) -> PwmResult<()> {
+        if config.overflow {
+            return Err(-libc::EINVAL);
+        }
+
+        self.info.frequency = config.info.frequency;
+        self.info.channels = config.info.channels;
+        self.apply()
+    }
+
+    /// Starts the pulse train with the current characteristics
+    ///
+    /// # Errors
+    /// Returns the negative errno if the ioctl fails
+    pub fn start(&self) -> PwmResult<()> {
+        // SAFETY: PWMIOC_START takes no argument
+        let result = unsafe { ioctl(self.fd, PWMIOC_START as _, 0) };
+
+        if result < 0 { Err(errno()) } else { Ok(()) }
+    }
+
+    /// Stops the pulse train
+    ///
+    /// # Errors
+    /// Returns the negative errno if the ioctl fails
+    pub fn stop(&self) -> PwmResult<()> {
+        // SAFETY: PWMIOC_STOP takes no argument
+        let result = unsafe { ioctl(self.fd, PWMIOC_STOP as _, 0) };
+
+        if result < 0 { Err(errno()) } else { Ok(()) }
+    }
+
+    /// Passes the current characteristics to the driver
+    fn apply(&mut self) -> PwmResult<()> {
+        // SAFETY: The driver reads the pwm_info_s structure during the ioctl
+        let result = unsafe {
+            ioctl(
+                self.fd,
+                PWMIOC_SETCHARACTERISTICS as _,
+                &mut self.info as *mut pwm_info_s as *mut c_void,
+            )
+        };
+
+        if result < 0 { Err(errno()) } else { Ok(()) }
+    }
+}
+
+impl AsRawFd for Pwm {
+    /// Returns the file descriptor of the PWM device. The descriptor stays
+    /// owned by the Pwm.
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd
+    }
+}
+
+impl Drop for Pwm {
+    /// Closes the PWM device when the Pwm goes out of scope
+    fn drop(&mut self) {
+        unsafe { libc::close(self.fd) };
+    }
+}
@@ -0,0 +1,39 @@
+//! Error number helpers shared by the device modules
+//!
+//! Every fallible operation of this crate reports failures as the negative
+//! errno of the failing call, e.g. `-EINVAL`, matching the convention of the
+//! NuttX drivers themselves. libc calls instead return `-1` (or `MAP_FAILED`)
+//! and store the positive error number in `errno`; the helpers below perform
+//! that translation in one place.
+
+use libc::c_int;
+
+/// Returns the errno of the last failed libc call as a negative value
+pub(crate) fn errno() -> i32 {
+    -unsafe { *libc::__errno() }
+}
+
+/// Converts the result of a libc call returning `int`
+///
+/// # Returns
+/// - Ok(c_int) with the result if it is non-negative
+/// - Err(i32) with the negative errno otherwise
+pub(crate) fn errno_result(ret: c_int) -> Result<c_int, i32> {
+    if ret < 0 { Err(errno()) } else { Ok(ret) }
+}
+
+/// Converts the result of a libc call returning a pointer
+///
+/// Both a null pointer and `MAP_FAILED` are treated as failures.
+///
+/// # Returns
+/// - Ok(*mut T) with the pointer if it is valid
+/// - Err(i32) with the negative errno otherwise
+pub(crate) fn errno_result_ptr<T>(ptr: *mut T) -> Result<*mut T, i32> {
+    // MAP_FAILED is defined as (void *)-1
+    if ptr.is_null() || ptr as usize == usize::MAX {
+        Err(errno())
+    } else {
+        Ok(ptr)
+    }
+}
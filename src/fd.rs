@@ -0,0 +1,59 @@
+//! File descriptor access for NuttX device handles
+//!
+//! This module provides a `no_std` counterpart to `std::os::fd::AsRawFd`, so
+//! device handles of this crate can expose their underlying file descriptor
+//! to external poll loops and event frameworks.
+//!
+
+use libc::c_int;
+
+/// Raw file descriptor type, matches C's `int`
+pub type RawFd = c_int;
+
+/// A trait to extract the raw file descriptor from a device handle
+///
+/// The returned descriptor is borrowed: it remains owned by the handle and is
+/// closed when the handle is dropped.
+pub trait AsRawFd {
+    /// Returns the underlying file descriptor
+    fn as_raw_fd(&self) -> RawFd;
+}
+
+/// Owned descriptor stored by device handles
+///
+/// With the `std` feature this is [`std::os::fd::OwnedFd`], which closes the
+/// descriptor when dropped and lets handles implement `AsFd` and convert into
+/// an `OwnedFd`. Without it, handles store the bare descriptor and close it in
+/// their own `Drop` implementation.
+#[cfg(feature = "std")]
+pub(crate) type DeviceFd = std::os::fd::OwnedFd;
+
+#[cfg(not(feature = "std"))]
+pub(crate) type DeviceFd = RawFd;
+
+/// Takes ownership of a descriptor
+///
+/// # Safety
+/// `fd` must be open and must not be owned by anything else.
+#[cfg(feature = "std")]
+pub(crate) unsafe fn adopt(fd: RawFd) -> DeviceFd {
+    use std::os::fd::FromRawFd;
+
+    unsafe { std::os::fd::OwnedFd::from_raw_fd(fd) }
+}
+
+#[cfg(not(feature = "std"))]
+pub(crate) unsafe fn adopt(fd: RawFd) -> DeviceFd {
+    fd
+}
+
+/// Returns the raw descriptor of an owned descriptor
+#[cfg(feature = "std")]
+pub(crate) fn raw(fd: &DeviceFd) -> RawFd {
+    std::os::fd::AsRawFd::as_raw_fd(fd)
+}
+
+#[cfg(not(feature = "std"))]
+pub(crate) fn raw(fd: &DeviceFd) -> RawFd {
+    *fd
+}
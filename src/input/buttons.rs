@@ -0,0 +1,180 @@
+//! Discrete button input device interface
+//!
+//! This module provides Rust bindings for the NuttX button driver interface.
+//! It allows reading the set of pressed buttons and receiving a signal when
+//! buttons are pressed or released.
+//!
+//! The implementation matches the NuttX button interface defined in
+//! `nuttx/include/nuttx/input/buttons.h`.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use core::ffi::CStr;
+//! use nuttx::input::buttons::Buttons;
+//!
+//! let buttons = Buttons::open(CStr::from_bytes_with_nul(b"/dev/buttons\0").unwrap()).unwrap();
+//! let prev = buttons.read_state().unwrap();
+//! let cur = buttons.read_state().unwrap();
+//! let (pressed, released) = Buttons::changed_since(prev, cur);
+//! ```
+//!
+
+use core::ffi::CStr;
+use core::mem::{size_of, zeroed};
+use libc::{O_RDONLY, c_int, c_void, ioctl, open, read};
+
+use crate::bindings::{SIGEV_SIGNAL, btn_buttonset_t, btn_notify_s};
+use crate::errno::errno;
+use crate::fd::{AsRawFd, RawFd};
+use crate::input::InputError;
+use crate::io::ioctl::IoctlRequest;
+
+/// IOCTL command to get the set of buttons supported by the board
+///
+/// Matches C's BTNIOC_SUPPORTED
+const BTNIOC_SUPPORTED: IoctlRequest = crate::bindings::BTNIOC_SUPPORTED as IoctlRequest;
+
+/// IOCTL command to register for a signal on button press or release
+///
+/// Matches C's BTNIOC_REGISTER
+const BTNIOC_REGISTER: IoctlRequest = crate::bindings::BTNIOC_REGISTER as IoctlRequest;
+
+/// Represents an open button input device
+///
+/// Buttons are reported as a bitmask with bit N set while button N is pressed.
+/// The underlying device is closed when the Buttons is dropped.
+#[derive(Debug)]
+pub struct Buttons {
+    fd: c_int,
+}
+
+impl Buttons {
+    /// Opens a button device at the specified path
+    ///
+    /// # Arguments
+    /// * `path` - Path to the button device as a C string (e.g. "/dev/buttons")
+    ///
+    /// # Returns
+    /// - Ok(Buttons) on success
+    /// - Err(InputError::OpenFailed) with the negative errno if the device could
+    ///   not be opened
+    pub fn open(path: &CStr) -> Result<Self, InputError> {
+        let fd = unsafe { open(path.as_ptr(), O_RDONLY) };
+        if fd < 0 {
+            return Err(InputError::OpenFailed(errno()));
+        }
+        Ok(Buttons { fd })
+    }
+
+    /// Reads the set of currently pressed buttons
+    ///
+    /// # Returns
+    /// - Ok(u32) with bit N set if button N is pressed
+    /// - Err(InputError) if the read fails or returns less than a full button set
+    pub fn read_state(&self) -> Result<u32, InputError> {
+        let mut state: btn_buttonset_t = 0;
+        let size = size_of::<btn_buttonset_t>();
+
+        let ret = unsafe {
+            read(
+                self.fd,
+                &mut state as *mut btn_buttonset_t as *mut c_void,
+                size,
+            )
+        };
+
+        if ret < 0 {
+            return Err(InputError::read(errno()));
+        }
+        if (ret as usize) < size {
+            return Err(InputError::IncompleteRead);
+        }
+        Ok(state as u32)
+    }
+
+    /// Gets the set of buttons supported by the board
+    ///
+    /// # Returns
+    /// - Ok(u32) with bit N set if button N is available
+    /// - Err(InputError) if the ioctl fails
+    pub fn supported(&self) -> Result<u32, InputError> {
+        let mut supported: btn_buttonset_t = 0;
+
+        // SAFETY: BTNIOC_SUPPORTED stores a btn_buttonset_t through the passed pointer
+        let result = unsafe {
+            ioctl(
+                self.fd,
+                BTNIOC_SUPPORTED as _,
+                &mut supported as *mut btn_buttonset_t as *mut c_void,
+            )
+        };
+
+        if result < 0 {
+            Err(InputError::io(errno()))
+        } else {
+            Ok(supported as u32)
+        }
+    }
+
+    /// Registers the calling task to receive a signal when buttons change
+    ///
+    /// # Arguments
+    /// * `press` - Buttons whose press raises the signal
+    /// * `release` - Buttons whose release raises the signal
+    /// * `signo` - The signal to deliver, e.g. `SIGUSR1`
+    ///
+    /// # Returns
+    /// - Ok(()) on success
+    /// - Err(InputError) if the ioctl fails
+    pub fn register(&self, press: u32, release: u32, signo: c_int) -> Result<(), InputError> {
+        // SAFETY: A zeroed btn_notify_s is valid, the driver copies it during the ioctl
+        let mut notify: btn_notify_s = unsafe { zeroed() };
+        notify.bn_press = press as _;
+        notify.bn_release = release as _;
+        notify.bn_event.sigev_notify = SIGEV_SIGNAL as _;
+        notify.bn_event.sigev_signo = signo as _;
+
+        let result = unsafe {
+            ioctl(
+                self.fd,
+                BTNIOC_REGISTER as _,
+                &mut notify as *mut btn_notify_s as *mut c_void,
+            )
+        };
+
+        if result < 0 {
+            Err(InputError::io(errno()))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Compares two button states read with [`Buttons::read_state`]
+    ///
+    /// # Arguments
+    /// * `prev` - The earlier button state
+    /// * `cur` - The later button state
+    ///
+    /// # Returns
+    /// A `(pressed, released)` pair of masks holding the buttons that were
+    /// newly pressed and newly released between the two states
+    pub fn changed_since(prev: u32, cur: u32) -> (u32, u32) {
+        (cur & !prev, prev & !cur)
+    }
+}
+
+impl AsRawFd for Buttons {
+    /// Returns the file descriptor of the button device, e.g. for use in an
+    /// external poll loop. The descriptor stays owned by the Buttons.
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd
+    }
+}
+
+impl Drop for Buttons {
+    /// Closes the button device when the Buttons goes out of scope
+    fn drop(&mut self) {
+        unsafe { libc::close(self.fd) };
+    }
+}
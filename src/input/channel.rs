@@ -0,0 +1,503 @@
+//! Passing touch samples between threads
+//!
+//! This module decouples reading a touchscreen from processing its samples.
+//! Samples are passed through a [`SampleQueue`], a bounded queue shared
+//! between the thread reading the device and the threads consuming the
+//! samples. [`RingQueue`] is the provided implementation: it is lock-free,
+//! has a fixed capacity and allocates nothing, so it can be placed in a
+//! `static` and used without `std` or an allocator.
+//!
+//! With the `std` feature, [`TouchChannel`] spawns a thread that owns the
+//! touchscreen and pushes its samples into a queue, and hands out
+//! [`TouchReceiver`]s that wait for them. The queue type is a parameter, so
+//! any [`SampleQueue`] can back the channel.
+//!
+//! The module is only available with the `channel` feature.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use core::ffi::CStr;
+//! use nuttx::input::TouchChannel;
+//! use nuttx::input::touchscreen::TouchScreen;
+//!
+//! let ts = TouchScreen::open(CStr::from_bytes_with_nul(b"/dev/input0\0").unwrap()).unwrap();
+//! let channel = TouchChannel::spawn(ts).unwrap();
+//!
+//! let receiver = channel.receiver();
+//! std::thread::spawn(move || {
+//!     while let Some(sample) = receiver.recv() {
+//!         for point in sample.points() {
+//!             // Handle the point
+//!         }
+//!     }
+//! });
+//! ```
+
+use core::cell::UnsafeCell;
+use core::mem::MaybeUninit;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::input::touchscreen::TouchSample;
+
+#[cfg(feature = "std")]
+pub use threaded::{TouchChannel, TouchReceiver};
+
+/// Capacity of the queue used by `TouchChannel::spawn`
+pub const DEFAULT_CAPACITY: usize = 32;
+
+/// A bounded queue of touch samples shared between threads
+///
+/// Implementations must allow [`SampleQueue::push`] and [`SampleQueue::pop`]
+/// to be called concurrently from any number of threads.
+pub trait SampleQueue: Sync {
+    /// Appends a sample to the queue
+    ///
+    /// # Returns
+    /// - Ok(()) if the sample was queued
+    /// - Err(TouchSample) with the sample if the queue is full
+    fn push(&self, sample: TouchSample) -> Result<(), TouchSample>;
+
+    /// Removes the oldest sample from the queue
+    ///
+    /// # Returns
+    /// The oldest sample, or None if the queue is empty
+    fn pop(&self) -> Option<TouchSample>;
+}
+
+/// Lock-free queue holding up to `N` touch samples
+///
+/// Any number of threads may push and pop at the same time. Each slot carries
+/// a sequence number telling whether it is ready to be written or read for the
+/// current position, so neither side ever waits for the other. `N` must be a
+/// power of two.
+///
+/// # Examples
+///
+/// ```
+/// use nuttx::input::channel::{RingQueue, SampleQueue};
+/// use nuttx::input::touchscreen::TouchSample;
+///
+/// static QUEUE: RingQueue<4> = RingQueue::new();
+///
+/// // SAFETY: Touch samples are plain C structures
+/// let sample: TouchSample = unsafe { core::mem::zeroed() };
+/// QUEUE.push(sample).unwrap();
+/// assert!(QUEUE.pop().is_some());
+/// assert!(QUEUE.pop().is_none());
+/// ```
+pub struct RingQueue<const N: usize> {
+    slots: [Slot; N],
+    /// Position of the next push
+    head: AtomicUsize,
+    /// Position of the next pop
+    tail: AtomicUsize,
+}
+
+/// A sample in a [`RingQueue`] with its sequence number
+///
+/// The sequence equals the position of the next push to the slot while it is
+/// empty, and that position plus one once the sample has been written.
+struct Slot {
+    seq: AtomicUsize,
+    sample: UnsafeCell<MaybeUninit<TouchSample>>,
+}
+
+// SAFETY: A sample is only accessed by the thread that claimed its position,
+// and the sequence number hands it over with release/acquire ordering
+unsafe impl<const N: usize> Sync for RingQueue<N> {}
+
+impl<const N: usize> RingQueue<N> {
+    /// Create an empty queue
+    pub const fn new() -> Self {
+        const {
+            assert!(
+                N.is_power_of_two(),
+                "RingQueue capacity must be a power of two"
+            )
+        };
+
+        let mut slots = [const {
+            Slot {
+                seq: AtomicUsize::new(0),
+                sample: UnsafeCell::new(MaybeUninit::uninit()),
+            }
+        }; N];
+        let mut i = 0;
+        while i < N {
+            slots[i].seq = AtomicUsize::new(i);
+            i += 1;
+        }
+
+        RingQueue {
+            slots,
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+        }
+    }
+
+    /// Maximum number of samples the queue holds
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Number of samples currently queued
+    ///
+    /// The value may be out of date by the time it is used if other threads
+    /// access the queue.
+    pub fn len(&self) -> usize {
+        let tail = self.tail.load(Ordering::Acquire);
+        let head = self.head.load(Ordering::Acquire);
+        head.wrapping_sub(tail).min(N)
+    }
+
+    /// Whether no sample is queued, with the same caveat as [`RingQueue::len`]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn slot(&self, pos: usize) -> &Slot {
+        &self.slots[pos & (N - 1)]
+    }
+}
+
+impl<const N: usize> Default for RingQueue<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> SampleQueue for RingQueue<N> {
+    fn push(&self, sample: TouchSample) -> Result<(), TouchSample> {
+        let mut pos = self.head.load(Ordering::Relaxed);
+        loop {
+            let slot = self.slot(pos);
+            let seq = slot.seq.load(Ordering::Acquire);
+            let diff = seq.wrapping_sub(pos) as isize;
+
+            if diff == 0 {
+                match self.head.compare_exchange_weak(
+                    pos,
+                    pos.wrapping_add(1),
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => {
+                        // SAFETY: Claiming the position gives exclusive access
+                        // to the empty slot until the sequence is updated
+                        unsafe { (*slot.sample.get()).write(sample) };
+                        slot.seq.store(pos.wrapping_add(1), Ordering::Release);
+                        return Ok(());
+                    }
+                    Err(current) => pos = current,
+                }
+            } else if diff < 0 {
+                // The slot still holds the sample pushed one lap earlier
+                return Err(sample);
+            } else {
+                pos = self.head.load(Ordering::Relaxed);
+            }
+        }
+    }
+
+    fn pop(&self) -> Option<TouchSample> {
+        let mut pos = self.tail.load(Ordering::Relaxed);
+        loop {
+            let slot = self.slot(pos);
+            let seq = slot.seq.load(Ordering::Acquire);
+            let diff = seq.wrapping_sub(pos.wrapping_add(1)) as isize;
+
+            if diff == 0 {
+                match self.tail.compare_exchange_weak(
+                    pos,
+                    pos.wrapping_add(1),
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => {
+                        // SAFETY: Claiming the position gives exclusive access
+                        // to the written slot until the sequence is updated
+                        let sample = unsafe { (*slot.sample.get()).assume_init_read() };
+                        slot.seq.store(pos.wrapping_add(N), Ordering::Release);
+                        return Some(sample);
+                    }
+                    Err(current) => pos = current,
+                }
+            } else if diff < 0 {
+                // The slot has not been written for this position yet
+                return None;
+            } else {
+                pos = self.tail.load(Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+mod threaded {
+    use core::mem::zeroed;
+    use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use core::time::Duration;
+    use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+    use std::thread::{self, JoinHandle};
+
+    use super::{DEFAULT_CAPACITY, RingQueue, SampleQueue};
+    use crate::input::InputError;
+    use crate::input::touchscreen::{TouchSample, TouchScreen};
+
+    /// Number of samples the reader requests from the driver at once
+    const BATCH: usize = 8;
+
+    /// How long the reader waits for touch data before checking for a stop
+    /// request
+    const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+    /// State shared between the reader thread and the receivers
+    struct Shared<Q> {
+        queue: Q,
+        /// Held while checking for samples before waiting, and while
+        /// signalling new samples, so no wakeup is lost in between
+        lock: Mutex<()>,
+        ready: Condvar,
+        /// Set to ask the reader to stop
+        stop: AtomicBool,
+        /// Set once the reader has stopped, no sample is pushed afterwards
+        finished: AtomicBool,
+        /// Number of samples discarded because the queue was full
+        dropped: AtomicUsize,
+    }
+
+    impl<Q> Shared<Q> {
+        fn lock(&self) -> MutexGuard<'_, ()> {
+            self.lock.lock().unwrap_or_else(PoisonError::into_inner)
+        }
+
+        fn notify(&self) {
+            drop(self.lock());
+            self.ready.notify_all();
+        }
+    }
+
+    /// A touchscreen read by a dedicated thread
+    ///
+    /// The reader thread owns the touchscreen and pushes every sample it reads
+    /// into a queue of type `Q`, from which [`TouchChannel::recv`] and the
+    /// [`TouchReceiver`]s take them. Samples read while the queue is full are
+    /// discarded and counted by [`TouchChannel::dropped`].
+    ///
+    /// Dropping the channel stops the reader and closes the touchscreen. The
+    /// reader checks for a stop request between reads, at least every 100ms.
+    pub struct TouchChannel<Q: SampleQueue + Send + 'static = RingQueue<DEFAULT_CAPACITY>> {
+        shared: Arc<Shared<Q>>,
+        reader: Option<JoinHandle<Result<(), InputError>>>,
+    }
+
+    impl TouchChannel {
+        /// Starts reading a touchscreen into a [`RingQueue`] of
+        /// [`DEFAULT_CAPACITY`] samples
+        ///
+        /// # Arguments
+        /// * `ts` - The touchscreen, owned by the reader thread
+        ///
+        /// # Errors
+        /// Returns `InputError::Io` with the negative errno if the thread
+        /// cannot be created
+        pub fn spawn(ts: TouchScreen) -> Result<Self, InputError> {
+            Self::with_queue(ts, RingQueue::new())
+        }
+    }
+
+    impl<Q: SampleQueue + Send + 'static> TouchChannel<Q> {
+        /// Starts reading a touchscreen into the given queue
+        ///
+        /// # Arguments
+        /// * `ts` - The touchscreen, owned by the reader thread
+        /// * `queue` - The queue passing the samples to the receivers
+        ///
+        /// # Errors
+        /// Returns `InputError::Io` with the negative errno if the thread
+        /// cannot be created
+        pub fn with_queue(ts: TouchScreen, queue: Q) -> Result<Self, InputError> {
+            let shared = Arc::new(Shared {
+                queue,
+                lock: Mutex::new(()),
+                ready: Condvar::new(),
+                stop: AtomicBool::new(false),
+                finished: AtomicBool::new(false),
+                dropped: AtomicUsize::new(0),
+            });
+
+            let reader_shared = Arc::clone(&shared);
+            let reader = thread::Builder::new()
+                .name("touch".into())
+                .spawn(move || {
+                    let result = read_loop(ts, &reader_shared);
+                    let _guard = reader_shared.lock();
+                    reader_shared.finished.store(true, Ordering::Release);
+                    reader_shared.ready.notify_all();
+                    result
+                })
+                .map_err(|err| InputError::Io(-err.raw_os_error().unwrap_or(libc::EAGAIN)))?;
+
+            Ok(TouchChannel {
+                shared,
+                reader: Some(reader),
+            })
+        }
+
+        /// Get a receiver taking samples from this channel
+        ///
+        /// Receivers can be cloned and sent to other threads. Each sample is
+        /// taken by a single receiver.
+        pub fn receiver(&self) -> TouchReceiver<Q> {
+            TouchReceiver {
+                shared: Arc::clone(&self.shared),
+            }
+        }
+
+        /// Waits for the next sample, see [`TouchReceiver::recv`]
+        pub fn recv(&self) -> Option<TouchSample> {
+            recv(&self.shared)
+        }
+
+        /// Takes the next sample if one is queued, see
+        /// [`TouchReceiver::try_recv`]
+        pub fn try_recv(&self) -> Option<TouchSample> {
+            self.shared.queue.pop()
+        }
+
+        /// Number of samples discarded so far because the queue was full
+        pub fn dropped(&self) -> usize {
+            self.shared.dropped.load(Ordering::Relaxed)
+        }
+
+        /// Whether the reader thread has stopped, e.g. after a read error
+        pub fn is_finished(&self) -> bool {
+            self.shared.finished.load(Ordering::Acquire)
+        }
+
+        /// Stops the reader thread and waits for it to exit
+        ///
+        /// Samples still queued remain available to the receivers.
+        ///
+        /// # Errors
+        /// Returns the error that stopped the reader early, if any
+        pub fn stop(mut self) -> Result<(), InputError> {
+            self.join()
+        }
+
+        fn join(&mut self) -> Result<(), InputError> {
+            self.shared.stop.store(true, Ordering::Release);
+            match self.reader.take().map(JoinHandle::join) {
+                Some(Ok(result)) => result,
+                Some(Err(panic)) => std::panic::resume_unwind(panic),
+                None => Ok(()),
+            }
+        }
+    }
+
+    impl<Q: SampleQueue + Send + 'static> Drop for TouchChannel<Q> {
+        /// Stops the reader thread, ignoring its error
+        fn drop(&mut self) {
+            self.shared.stop.store(true, Ordering::Release);
+            if let Some(reader) = self.reader.take() {
+                let _ = reader.join();
+            }
+        }
+    }
+
+    /// Takes samples from a [`TouchChannel`], from any thread
+    pub struct TouchReceiver<Q: SampleQueue + Send + 'static = RingQueue<DEFAULT_CAPACITY>> {
+        shared: Arc<Shared<Q>>,
+    }
+
+    impl<Q: SampleQueue + Send + 'static> Clone for TouchReceiver<Q> {
+        fn clone(&self) -> Self {
+            TouchReceiver {
+                shared: Arc::clone(&self.shared),
+            }
+        }
+    }
+
+    impl<Q: SampleQueue + Send + 'static> TouchReceiver<Q> {
+        /// Waits for the next sample
+        ///
+        /// # Returns
+        /// The oldest queued sample, or None once the reader has stopped and
+        /// the queue is empty
+        pub fn recv(&self) -> Option<TouchSample> {
+            recv(&self.shared)
+        }
+
+        /// Takes the next sample if one is queued
+        ///
+        /// # Returns
+        /// The oldest queued sample, or None if the queue is empty
+        pub fn try_recv(&self) -> Option<TouchSample> {
+            self.shared.queue.pop()
+        }
+
+        /// Whether the reader thread has stopped, after which no more samples
+        /// are queued
+        pub fn is_finished(&self) -> bool {
+            self.shared.finished.load(Ordering::Acquire)
+        }
+    }
+
+    /// Waits for a sample in the shared queue, or for the reader to stop
+    fn recv<Q: SampleQueue>(shared: &Shared<Q>) -> Option<TouchSample> {
+        loop {
+            if let Some(sample) = shared.queue.pop() {
+                return Some(sample);
+            }
+
+            // Check again under the lock, the reader notifies while holding it
+            let guard = shared.lock();
+            if let Some(sample) = shared.queue.pop() {
+                return Some(sample);
+            }
+            if shared.finished.load(Ordering::Acquire) {
+                return None;
+            }
+            drop(
+                shared
+                    .ready
+                    .wait(guard)
+                    .unwrap_or_else(PoisonError::into_inner),
+            );
+        }
+    }
+
+    /// Reads samples into the queue until asked to stop or a read fails
+    fn read_loop<Q: SampleQueue>(
+        mut ts: TouchScreen,
+        shared: &Shared<Q>,
+    ) -> Result<(), InputError> {
+        // SAFETY: Touch samples are plain C structures
+        let mut buf: [TouchSample; BATCH] = unsafe { zeroed() };
+
+        while !shared.stop.load(Ordering::Acquire) {
+            match ts.wait_readable(Some(POLL_INTERVAL)) {
+                Ok(true) => {}
+                Ok(false) | Err(InputError::Interrupted) => continue,
+                Err(err) => return Err(err),
+            }
+
+            let count = match ts.read_samples(&mut buf) {
+                Ok(count) => count,
+                Err(InputError::WouldBlock | InputError::Interrupted) => continue,
+                Err(err) => return Err(err),
+            };
+
+            for sample in &buf[..count] {
+                if shared.queue.push(*sample).is_err() {
+                    shared.dropped.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+            if count > 0 {
+                shared.notify();
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,64 @@
+//! Error type shared by the input device interfaces
+//!
+//! Failing libc calls report a negative errno, which is classified into an
+//! [`InputError`] so callers can branch on the common non-fatal conditions
+//! (interrupted and would-block) without inspecting raw error numbers.
+//!
+
+use libc::{EAGAIN, EINTR, EWOULDBLOCK};
+
+/// Errors reported by input devices
+///
+/// Variants carrying an `i32` hold the negative errno reported by NuttX.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputError {
+    /// The device could not be opened
+    OpenFailed(i32),
+    /// Reading from the device failed
+    ReadFailed(i32),
+    /// The operation was interrupted by a signal and can be retried
+    Interrupted,
+    /// The device is in non-blocking mode and no data is available
+    WouldBlock,
+    /// The device returned less data than a complete event
+    IncompleteRead,
+    /// Any other failing operation on the device, e.g. an ioctl
+    Io(i32),
+}
+
+impl InputError {
+    /// Returns the negative errno corresponding to this error
+    ///
+    /// # Returns
+    /// - Some(i32) with the negative errno
+    /// - None for `IncompleteRead`, which is detected by this crate rather than
+    ///   reported by NuttX
+    pub fn errno(&self) -> Option<i32> {
+        match self {
+            InputError::OpenFailed(errno)
+            | InputError::ReadFailed(errno)
+            | InputError::Io(errno) => Some(*errno),
+            InputError::Interrupted => Some(-EINTR),
+            InputError::WouldBlock => Some(-EAGAIN),
+            InputError::IncompleteRead => None,
+        }
+    }
+
+    /// Classifies the negative errno of a failed read
+    pub(crate) fn read(errno: i32) -> Self {
+        Self::classify(errno).unwrap_or(InputError::ReadFailed(errno))
+    }
+
+    /// Classifies the negative errno of any other failed operation
+    pub(crate) fn io(errno: i32) -> Self {
+        Self::classify(errno).unwrap_or(InputError::Io(errno))
+    }
+
+    fn classify(errno: i32) -> Option<Self> {
+        match -errno {
+            EINTR => Some(InputError::Interrupted),
+            err if err == EAGAIN || err == EWOULDBLOCK => Some(InputError::WouldBlock),
+            _ => None,
+        }
+    }
+}
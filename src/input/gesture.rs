@@ -0,0 +1,443 @@
+//! Recognition of touch gestures
+//!
+//! This module provides a [`Recognizer`] assembling the raw touch down, move
+//! and up events of a touchscreen into taps, long presses, swipes and pinches.
+//! It works on the samples read from a [`TouchScreen`], so it is independent
+//! of the gestures some touch controllers detect themselves (see
+//! [`TouchPoint::gesture`]).
+//!
+//! The recognizer is allocation free: at most [`MAX_TRACKED_POINTS`] contacts
+//! are followed at once, further contacts are ignored.
+//!
+//! [`TouchScreen`]: crate::input::touchscreen::TouchScreen
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use core::ffi::CStr;
+//! use nuttx::input::gesture::{Gesture, Recognizer};
+//! use nuttx::input::touchscreen::TouchScreen;
+//!
+//! let mut ts = TouchScreen::open_blocking(CStr::from_bytes_with_nul(b"/dev/input0\0").unwrap()).unwrap();
+//! let mut recognizer = Recognizer::new();
+//!
+//! loop {
+//!     let sample = ts.read_sample().unwrap();
+//!     match recognizer.feed(&sample) {
+//!         Some(Gesture::Tap { x, y }) => { /* Activate the widget at (x, y) */ }
+//!         Some(Gesture::Swipe { direction, .. }) => { /* Change the page */ }
+//!         _ => {}
+//!     }
+//! }
+//! ```
+//!
+
+use core::time::Duration;
+
+use crate::input::touchscreen::{TouchPoint, TouchSample};
+
+/// Maximum number of contacts followed at once
+pub const MAX_TRACKED_POINTS: usize = 5;
+
+/// Direction of a swipe, in screen coordinates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Towards smaller y
+    Up,
+    /// Towards larger y
+    Down,
+    /// Towards smaller x
+    Left,
+    /// Towards larger x
+    Right,
+}
+
+/// Gesture recognized from touch events
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Gesture {
+    /// A short touch without movement, at the position of the touch
+    Tap { x: i32, y: i32 },
+    /// A tap shortly after and close to a previous tap, at the position of
+    /// the second touch. The first tap has been reported as a `Tap` already.
+    DoubleTap { x: i32, y: i32 },
+    /// A touch held without movement, at the position of the touch
+    LongPress { x: i32, y: i32 },
+    /// A single contact moved in one direction before it was released
+    Swipe {
+        /// The dominant direction of the movement
+        direction: Direction,
+        /// The distance moved along the dominant direction
+        distance: u32,
+    },
+    /// Two contacts moved towards or away from each other
+    Pinch {
+        /// Change of the distance between the contacts since the previous
+        /// pinch event, positive when they moved apart
+        delta: i32,
+    },
+}
+
+/// Thresholds used to tell gestures apart
+///
+/// Distances are in the units of the touch coordinates, usually pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Thresholds {
+    /// Largest movement of a contact that is still a tap or long press
+    pub tap_slop: u32,
+    /// Longest touch reported as a tap
+    pub tap_max_duration: Duration,
+    /// Longest time between two taps reported as a double tap
+    pub double_tap_interval: Duration,
+    /// Shortest touch reported as a long press
+    pub long_press_duration: Duration,
+    /// Shortest movement reported as a swipe
+    pub swipe_min_distance: u32,
+    /// Smallest change of the distance between two contacts reported as a
+    /// pinch
+    pub pinch_min_change: u32,
+}
+
+impl Default for Thresholds {
+    /// Creates thresholds suited to a finger on a small display
+    fn default() -> Self {
+        Self {
+            tap_slop: 10,
+            tap_max_duration: Duration::from_millis(300),
+            double_tap_interval: Duration::from_millis(300),
+            long_press_duration: Duration::from_millis(500),
+            swipe_min_distance: 50,
+            pinch_min_change: 20,
+        }
+    }
+}
+
+/// A contact followed from touch down to touch up
+#[derive(Debug, Clone, Copy)]
+struct Track {
+    id: u8,
+    start: (i32, i32),
+    start_time: Duration,
+    pos: (i32, i32),
+    /// Whether the contact moved further than the tap slop
+    moved: bool,
+    /// Whether a long press was reported for the contact
+    long_pressed: bool,
+}
+
+/// Assembles touch events into gestures
+///
+/// Feed every sample read from the touchscreen to [`Recognizer::feed`]. Taps,
+/// double taps and swipes are reported when the contact is released, pinches
+/// while the contacts move. A long press is reported by the first event of the
+/// held contact after the long press duration, or at its release, as NuttX
+/// drivers report no events for a contact that does not move.
+///
+/// Touch points without a valid ID are all treated as the same contact. Once
+/// a second contact touches down, the touch only reports pinches until all
+/// contacts are released.
+#[derive(Debug, Clone)]
+pub struct Recognizer {
+    thresholds: Thresholds,
+    tracks: [Option<Track>; MAX_TRACKED_POINTS],
+    /// Position and time of the last tap, for double taps
+    last_tap: Option<((i32, i32), Duration)>,
+    /// Distance between the first two contacts at the last pinch event
+    pinch_distance: Option<u32>,
+    /// Whether several contacts touched since the first touch down
+    multi_touch: bool,
+}
+
+impl Recognizer {
+    /// Creates a Recognizer with the default thresholds
+    pub fn new() -> Self {
+        Self::with_thresholds(Thresholds::default())
+    }
+
+    /// Creates a Recognizer with custom thresholds
+    pub fn with_thresholds(thresholds: Thresholds) -> Self {
+        Recognizer {
+            thresholds,
+            tracks: [None; MAX_TRACKED_POINTS],
+            last_tap: None,
+            pinch_distance: None,
+            multi_touch: false,
+        }
+    }
+
+    /// The thresholds in use
+    pub fn thresholds(&self) -> &Thresholds {
+        &self.thresholds
+    }
+
+    /// Forgets all contacts and the last tap, e.g. when the screen changes
+    pub fn reset(&mut self) {
+        *self = Self::with_thresholds(self.thresholds);
+    }
+
+    /// Processes the touch points of a sample
+    ///
+    /// # Returns
+    /// The gesture completed by the sample, or None. Should one sample
+    /// complete several gestures, only the first is returned.
+    pub fn feed(&mut self, sample: &TouchSample) -> Option<Gesture> {
+        sample
+            .points()
+            .fold(None, |gesture, point| gesture.or(self.feed_point(point)))
+    }
+
+    /// Processes a single touch point, e.g. one read with
+    /// [`TouchScreen::read_points`](crate::input::touchscreen::TouchScreen::read_points)
+    ///
+    /// # Returns
+    /// The gesture completed by the point, or None
+    pub fn feed_point(&mut self, point: &TouchPoint) -> Option<Gesture> {
+        let id = if point.is_id_valid() { point.id } else { 0 };
+        let time = point.timestamp_duration();
+
+        if point.is_touch_down() {
+            self.touch_down(id, point, time)
+        } else if point.is_touch_move() {
+            self.touch_move(id, point, time)
+        } else if point.is_touch_up() {
+            self.touch_up(id, point, time)
+        } else {
+            None
+        }
+    }
+
+    fn touch_down(&mut self, id: u8, point: &TouchPoint, time: Duration) -> Option<Gesture> {
+        let pos = (point.x as i32, point.y as i32);
+        let track = Track {
+            id,
+            start: pos,
+            start_time: time,
+            pos,
+            moved: false,
+            long_pressed: false,
+        };
+
+        // A repeated touch down of a followed contact restarts it
+        let slot = match self.find(id) {
+            Some(index) => index,
+            None => self.tracks.iter().position(Option::is_none)?,
+        };
+        self.tracks[slot] = Some(track);
+
+        if self.active() > 1 {
+            self.multi_touch = true;
+            self.pinch_distance = self.contact_distance();
+        }
+        None
+    }
+
+    fn touch_move(&mut self, id: u8, point: &TouchPoint, time: Duration) -> Option<Gesture> {
+        let slop = self.thresholds.tap_slop;
+        let long_press = self.thresholds.long_press_duration;
+        let track = self.tracks[self.find(id)?].as_mut()?;
+
+        if point.is_pos_valid() {
+            track.pos = (point.x as i32, point.y as i32);
+        }
+        track.moved |= distance(track.start, track.pos) > slop;
+
+        if self.multi_touch {
+            return self.pinch();
+        }
+
+        if !track.moved
+            && !track.long_pressed
+            && time.saturating_sub(track.start_time) >= long_press
+        {
+            track.long_pressed = true;
+            let (x, y) = track.pos;
+            return Some(Gesture::LongPress { x, y });
+        }
+        None
+    }
+
+    fn touch_up(&mut self, id: u8, point: &TouchPoint, time: Duration) -> Option<Gesture> {
+        let slot = self.find(id)?;
+        let mut track = self.tracks[slot].take()?;
+
+        if point.is_pos_valid() {
+            track.pos = (point.x as i32, point.y as i32);
+        }
+
+        if self.multi_touch {
+            if self.active() == 0 {
+                self.multi_touch = false;
+                self.pinch_distance = None;
+            } else if self.active() == 1 {
+                self.pinch_distance = None;
+            }
+            return None;
+        }
+
+        self.single_touch_up(&track, time)
+    }
+
+    /// Classifies a released contact that was the only one
+    fn single_touch_up(&mut self, track: &Track, time: Duration) -> Option<Gesture> {
+        let thresholds = self.thresholds;
+        let (dx, dy) = (track.pos.0 - track.start.0, track.pos.1 - track.start.1);
+        let (x, y) = track.pos;
+
+        let (direction, moved) = if dx.unsigned_abs() >= dy.unsigned_abs() {
+            let direction = if dx < 0 {
+                Direction::Left
+            } else {
+                Direction::Right
+            };
+            (direction, dx.unsigned_abs())
+        } else {
+            let direction = if dy < 0 {
+                Direction::Up
+            } else {
+                Direction::Down
+            };
+            (direction, dy.unsigned_abs())
+        };
+
+        if moved >= thresholds.swipe_min_distance {
+            self.last_tap = None;
+            return Some(Gesture::Swipe {
+                direction,
+                distance: moved,
+            });
+        }
+
+        let held = time.saturating_sub(track.start_time);
+        if track.moved || track.long_pressed {
+            None
+        } else if held >= thresholds.long_press_duration {
+            Some(Gesture::LongPress { x, y })
+        } else if held <= thresholds.tap_max_duration {
+            let double = self.last_tap.is_some_and(|(pos, tap_time)| {
+                time.saturating_sub(tap_time) <= thresholds.double_tap_interval
+                    && distance(pos, track.pos) <= thresholds.tap_slop
+            });
+
+            if double {
+                self.last_tap = None;
+                Some(Gesture::DoubleTap { x, y })
+            } else {
+                self.last_tap = Some((track.pos, time));
+                Some(Gesture::Tap { x, y })
+            }
+        } else {
+            None
+        }
+    }
+
+    /// Reports a pinch if the first two contacts moved far enough
+    fn pinch(&mut self) -> Option<Gesture> {
+        let current = self.contact_distance()?;
+        let previous = *self.pinch_distance.get_or_insert(current);
+
+        if current.abs_diff(previous) < self.thresholds.pinch_min_change {
+            return None;
+        }
+
+        self.pinch_distance = Some(current);
+        Some(Gesture::Pinch {
+            delta: current as i32 - previous as i32,
+        })
+    }
+
+    /// Distance between the first two followed contacts
+    fn contact_distance(&self) -> Option<u32> {
+        let mut tracks = self.tracks.iter().flatten();
+        let first = tracks.next()?;
+        let second = tracks.next()?;
+        Some(distance(first.pos, second.pos))
+    }
+
+    fn find(&self, id: u8) -> Option<usize> {
+        self.tracks
+            .iter()
+            .position(|track| track.is_some_and(|track| track.id == id))
+    }
+
+    fn active(&self) -> usize {
+        self.tracks.iter().flatten().count()
+    }
+}
+
+impl Default for Recognizer {
+    /// Creates a Recognizer with the default thresholds
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Euclidean distance between two positions
+fn distance(a: (i32, i32), b: (i32, i32)) -> u32 {
+    let dx = a.0.abs_diff(b.0) as u64;
+    let dy = a.1.abs_diff(b.1) as u64;
+    (dx * dx + dy * dy).isqrt() as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::touchscreen::TouchFlags;
+
+    /// A touch point of the only contact, `time_us` microseconds after boot
+    fn point(flags: TouchFlags, x: i16, time_us: u64) -> TouchPoint {
+        TouchPoint {
+            x,
+            y: 100,
+            flags: (flags | TouchFlags::POS_VALID).bits(),
+            timestamp: time_us,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn tap_with_microsecond_timestamps() {
+        let mut recognizer = Recognizer::new();
+        let boot = 12_000_000;
+
+        // Released after 80 ms, as drivers report it
+        assert_eq!(
+            recognizer.feed_point(&point(TouchFlags::DOWN, 40, boot)),
+            None
+        );
+        assert_eq!(
+            recognizer.feed_point(&point(TouchFlags::MOVE, 41, boot + 40_000)),
+            None
+        );
+        assert_eq!(
+            recognizer.feed_point(&point(TouchFlags::UP, 41, boot + 80_000)),
+            Some(Gesture::Tap { x: 41, y: 100 })
+        );
+
+        // A second tap 200 ms later is a double tap
+        let later = boot + 280_000;
+        recognizer.feed_point(&point(TouchFlags::DOWN, 42, later));
+        assert_eq!(
+            recognizer.feed_point(&point(TouchFlags::UP, 42, later + 60_000)),
+            Some(Gesture::DoubleTap { x: 42, y: 100 })
+        );
+    }
+
+    #[test]
+    fn long_press_with_microsecond_timestamps() {
+        let mut recognizer = Recognizer::new();
+        let boot = 12_000_000;
+
+        recognizer.feed_point(&point(TouchFlags::DOWN, 40, boot));
+        assert_eq!(
+            recognizer.feed_point(&point(TouchFlags::MOVE, 40, boot + 400_000)),
+            None
+        );
+        assert_eq!(
+            recognizer.feed_point(&point(TouchFlags::MOVE, 40, boot + 600_000)),
+            Some(Gesture::LongPress { x: 40, y: 100 })
+        );
+        assert_eq!(
+            recognizer.feed_point(&point(TouchFlags::UP, 40, boot + 700_000)),
+            None
+        );
+    }
+}
@@ -0,0 +1,326 @@
+//! Joystick input device interface
+//!
+//! This module provides Rust bindings for the NuttX discrete and analog
+//! joystick driver interfaces. Both report the joystick position and the set
+//! of pressed buttons, and can signal button changes.
+//!
+//! The implementation matches the NuttX joystick interfaces defined in
+//! `nuttx/include/nuttx/input/djoystick.h` and
+//! `nuttx/include/nuttx/input/ajoystick.h`.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use core::ffi::CStr;
+//! use nuttx::input::joystick::Joystick;
+//!
+//! let joystick = Joystick::open_analog(CStr::from_bytes_with_nul(b"/dev/ajoy0\0").unwrap()).unwrap();
+//! let sample = joystick.read_sample().unwrap();
+//! ```
+//!
+
+use core::ffi::CStr;
+use core::mem::{size_of, zeroed};
+use libc::{O_RDONLY, c_int, c_void, ioctl, open, read};
+
+use crate::bindings::{
+    DJOY_DOWN_BIT, DJOY_LEFT_BIT, DJOY_RIGHT_BIT, DJOY_UP_BIT, SIGEV_SIGNAL, ajoy_buttonset_t,
+    ajoy_notify_s, ajoy_sample_s, djoy_buttonset_t, djoy_notify_s,
+};
+use crate::errno::errno;
+use crate::fd::{AsRawFd, RawFd};
+use crate::input::InputError;
+use crate::io::ioctl::IoctlRequest;
+
+/// IOCTL command to get the set of buttons supported by a discrete joystick
+///
+/// Matches C's DJOYIOC_SUPPORTED
+const DJOYIOC_SUPPORTED: IoctlRequest = crate::bindings::DJOYIOC_SUPPORTED as IoctlRequest;
+
+/// IOCTL command to register for a signal on discrete joystick changes
+///
+/// Matches C's DJOYIOC_REGISTER
+const DJOYIOC_REGISTER: IoctlRequest = crate::bindings::DJOYIOC_REGISTER as IoctlRequest;
+
+/// IOCTL command to get the set of buttons supported by an analog joystick
+///
+/// Matches C's AJOYIOC_SUPPORTED
+const AJOYIOC_SUPPORTED: IoctlRequest = crate::bindings::AJOYIOC_SUPPORTED as IoctlRequest;
+
+/// IOCTL command to register for a signal on analog joystick button changes
+///
+/// Matches C's AJOYIOC_REGISTER
+const AJOYIOC_REGISTER: IoctlRequest = crate::bindings::AJOYIOC_REGISTER as IoctlRequest;
+
+/// Number of direction bits preceding the buttons in a discrete joystick set
+const DJOY_NDIRECTIONS: u32 = 4;
+
+/// Mask of the direction bits in a discrete joystick set
+const DJOY_DIRECTIONS: u32 = DJOY_UP_BIT | DJOY_DOWN_BIT | DJOY_LEFT_BIT | DJOY_RIGHT_BIT;
+
+/// Kind of joystick driver behind a [`Joystick`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JoystickKind {
+    /// A discrete joystick, reporting each direction as a switch
+    Discrete,
+    /// An analog joystick, reporting the position of each axis
+    Analog,
+}
+
+/// A joystick reading
+///
+/// Axes range from `-i16::MAX` to `i16::MAX`, with left and up being negative.
+/// Discrete joysticks report either end of the range or zero.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct JoystickSample {
+    /// Pressed buttons, with bit N set while button N + 1 is pressed
+    pub buttons: u32,
+    /// Horizontal position
+    pub x: i16,
+    /// Vertical position
+    pub y: i16,
+}
+
+/// Axes reported by a joystick
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct JoystickAxes {
+    /// The joystick reports a horizontal position
+    pub x: bool,
+    /// The joystick reports a vertical position
+    pub y: bool,
+}
+
+/// Represents an open connection to a joystick input device
+///
+/// The underlying device is closed when the Joystick is dropped.
+#[derive(Debug)]
+pub struct Joystick {
+    fd: c_int,
+    kind: JoystickKind,
+}
+
+impl Joystick {
+    /// Opens a discrete joystick device at the specified path
+    ///
+    /// # Arguments
+    /// * `path` - Path to the joystick device as a C string (e.g. "/dev/djoy0")
+    ///
+    /// # Returns
+    /// - Ok(Joystick) on success
+    /// - Err(InputError::OpenFailed) with the negative errno if the device could
+    ///   not be opened
+    pub fn open_discrete(path: &CStr) -> Result<Self, InputError> {
+        Self::open(path, JoystickKind::Discrete)
+    }
+
+    /// Opens an analog joystick device at the specified path
+    ///
+    /// # Arguments
+    /// * `path` - Path to the joystick device as a C string (e.g. "/dev/ajoy0")
+    ///
+    /// # Returns
+    /// - Ok(Joystick) on success
+    /// - Err(InputError::OpenFailed) with the negative errno if the device could
+    ///   not be opened
+    pub fn open_analog(path: &CStr) -> Result<Self, InputError> {
+        Self::open(path, JoystickKind::Analog)
+    }
+
+    fn open(path: &CStr, kind: JoystickKind) -> Result<Self, InputError> {
+        let fd = unsafe { open(path.as_ptr(), O_RDONLY) };
+        if fd < 0 {
+            return Err(InputError::OpenFailed(errno()));
+        }
+        Ok(Joystick { fd, kind })
+    }
+
+    /// Returns the kind of joystick driver
+    pub fn kind(&self) -> JoystickKind {
+        self.kind
+    }
+
+    /// Reads the current position and buttons of the joystick
+    ///
+    /// # Returns
+    /// - Ok(JoystickSample) with the current reading
+    /// - Err(InputError) if the read fails or returns less than a full sample
+    pub fn read_sample(&self) -> Result<JoystickSample, InputError> {
+        match self.kind {
+            JoystickKind::Discrete => {
+                let state: djoy_buttonset_t = self.read_raw()?;
+                let state = state as u32;
+
+                Ok(JoystickSample {
+                    buttons: state >> DJOY_NDIRECTIONS,
+                    x: Self::direction(state, DJOY_LEFT_BIT, DJOY_RIGHT_BIT),
+                    y: Self::direction(state, DJOY_UP_BIT, DJOY_DOWN_BIT),
+                })
+            }
+            JoystickKind::Analog => {
+                let sample: ajoy_sample_s = self.read_raw()?;
+
+                Ok(JoystickSample {
+                    buttons: sample.as_buttons as u32,
+                    x: sample.as_x,
+                    y: sample.as_y,
+                })
+            }
+        }
+    }
+
+    /// Gets the set of buttons supported by the joystick
+    ///
+    /// # Returns
+    /// - Ok(u32) with bit N set if button N + 1 is available
+    /// - Err(InputError) if the ioctl fails
+    pub fn supported_buttons(&self) -> Result<u32, InputError> {
+        Ok(match self.kind {
+            JoystickKind::Discrete => self.supported_raw()? >> DJOY_NDIRECTIONS,
+            JoystickKind::Analog => self.supported_raw()?,
+        })
+    }
+
+    /// Gets the axes reported by the joystick
+    ///
+    /// Analog joysticks always report both axes, while a discrete joystick
+    /// reports an axis if it supports either of its directions.
+    ///
+    /// # Returns
+    /// - Ok(JoystickAxes) with the reported axes
+    /// - Err(InputError) if the ioctl fails
+    pub fn supported_axes(&self) -> Result<JoystickAxes, InputError> {
+        match self.kind {
+            JoystickKind::Discrete => {
+                let supported = self.supported_raw()?;
+
+                Ok(JoystickAxes {
+                    x: supported & (DJOY_LEFT_BIT | DJOY_RIGHT_BIT) != 0,
+                    y: supported & (DJOY_UP_BIT | DJOY_DOWN_BIT) != 0,
+                })
+            }
+            JoystickKind::Analog => Ok(JoystickAxes { x: true, y: true }),
+        }
+    }
+
+    /// Registers the calling task to receive a signal when buttons change
+    ///
+    /// Discrete joysticks also raise the signal whenever a direction is pressed
+    /// or released.
+    ///
+    /// # Arguments
+    /// * `press` - Buttons whose press raises the signal, numbered as in
+    ///   [`JoystickSample::buttons`]
+    /// * `release` - Buttons whose release raises the signal
+    /// * `signo` - The signal to deliver, e.g. `SIGUSR1`
+    ///
+    /// # Returns
+    /// - Ok(()) on success
+    /// - Err(InputError) if the ioctl fails
+    pub fn register(&self, press: u32, release: u32, signo: c_int) -> Result<(), InputError> {
+        let result = match self.kind {
+            JoystickKind::Discrete => {
+                // SAFETY: A zeroed djoy_notify_s is valid, the driver copies it
+                // during the ioctl
+                let mut notify: djoy_notify_s = unsafe { zeroed() };
+                notify.dn_press = ((press << DJOY_NDIRECTIONS) | DJOY_DIRECTIONS) as _;
+                notify.dn_release = ((release << DJOY_NDIRECTIONS) | DJOY_DIRECTIONS) as _;
+                notify.dn_event.sigev_notify = SIGEV_SIGNAL as _;
+                notify.dn_event.sigev_signo = signo as _;
+
+                unsafe {
+                    ioctl(
+                        self.fd,
+                        DJOYIOC_REGISTER as _,
+                        &mut notify as *mut djoy_notify_s as *mut c_void,
+                    )
+                }
+            }
+            JoystickKind::Analog => {
+                // SAFETY: A zeroed ajoy_notify_s is valid, the driver copies it
+                // during the ioctl
+                let mut notify: ajoy_notify_s = unsafe { zeroed() };
+                notify.an_press = press as _;
+                notify.an_release = release as _;
+                notify.an_event.sigev_notify = SIGEV_SIGNAL as _;
+                notify.an_event.sigev_signo = signo as _;
+
+                unsafe {
+                    ioctl(
+                        self.fd,
+                        AJOYIOC_REGISTER as _,
+                        &mut notify as *mut ajoy_notify_s as *mut c_void,
+                    )
+                }
+            }
+        };
+
+        if result < 0 {
+            Err(InputError::io(errno()))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Reads a complete driver sample of type `T`
+    fn read_raw<T: Copy>(&self) -> Result<T, InputError> {
+        // SAFETY: T is one of the plain C sample types, for which zero is valid
+        let mut sample: T = unsafe { zeroed() };
+        let size = size_of::<T>();
+
+        let ret = unsafe { read(self.fd, &mut sample as *mut T as *mut c_void, size) };
+
+        if ret < 0 {
+            return Err(InputError::read(errno()));
+        }
+        if (ret as usize) < size {
+            return Err(InputError::IncompleteRead);
+        }
+        Ok(sample)
+    }
+
+    /// Gets the raw supported set of the driver
+    fn supported_raw(&self) -> Result<u32, InputError> {
+        Ok(match self.kind {
+            JoystickKind::Discrete => self.get::<djoy_buttonset_t>(DJOYIOC_SUPPORTED)? as u32,
+            JoystickKind::Analog => self.get::<ajoy_buttonset_t>(AJOYIOC_SUPPORTED)? as u32,
+        })
+    }
+
+    /// Issues an ioctl that stores a value of type `T`
+    fn get<T: Copy>(&self, cmd: IoctlRequest) -> Result<T, InputError> {
+        // SAFETY: T is one of the plain C button set types, for which zero is valid
+        let mut value: T = unsafe { zeroed() };
+
+        let result = unsafe { ioctl(self.fd, cmd as _, &mut value as *mut T as *mut c_void) };
+
+        if result < 0 {
+            Err(InputError::io(errno()))
+        } else {
+            Ok(value)
+        }
+    }
+
+    /// Converts a pair of discrete direction bits into an axis position
+    fn direction(state: u32, negative: u32, positive: u32) -> i16 {
+        match (state & negative != 0, state & positive != 0) {
+            (true, false) => -i16::MAX,
+            (false, true) => i16::MAX,
+            _ => 0,
+        }
+    }
+}
+
+impl AsRawFd for Joystick {
+    /// Returns the file descriptor of the joystick device, e.g. for use in an
+    /// external poll loop. The descriptor stays owned by the Joystick.
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd
+    }
+}
+
+impl Drop for Joystick {
+    /// Closes the joystick device when the Joystick goes out of scope
+    fn drop(&mut self) {
+        unsafe { libc::close(self.fd) };
+    }
+}
@@ -0,0 +1,559 @@
+//! Keyboard input device interface
+//!
+//! This module provides Rust bindings for NuttX keyboard drivers, which deliver
+//! key presses and releases as a byte stream using the NuttX keyboard encoding:
+//!
+//! - A plain byte is the press of that character
+//! - `ESC [ <ch> b` is the release of character `<ch>`
+//! - `ESC [ <code> c` is the press of the special key `<code>`
+//! - `ESC [ <code> d` is the release of the special key `<code>`
+//!
+//! The implementation matches the NuttX keyboard encoding defined in
+//! `nuttx/include/nuttx/input/kbd_codec.h`.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use core::ffi::CStr;
+//! use nuttx::input::keyboard::{KeyEvent, Keyboard};
+//!
+//! let mut kbd = Keyboard::open_blocking(CStr::from_bytes_with_nul(b"/dev/kbd0\0").unwrap()).unwrap();
+//! let mut events = [KeyEvent::default(); 8];
+//! let count = kbd.read_events(&mut events).unwrap();
+//! ```
+//!
+
+use core::ffi::CStr;
+use libc::{O_NONBLOCK, O_RDONLY, c_int, c_void, open, read};
+
+use crate::bindings::{
+    self, kbd_getstate_e_KBD_RELEASE, kbd_getstate_e_KBD_SPECPRESS, kbd_getstate_e_KBD_SPECREL,
+};
+use crate::errno::errno;
+use crate::fd::{AsRawFd, RawFd};
+use crate::input::InputError;
+
+/// Escape character starting an encoded key sequence
+const ASCII_ESC: u8 = 0x1b;
+
+/// Terminator of an encoded character release
+const TERM_RELEASE: u8 = b'a' + kbd_getstate_e_KBD_RELEASE as u8;
+
+/// Terminator of an encoded special key press
+const TERM_SPECPRESS: u8 = b'a' + kbd_getstate_e_KBD_SPECPRESS as u8;
+
+/// Terminator of an encoded special key release
+const TERM_SPECREL: u8 = b'a' + kbd_getstate_e_KBD_SPECREL as u8;
+
+/// Length of an encoded key sequence
+const SEQUENCE_LEN: usize = 4;
+
+/// Number of encoded bytes buffered between reads
+const RAW_BUFFER_SIZE: usize = 32;
+
+/// Key reported by the keyboard
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyCode {
+    /// A character key, holding the translated character
+    Char(u8),
+    /// A special key, holding a `KEYCODE_*` value of `enum kbd_keycode_e`
+    Special(u8),
+}
+
+impl Default for KeyCode {
+    fn default() -> Self {
+        KeyCode::Char(0)
+    }
+}
+
+/// Modifier keys active for a key event
+///
+/// NuttX delivers characters already translated by the driver, so modifiers
+/// are inferred from the character: uppercase letters imply shift and control
+/// characters other than tab, newline, carriage return, backspace, escape and
+/// delete imply control.
+///
+/// # Limitations
+///
+/// The keyboard encoding has no codes for the modifier keys themselves, so
+/// their state cannot be tracked from press and release events and the
+/// inference is only a heuristic:
+///
+/// - Shifted symbols such as `!` report no shift, and letters typed with caps
+///   lock active report shift
+/// - Control is only reported for characters the driver translated into a
+///   control character, and control with tab, enter, backspace or escape is
+///   reported as the plain key
+/// - Special keys, such as arrows and function keys, never report modifiers
+/// - Alt and other modifiers are not reported
+///
+/// Applications needing exact modifier state have to use a driver reporting
+/// raw key codes instead.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Modifiers {
+    /// Shift was held
+    pub shift: bool,
+    /// Control was held
+    pub ctrl: bool,
+}
+
+impl Modifiers {
+    fn from_char(ch: u8) -> Self {
+        Modifiers {
+            shift: ch.is_ascii_uppercase(),
+            ctrl: ch.is_ascii_control()
+                && !matches!(ch, b'\t' | b'\n' | b'\r' | 0x08 | ASCII_ESC | 0x7f),
+        }
+    }
+}
+
+/// A single key press or release
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct KeyEvent {
+    /// The key that changed
+    pub code: KeyCode,
+    /// true if the key was pressed, false if it was released
+    pub pressed: bool,
+    /// Modifier keys active for the event
+    pub modifiers: Modifiers,
+}
+
+/// Meaning of a key, independent of its encoding
+///
+/// Obtained with [`KeyEvent::key`]. Navigation and editing keys are reported
+/// the same whether the driver encodes them as a control character or as a
+/// special key, e.g. backspace as `0x08` or `KEYCODE_BACKDEL`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    /// A printable character: a letter, digit, punctuation or space. Letters
+    /// typed with control held are reported as the lowercase letter, with
+    /// [`Modifiers::ctrl`] set.
+    Char(u8),
+    /// Enter or return
+    Enter,
+    /// Tab
+    Tab,
+    /// Backspace, deleting backwards
+    Backspace,
+    /// Delete, deleting forwards
+    Delete,
+    /// Escape
+    Escape,
+    /// Left arrow
+    Left,
+    /// Right arrow
+    Right,
+    /// Up arrow
+    Up,
+    /// Down arrow
+    Down,
+    /// Home
+    Home,
+    /// End
+    End,
+    /// Page up
+    PageUp,
+    /// Page down
+    PageDown,
+    /// Insert
+    Insert,
+    /// Function key F1 to F24, holding its number
+    Function(u8),
+    /// Caps lock, including the locking variant
+    CapsLock,
+    /// Num lock, including the locking variant
+    NumLock,
+    /// Scroll lock, including the locking variant
+    ScrollLock,
+    /// Menu
+    Menu,
+    /// Pause
+    Pause,
+    /// Print screen
+    PrintScreen,
+    /// Any other key, with its raw code
+    Other(KeyCode),
+}
+
+impl KeyEvent {
+    /// Translates the raw key code into a [`Key`]
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use core::ffi::CStr;
+    /// use nuttx::input::keyboard::{Key, KeyEvent, Keyboard};
+    ///
+    /// let mut kbd = Keyboard::open_blocking(CStr::from_bytes_with_nul(b"/dev/kbd0\0").unwrap()).unwrap();
+    /// let mut events = [KeyEvent::default(); 8];
+    /// let count = kbd.read_events(&mut events).unwrap();
+    ///
+    /// for event in events[..count].iter().filter(|event| event.pressed) {
+    ///     match event.key() {
+    ///         Key::Up => { /* Select the previous menu entry */ }
+    ///         Key::Down => { /* Select the next menu entry */ }
+    ///         Key::Enter => { /* Activate the entry */ }
+    ///         _ => {}
+    ///     }
+    /// }
+    /// ```
+    pub fn key(&self) -> Key {
+        match self.code {
+            KeyCode::Char(ch) => Key::from_char(ch),
+            KeyCode::Special(code) => Key::from_special(code),
+        }
+    }
+
+    fn char(ch: u8, pressed: bool) -> Self {
+        KeyEvent {
+            code: KeyCode::Char(ch),
+            pressed,
+            modifiers: Modifiers::from_char(ch),
+        }
+    }
+
+    fn special(code: u8, pressed: bool) -> Self {
+        KeyEvent {
+            code: KeyCode::Special(code),
+            pressed,
+            modifiers: Modifiers::default(),
+        }
+    }
+}
+
+impl Key {
+    fn from_char(ch: u8) -> Self {
+        match ch {
+            b'\r' | b'\n' => Key::Enter,
+            b'\t' => Key::Tab,
+            0x08 => Key::Backspace,
+            0x7f => Key::Delete,
+            ASCII_ESC => Key::Escape,
+            // Control with a letter produces the letter's position in the alphabet
+            0x01..=0x1a => Key::Char(b'a' + ch - 1),
+            b' '..=b'~' => Key::Char(ch),
+            _ => Key::Other(KeyCode::Char(ch)),
+        }
+    }
+
+    fn from_special(code: u8) -> Self {
+        const F1: u8 = bindings::kbd_keycode_e_KEYCODE_F1 as u8;
+        const F24: u8 = bindings::kbd_keycode_e_KEYCODE_F24 as u8;
+
+        match code as bindings::kbd_keycode_e {
+            bindings::kbd_keycode_e_KEYCODE_ENTER => Key::Enter,
+            bindings::kbd_keycode_e_KEYCODE_BACKDEL => Key::Backspace,
+            bindings::kbd_keycode_e_KEYCODE_FWDDEL => Key::Delete,
+            bindings::kbd_keycode_e_KEYCODE_LEFT => Key::Left,
+            bindings::kbd_keycode_e_KEYCODE_RIGHT => Key::Right,
+            bindings::kbd_keycode_e_KEYCODE_UP => Key::Up,
+            bindings::kbd_keycode_e_KEYCODE_DOWN => Key::Down,
+            bindings::kbd_keycode_e_KEYCODE_HOME => Key::Home,
+            bindings::kbd_keycode_e_KEYCODE_END => Key::End,
+            bindings::kbd_keycode_e_KEYCODE_PAGEUP => Key::PageUp,
+            bindings::kbd_keycode_e_KEYCODE_PAGEDOWN => Key::PageDown,
+            bindings::kbd_keycode_e_KEYCODE_INSERT => Key::Insert,
+            bindings::kbd_keycode_e_KEYCODE_CAPSLOCK
+            | bindings::kbd_keycode_e_KEYCODE_LCAPSLOCK => Key::CapsLock,
+            bindings::kbd_keycode_e_KEYCODE_NUMLOCK | bindings::kbd_keycode_e_KEYCODE_LNUMLOCK => {
+                Key::NumLock
+            }
+            bindings::kbd_keycode_e_KEYCODE_SCROLLLOCK
+            | bindings::kbd_keycode_e_KEYCODE_LSCROLLLOCK => Key::ScrollLock,
+            bindings::kbd_keycode_e_KEYCODE_MENU => Key::Menu,
+            bindings::kbd_keycode_e_KEYCODE_PAUSE => Key::Pause,
+            bindings::kbd_keycode_e_KEYCODE_PRTSCRN => Key::PrintScreen,
+            _ if (F1..=F24).contains(&code) => Key::Function(code - F1 + 1),
+            _ => Key::Other(KeyCode::Special(code)),
+        }
+    }
+}
+
+/// Represents an open connection to a keyboard input device
+///
+/// Encoded sequences split across reads are buffered until they are complete.
+/// As a consequence a lone escape key press is only reported once the next
+/// byte arrives. The underlying device is closed when the Keyboard is dropped.
+#[derive(Debug)]
+pub struct Keyboard {
+    fd: c_int,
+    raw: [u8; RAW_BUFFER_SIZE],
+    start: usize,
+    end: usize,
+}
+
+impl Keyboard {
+    /// Opens a keyboard device at the specified path in non-blocking mode
+    ///
+    /// # Arguments
+    /// * `path` - Path to the keyboard device as a C string (e.g. "/dev/kbd0")
+    ///
+    /// # Returns
+    /// - Ok(Keyboard) on success
+    /// - Err(InputError::OpenFailed) with the negative errno if the device could
+    ///   not be opened
+    pub fn open(path: &CStr) -> Result<Self, InputError> {
+        Self::open_with_flags(path, O_RDONLY | O_NONBLOCK)
+    }
+
+    /// Opens a keyboard device at the specified path in blocking mode
+    ///
+    /// Unlike [`Keyboard::open`], reads on the returned device wait until a key
+    /// event is available instead of failing with `WouldBlock`.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the keyboard device as a C string (e.g. "/dev/kbd0")
+    ///
+    /// # Returns
+    /// - Ok(Keyboard) on success
+    /// - Err(InputError::OpenFailed) with the negative errno if the device could
+    ///   not be opened
+    pub fn open_blocking(path: &CStr) -> Result<Self, InputError> {
+        Self::open_with_flags(path, O_RDONLY)
+    }
+
+    fn open_with_flags(path: &CStr, flags: c_int) -> Result<Self, InputError> {
+        let fd = unsafe { open(path.as_ptr(), flags) };
+        if fd < 0 {
+            return Err(InputError::OpenFailed(errno()));
+        }
+
+        Ok(Keyboard {
+            fd,
+            raw: [0; RAW_BUFFER_SIZE],
+            start: 0,
+            end: 0,
+        })
+    }
+
+    /// Reads and decodes key events from the device
+    ///
+    /// Events left over from a previous read are returned first, the device is
+    /// only read when none are pending.
+    ///
+    /// # Arguments
+    /// * `buf` - Buffer receiving the decoded events
+    ///
+    /// # Returns
+    /// - Ok(usize) with the number of events stored in `buf`, 0 at end of file
+    /// - Err(InputError::WouldBlock) if the device is non-blocking and no key
+    ///   event is available
+    /// - Err(InputError) if the read fails
+    pub fn read_events(&mut self, buf: &mut [KeyEvent]) -> Result<usize, InputError> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        loop {
+            let count = self.decode(buf);
+            if count > 0 {
+                return Ok(count);
+            }
+
+            // Keep the incomplete sequence and make room for the rest of it
+            self.raw.copy_within(self.start..self.end, 0);
+            self.end -= self.start;
+            self.start = 0;
+
+            let ret = unsafe {
+                read(
+                    self.fd,
+                    self.raw[self.end..].as_mut_ptr() as *mut c_void,
+                    RAW_BUFFER_SIZE - self.end,
+                )
+            };
+
+            if ret < 0 {
+                return Err(InputError::read(errno()));
+            }
+            if ret == 0 {
+                return Ok(0);
+            }
+            self.end += ret as usize;
+        }
+    }
+
+    /// Decodes buffered bytes into `buf`, returning the number of events
+    fn decode(&mut self, buf: &mut [KeyEvent]) -> usize {
+        let mut count = 0;
+
+        while count < buf.len() {
+            let Some((event, len)) = Self::decode_one(&self.raw[self.start..self.end]) else {
+                break;
+            };
+
+            buf[count] = event;
+            count += 1;
+            self.start += len;
+        }
+        count
+    }
+
+    /// Decodes the event at the start of `bytes`
+    ///
+    /// Returns the event and the number of bytes it occupies, or None if
+    /// `bytes` is empty or holds an incomplete sequence.
+    fn decode_one(bytes: &[u8]) -> Option<(KeyEvent, usize)> {
+        let (&first, rest) = bytes.split_first()?;
+        if first != ASCII_ESC {
+            return Some((KeyEvent::char(first, true), 1));
+        }
+
+        match rest {
+            [] => None,
+            [b'[', ..] if bytes.len() < SEQUENCE_LEN => None,
+            [b'[', code, term, ..] => match *term {
+                TERM_RELEASE => Some((KeyEvent::char(*code, false), SEQUENCE_LEN)),
+                TERM_SPECPRESS => Some((KeyEvent::special(*code, true), SEQUENCE_LEN)),
+                TERM_SPECREL => Some((KeyEvent::special(*code, false), SEQUENCE_LEN)),
+                _ => Some((KeyEvent::char(ASCII_ESC, true), 1)),
+            },
+            // Not an encoded sequence, the escape key itself was pressed
+            _ => Some((KeyEvent::char(ASCII_ESC, true), 1)),
+        }
+    }
+}
+
+impl AsRawFd for Keyboard {
+    /// Returns the file descriptor of the keyboard device, e.g. for use in an
+    /// external poll loop. The descriptor stays owned by the Keyboard.
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd
+    }
+}
+
+impl Drop for Keyboard {
+    /// Closes the keyboard device when the Keyboard goes out of scope
+    fn drop(&mut self) {
+        unsafe { libc::close(self.fd) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEYCODE_UP: u8 = bindings::kbd_keycode_e_KEYCODE_UP as u8;
+    const KEYCODE_F1: u8 = bindings::kbd_keycode_e_KEYCODE_F1 as u8;
+
+    #[test]
+    fn decode_characters() {
+        assert_eq!(
+            Keyboard::decode_one(b"ab"),
+            Some((KeyEvent::char(b'a', true), 1))
+        );
+        assert_eq!(
+            Keyboard::decode_one(&[ASCII_ESC, b'[', b'a', TERM_RELEASE]),
+            Some((KeyEvent::char(b'a', false), SEQUENCE_LEN))
+        );
+        assert_eq!(Keyboard::decode_one(b""), None);
+    }
+
+    #[test]
+    fn decode_special_keys() {
+        let press = [ASCII_ESC, b'[', KEYCODE_UP, TERM_SPECPRESS];
+        let (event, len) = Keyboard::decode_one(&press).unwrap();
+        assert_eq!(event, KeyEvent::special(KEYCODE_UP, true));
+        assert_eq!((event.key(), len), (Key::Up, SEQUENCE_LEN));
+
+        let release = [ASCII_ESC, b'[', KEYCODE_F1, TERM_SPECREL, b'x'];
+        let (event, len) = Keyboard::decode_one(&release).unwrap();
+        assert_eq!(event, KeyEvent::special(KEYCODE_F1, false));
+        assert_eq!((event.key(), len), (Key::Function(1), SEQUENCE_LEN));
+    }
+
+    #[test]
+    fn decode_truncated_sequences() {
+        // Incomplete sequences wait for the rest of their bytes
+        let press = [ASCII_ESC, b'[', KEYCODE_UP, TERM_SPECPRESS];
+        for len in 1..SEQUENCE_LEN {
+            assert_eq!(Keyboard::decode_one(&press[..len]), None, "{len} bytes");
+        }
+
+        // An escape not followed by a sequence is the escape key itself
+        let escape = Some((KeyEvent::char(ASCII_ESC, true), 1));
+        assert_eq!(Keyboard::decode_one(&[ASCII_ESC, b'a']), escape);
+        assert_eq!(Keyboard::decode_one(&[ASCII_ESC, ASCII_ESC]), escape);
+        assert_eq!(Keyboard::decode_one(&[ASCII_ESC, b'[', b'a', b'z']), escape);
+    }
+
+    #[test]
+    fn key_from_char() {
+        assert_eq!(Key::from_char(b'\r'), Key::Enter);
+        assert_eq!(Key::from_char(b'\n'), Key::Enter);
+        assert_eq!(Key::from_char(b'\t'), Key::Tab);
+        assert_eq!(Key::from_char(0x08), Key::Backspace);
+        assert_eq!(Key::from_char(0x7f), Key::Delete);
+        assert_eq!(Key::from_char(ASCII_ESC), Key::Escape);
+        assert_eq!(Key::from_char(b' '), Key::Char(b' '));
+        assert_eq!(Key::from_char(b'Q'), Key::Char(b'Q'));
+        assert_eq!(Key::from_char(b'~'), Key::Char(b'~'));
+
+        // Control letters are reported as the lowercase letter
+        assert_eq!(Key::from_char(0x01), Key::Char(b'a'));
+        assert_eq!(Key::from_char(0x1a), Key::Char(b'z'));
+        assert_eq!(Key::from_char(0x1c), Key::Other(KeyCode::Char(0x1c)));
+        assert_eq!(Key::from_char(0x80), Key::Other(KeyCode::Char(0x80)));
+    }
+
+    #[test]
+    fn key_from_special() {
+        let key = |code: bindings::kbd_keycode_e| Key::from_special(code as u8);
+
+        assert_eq!(key(bindings::kbd_keycode_e_KEYCODE_ENTER), Key::Enter);
+        assert_eq!(key(bindings::kbd_keycode_e_KEYCODE_BACKDEL), Key::Backspace);
+        assert_eq!(key(bindings::kbd_keycode_e_KEYCODE_FWDDEL), Key::Delete);
+        assert_eq!(key(bindings::kbd_keycode_e_KEYCODE_PAGEDOWN), Key::PageDown);
+        assert_eq!(
+            key(bindings::kbd_keycode_e_KEYCODE_LCAPSLOCK),
+            Key::CapsLock
+        );
+        assert_eq!(key(bindings::kbd_keycode_e_KEYCODE_F1), Key::Function(1));
+        assert_eq!(key(bindings::kbd_keycode_e_KEYCODE_F24), Key::Function(24));
+
+        let power = bindings::kbd_keycode_e_KEYCODE_POWER as u8;
+        assert_eq!(
+            Key::from_special(power),
+            Key::Other(KeyCode::Special(power))
+        );
+    }
+
+    #[test]
+    fn modifiers_inferred_from_characters() {
+        let modifiers = |ch| KeyEvent::char(ch, true).modifiers;
+        let shift = Modifiers {
+            shift: true,
+            ctrl: false,
+        };
+        let ctrl = Modifiers {
+            shift: false,
+            ctrl: true,
+        };
+
+        assert_eq!(modifiers(b'a'), Modifiers::default());
+        assert_eq!(modifiers(b'A'), shift);
+        assert_eq!(modifiers(0x03), ctrl);
+        assert_eq!(KeyEvent::char(0x03, true).key(), Key::Char(b'c'));
+
+        // Releases infer the same modifiers as presses
+        assert_eq!(KeyEvent::char(b'A', false).modifiers, shift);
+    }
+
+    #[test]
+    fn modifiers_limitations() {
+        let modifiers = |ch| KeyEvent::char(ch, true).modifiers;
+
+        // Shifted symbols and digits carry no shift
+        assert_eq!(modifiers(b'!'), Modifiers::default());
+        assert_eq!(modifiers(b'@'), Modifiers::default());
+
+        // Control characters that have a key of their own carry no control
+        for ch in [b'\t', b'\n', b'\r', 0x08, ASCII_ESC, 0x7f] {
+            assert_eq!(modifiers(ch), Modifiers::default(), "{ch:#x}");
+        }
+
+        // Special keys never carry modifiers
+        let up = bindings::kbd_keycode_e_KEYCODE_UP as u8;
+        assert_eq!(KeyEvent::special(up, true).modifiers, Modifiers::default());
+    }
+}
@@ -1 +1,14 @@
+pub mod buttons;
+#[cfg(feature = "channel")]
+pub mod channel;
+pub mod error;
+pub mod gesture;
+pub mod joystick;
+pub mod keyboard;
+pub mod poller;
 pub mod touchscreen;
+
+#[cfg(all(feature = "channel", feature = "std"))]
+pub use channel::TouchChannel;
+pub use error::InputError;
+pub use poller::{PollEvents, Poller};
@@ -0,0 +1,175 @@
+//! Waiting on several input devices at once
+//!
+//! This module provides a [`Poller`] that waits until any of a set of input
+//! devices, such as a touchscreen and buttons, has data to be read. It is
+//! built on `poll` and accepts any device implementing [`AsRawFd`].
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use core::ffi::CStr;
+//! use nuttx::input::Poller;
+//! use nuttx::input::buttons::Buttons;
+//! use nuttx::input::touchscreen::TouchScreen;
+//!
+//! let mut touch = TouchScreen::open(CStr::from_bytes_with_nul(b"/dev/input0\0").unwrap()).unwrap();
+//! let buttons = Buttons::open(CStr::from_bytes_with_nul(b"/dev/buttons\0").unwrap()).unwrap();
+//!
+//! let mut poller = Poller::new();
+//! let touch_id = poller.add(&touch).unwrap();
+//! let buttons_id = poller.add(&buttons).unwrap();
+//!
+//! let events = poller.wait(None).unwrap();
+//! if events.is_readable(touch_id) {
+//!     let sample = touch.read_sample().unwrap();
+//! }
+//! if events.is_readable(buttons_id) {
+//!     let state = buttons.read_state().unwrap();
+//! }
+//! ```
+//!
+
+use core::time::Duration;
+use libc::{POLLERR, POLLHUP, POLLIN, POLLNVAL, c_int, nfds_t, poll, pollfd};
+
+use crate::errno::errno_result;
+use crate::fd::AsRawFd;
+use crate::input::InputError;
+
+/// Maximum number of sources a [`Poller`] can wait on
+pub const MAX_SOURCES: usize = 8;
+
+/// Set of sources found readable by [`Poller::wait`]
+///
+/// Sources are identified by the index returned from [`Poller::add`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PollEvents {
+    readable: u32,
+}
+
+impl PollEvents {
+    /// Returns true if the source with the given index is readable
+    pub fn is_readable(&self, index: usize) -> bool {
+        index < MAX_SOURCES && self.readable & (1 << index) != 0
+    }
+
+    /// Returns true if no source is readable, i.e. the wait timed out
+    pub fn is_empty(&self) -> bool {
+        self.readable == 0
+    }
+
+    /// Iterates over the indices of the readable sources
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..MAX_SOURCES).filter(|&index| self.is_readable(index))
+    }
+}
+
+/// Waits until any of several input devices is readable
+///
+/// Only the descriptors of the registered devices are kept, so the devices can
+/// still be read mutably between waits. Devices must stay open while they are
+/// registered, a closed descriptor is reported as readable by every wait.
+///
+/// Only the descriptors are polled, so data a device has already received
+/// is not reported. A `TouchScreen` may keep samples from an earlier read,
+/// drain it with `TouchScreen::poll_sample` until it returns None before
+/// waiting again.
+#[derive(Debug)]
+pub struct Poller {
+    fds: [pollfd; MAX_SOURCES],
+    len: usize,
+}
+
+impl Poller {
+    /// Creates a Poller without any source
+    pub fn new() -> Self {
+        let fd = pollfd {
+            fd: -1,
+            events: 0,
+            revents: 0,
+        };
+
+        Poller {
+            fds: [fd; MAX_SOURCES],
+            len: 0,
+        }
+    }
+
+    /// Registers an input device to wait on
+    ///
+    /// # Arguments
+    /// * `source` - The device, e.g. a `TouchScreen` or `Buttons`
+    ///
+    /// # Returns
+    /// - Ok(usize) with the index identifying the source in [`PollEvents`]
+    /// - Err(InputError::Io) with `-ENOSPC` if [`MAX_SOURCES`] sources are
+    ///   already registered
+    pub fn add(&mut self, source: &dyn AsRawFd) -> Result<usize, InputError> {
+        if self.len == MAX_SOURCES {
+            return Err(InputError::Io(-libc::ENOSPC));
+        }
+
+        let index = self.len;
+        self.fds[index] = pollfd {
+            fd: source.as_raw_fd(),
+            events: POLLIN,
+            revents: 0,
+        };
+        self.len += 1;
+        Ok(index)
+    }
+
+    /// Number of registered sources
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if no source is registered
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Waits until at least one source is readable
+    ///
+    /// A source that reports an error, a hang-up or an invalid descriptor is
+    /// returned as readable, so the error is reported by its next read.
+    ///
+    /// # Arguments
+    /// * `timeout` - Maximum time to wait, or None to wait indefinitely. Timeouts
+    ///   are rounded up to whole milliseconds.
+    ///
+    /// # Returns
+    /// - Ok(PollEvents) with the readable sources, empty if the timeout expired
+    /// - Err(InputError) if polling failed
+    ///
+    /// # Errors
+    /// Returns `InputError::Interrupted` if the wait was interrupted by a signal,
+    /// in which case the wait can be retried.
+    pub fn wait(&mut self, timeout: Option<Duration>) -> Result<PollEvents, InputError> {
+        let timeout_ms = match timeout {
+            None => -1,
+            Some(timeout) => {
+                let ms = timeout.as_nanos().div_ceil(1_000_000);
+                ms.min(c_int::MAX as u128) as c_int
+            }
+        };
+
+        let fds = &mut self.fds[..self.len];
+        errno_result(unsafe { poll(fds.as_mut_ptr(), fds.len() as nfds_t, timeout_ms) })
+            .map_err(InputError::io)?;
+
+        let readable = fds
+            .iter()
+            .enumerate()
+            .filter(|(_, fd)| fd.revents & (POLLIN | POLLERR | POLLHUP | POLLNVAL) != 0)
+            .fold(0, |mask, (index, _)| mask | 1 << index);
+        Ok(PollEvents { readable })
+    }
+}
+
+impl Default for Poller {
+    /// Creates a Poller without any source
+    fn default() -> Self {
+        Self::new()
+    }
+}
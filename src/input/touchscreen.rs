@@ -7,13 +7,34 @@
 //! `nuttx/include/nuttx/input/touchscreen.h`.
 //!
 
+use core::cmp::Ordering;
 use core::ffi::CStr;
-use core::mem::size_of;
-use libc::{O_NONBLOCK, O_RDONLY, c_int, c_void, open, read};
+use core::mem::{offset_of, size_of, size_of_val};
+use core::ops::{BitAnd, BitOr, BitOrAssign};
+#[cfg(feature = "async")]
+use core::task::Poll;
+use core::time::Duration;
+#[cfg(not(feature = "mock"))]
+use libc::{EINVAL, ENOSYS, ENOTTY, POLLIN, c_void, getpid, poll, pollfd, read};
+use libc::{ENOTSUP, F_GETFL, F_SETFL, O_NONBLOCK, O_RDONLY, c_int, fcntl, open};
 
-use crate::bindings::{
+use crate::bindings::{touch_point_s, touch_sample_s};
+#[cfg(not(feature = "mock"))]
+use crate::errno::errno;
+use crate::errno::errno_result;
+use crate::fd::{AsRawFd, DeviceFd, RawFd, adopt};
+use crate::input::InputError;
+
+// Re-export the touch point flag constants, see TouchFlags for a typed set
+pub use crate::bindings::{
     TOUCH_DOWN, TOUCH_GESTURE_VALID, TOUCH_ID_VALID, TOUCH_MOVE, TOUCH_POS_VALID,
-    TOUCH_PRESSURE_VALID, TOUCH_SIZE_VALID, TOUCH_UP, touch_point_s, touch_sample_s,
+    TOUCH_PRESSURE_VALID, TOUCH_SIZE_VALID, TOUCH_UP,
+};
+
+// Re-export the gesture codes, see Gesture for a typed value
+pub use crate::bindings::{
+    TOUCH_DOUBLE_CLICK, TOUCH_PALM, TOUCH_SLIDE_DOWN, TOUCH_SLIDE_LEFT, TOUCH_SLIDE_RIGHT,
+    TOUCH_SLIDE_UP,
 };
 
 /// Represents a single touch point with position, size, pressure and timing information
@@ -23,6 +44,10 @@ use crate::bindings::{
 /// until the touch is released.
 pub type TouchPoint = touch_point_s;
 
+/// Coordinate or extent of a touch point, matching the `int16_t` fields of
+/// `touch_point_s`
+pub type Coord = i16;
+
 /// Contains a set of touch points from a single touch event
 ///
 /// This matches the C `touch_sample_s` structure from NuttX's touchscreen.h.
@@ -30,12 +55,314 @@ pub type TouchPoint = touch_point_s;
 /// points stored in the `point` array.
 pub type TouchSample = touch_sample_s;
 
+/// Gesture reported by the touchscreen hardware
+///
+/// Mirrors the `TOUCH_*` gesture codes from NuttX's touchscreen.h, which are
+/// all covered. NuttX defines no codes for diagonal slides, zooming or other
+/// multi-finger gestures, so codes reported for them by a vendor driver, like
+/// any other undefined code, are preserved as `Unknown` and convert back to
+/// the same `u16`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Gesture {
+    /// TOUCH_DOUBLE_CLICK
+    DoubleClick,
+    /// TOUCH_SLIDE_UP
+    SlideUp,
+    /// TOUCH_SLIDE_DOWN
+    SlideDown,
+    /// TOUCH_SLIDE_LEFT
+    SlideLeft,
+    /// TOUCH_SLIDE_RIGHT
+    SlideRight,
+    /// TOUCH_PALM, the panel was covered by a palm
+    Palm,
+    /// A gesture code without a NuttX definition
+    Unknown(u16),
+}
+
+impl From<u16> for Gesture {
+    /// Decodes a raw gesture code as stored in `TouchPoint::gesture`
+    fn from(code: u16) -> Self {
+        match code as u32 {
+            TOUCH_DOUBLE_CLICK => Gesture::DoubleClick,
+            TOUCH_SLIDE_UP => Gesture::SlideUp,
+            TOUCH_SLIDE_DOWN => Gesture::SlideDown,
+            TOUCH_SLIDE_LEFT => Gesture::SlideLeft,
+            TOUCH_SLIDE_RIGHT => Gesture::SlideRight,
+            TOUCH_PALM => Gesture::Palm,
+            _ => Gesture::Unknown(code),
+        }
+    }
+}
+
+impl From<Gesture> for u16 {
+    /// Encodes a gesture as the raw code stored in `TouchPoint::gesture`
+    fn from(gesture: Gesture) -> Self {
+        let code = match gesture {
+            Gesture::DoubleClick => TOUCH_DOUBLE_CLICK,
+            Gesture::SlideUp => TOUCH_SLIDE_UP,
+            Gesture::SlideDown => TOUCH_SLIDE_DOWN,
+            Gesture::SlideLeft => TOUCH_SLIDE_LEFT,
+            Gesture::SlideRight => TOUCH_SLIDE_RIGHT,
+            Gesture::Palm => TOUCH_PALM,
+            Gesture::Unknown(code) => return code,
+        };
+        code as u16
+    }
+}
+
+/// Set of `TOUCH_*` flags describing a touch point
+///
+/// Wraps the `flags` field of [`TouchPoint`], so several flags can be tested
+/// at once.
+///
+/// # Examples
+///
+/// ```no_run
+/// use nuttx::input::touchscreen::{TouchFlags, TouchPoint};
+///
+/// let point = TouchPoint::default();
+/// if point.flags_typed().contains(TouchFlags::DOWN | TouchFlags::POS_VALID) {
+///     // A new contact at a known position
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct TouchFlags(u8);
+
+impl TouchFlags {
+    /// TOUCH_DOWN, a new touch contact
+    pub const DOWN: TouchFlags = TouchFlags(TOUCH_DOWN as u8);
+    /// TOUCH_MOVE, movement of a previously reported contact
+    pub const MOVE: TouchFlags = TouchFlags(TOUCH_MOVE as u8);
+    /// TOUCH_UP, the touch contact was lost
+    pub const UP: TouchFlags = TouchFlags(TOUCH_UP as u8);
+    /// TOUCH_ID_VALID, the touch ID is certain
+    pub const ID_VALID: TouchFlags = TouchFlags(TOUCH_ID_VALID as u8);
+    /// TOUCH_POS_VALID, the x/y coordinates are valid
+    pub const POS_VALID: TouchFlags = TouchFlags(TOUCH_POS_VALID as u8);
+    /// TOUCH_PRESSURE_VALID, the pressure value is valid
+    pub const PRESSURE_VALID: TouchFlags = TouchFlags(TOUCH_PRESSURE_VALID as u8);
+    /// TOUCH_SIZE_VALID, the width/height values are valid
+    pub const SIZE_VALID: TouchFlags = TouchFlags(TOUCH_SIZE_VALID as u8);
+    /// TOUCH_GESTURE_VALID, the gesture value is valid
+    pub const GESTURE_VALID: TouchFlags = TouchFlags(TOUCH_GESTURE_VALID as u8);
+
+    /// Creates a set without any flag
+    pub const fn empty() -> Self {
+        TouchFlags(0)
+    }
+
+    /// Creates a set from the raw `flags` of a touch point
+    ///
+    /// Bits without a named flag are kept.
+    pub const fn from_bits(bits: u8) -> Self {
+        TouchFlags(bits)
+    }
+
+    /// The raw value, as stored in `TouchPoint::flags`
+    pub const fn bits(&self) -> u8 {
+        self.0
+    }
+
+    /// Returns true if no flag is set
+    pub const fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns true if all flags of `other` are set
+    pub const fn contains(&self, other: TouchFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns true if any flag of `other` is set
+    pub const fn intersects(&self, other: TouchFlags) -> bool {
+        self.0 & other.0 != 0
+    }
+}
+
+impl BitOr for TouchFlags {
+    type Output = TouchFlags;
+
+    /// The flags set in either set
+    fn bitor(self, other: TouchFlags) -> TouchFlags {
+        TouchFlags(self.0 | other.0)
+    }
+}
+
+impl BitOrAssign for TouchFlags {
+    fn bitor_assign(&mut self, other: TouchFlags) {
+        self.0 |= other.0;
+    }
+}
+
+impl BitAnd for TouchFlags {
+    type Output = TouchFlags;
+
+    /// The flags set in both sets
+    fn bitand(self, other: TouchFlags) -> TouchFlags {
+        TouchFlags(self.0 & other.0)
+    }
+}
+
+/// fcntl command to set the task receiving the I/O signal of a descriptor
+///
+/// Matches C's F_SETOWN
+#[cfg(not(feature = "mock"))]
+const F_SETOWN: c_int = crate::bindings::F_SETOWN as c_int;
+
+/// fcntl command to set the signal raised when a descriptor becomes ready
+///
+/// Matches C's F_SETSIG
+#[cfg(not(feature = "mock"))]
+const F_SETSIG: c_int = crate::bindings::F_SETSIG as c_int;
+
+/// Maximum number of touch points that [`TouchScreen::read_points`] can receive
+/// from the driver in a single sample
+pub const MAX_TOUCH_POINTS: usize = 10;
+
+/// Size of a single-point sample as delivered by the driver
+const SAMPLE_SIZE: usize = size_of::<TouchSample>();
+
+/// Variable-length touch sample as delivered by multi-touch drivers
+///
+/// Shares the layout of `touch_sample_s`, with room for [`MAX_TOUCH_POINTS`]
+/// trailing points instead of one.
+#[repr(C)]
+struct MultiTouchSample {
+    npoints: c_int,
+    point: [TouchPoint; MAX_TOUCH_POINTS],
+}
+
+/// Size of the `npoints` header preceding the points of a sample
+const HEADER_LEN: usize = offset_of!(TouchSample, point);
+
+/// Capacity of the bytes kept between reads
+///
+/// Holds the largest sample twice, so a read into the free space left beside
+/// a partial sample can always complete it.
+const PENDING_SIZE: usize = 2 * size_of::<MultiTouchSample>();
+
+/// Size of a sample with `npoints` points as delivered by the driver
+///
+/// Matches C's SIZEOF_TOUCH_SAMPLE_S, which counts at least one point. Counts
+/// above [`MAX_TOUCH_POINTS`] are clamped, as no more points are received.
+fn sample_size(npoints: c_int) -> usize {
+    HEADER_LEN + npoints.clamp(1, MAX_TOUCH_POINTS as c_int) as usize * size_of::<TouchPoint>()
+}
+
+/// Size of the first sample in `bytes`, if they hold all of it
+fn first_sample_size(bytes: &[u8]) -> Option<usize> {
+    let header = bytes.get(..size_of::<c_int>())?;
+    let size = sample_size(c_int::from_ne_bytes(header.try_into().ok()?));
+    (bytes.len() >= size).then_some(size)
+}
+
+/// The first `npoints` of `points`, see [`TouchSample::points`]
+fn reported_points(npoints: c_int, points: &[TouchPoint]) -> impl Iterator<Item = &TouchPoint> {
+    points.iter().take(npoints.max(0) as usize)
+}
+
+/// The reported points that are in contact, see [`TouchSample::active_points`]
+fn active_points(npoints: c_int, points: &[TouchPoint]) -> impl Iterator<Item = &TouchPoint> {
+    reported_points(npoints, points).filter(|point| point.is_touch_down() || point.is_touch_move())
+}
+
+/// Views a sample structure as the bytes written by the driver
+///
+/// # Safety
+/// `T` must be a plain C structure valid for any byte values, such as
+/// [`TouchSample`] or [`MultiTouchSample`].
+unsafe fn sample_bytes<T>(sample: &mut T) -> &mut [u8] {
+    unsafe { core::slice::from_raw_parts_mut(sample as *mut T as *mut u8, size_of::<T>()) }
+}
+
+/// Calibration mapping raw touchscreen coordinates to display pixels
+///
+/// Each axis is transformed as `offset + scale * raw`, or `offset - scale * raw`
+/// when the axis is inverted, so an inverted axis typically uses the display
+/// width or height minus one as its offset. When `swap_xy` is set the raw x and y
+/// coordinates are exchanged before the transform is applied.
+///
+/// The default calibration is the identity transform.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Calibration {
+    /// Scale factor applied to the x coordinate
+    pub x_scale: f32,
+    /// Offset added to the x coordinate after scaling
+    pub x_offset: f32,
+    /// Scale factor applied to the y coordinate
+    pub y_scale: f32,
+    /// Offset added to the y coordinate after scaling
+    pub y_offset: f32,
+    /// Exchange the raw x and y coordinates
+    pub swap_xy: bool,
+    /// Mirror the x axis around `x_offset`
+    pub invert_x: bool,
+    /// Mirror the y axis around `y_offset`
+    pub invert_y: bool,
+}
+
+impl Default for Calibration {
+    /// Creates an identity calibration that leaves coordinates unchanged
+    fn default() -> Self {
+        Self {
+            x_scale: 1.0,
+            x_offset: 0.0,
+            y_scale: 1.0,
+            y_offset: 0.0,
+            swap_xy: false,
+            invert_x: false,
+            invert_y: false,
+        }
+    }
+}
+
+impl Calibration {
+    /// Applies the calibration to the x/y coordinates of a touch point
+    ///
+    /// Results outside the coordinate range are saturated.
+    pub fn apply(&self, point: &mut TouchPoint) {
+        let (raw_x, raw_y) = if self.swap_xy {
+            (point.y, point.x)
+        } else {
+            (point.x, point.y)
+        };
+
+        point.x = Self::transform(raw_x as f32, self.x_scale, self.x_offset, self.invert_x) as _;
+        point.y = Self::transform(raw_y as f32, self.y_scale, self.y_offset, self.invert_y) as _;
+    }
+
+    fn transform(raw: f32, scale: f32, offset: f32, invert: bool) -> f32 {
+        if invert {
+            offset - scale * raw
+        } else {
+            offset + scale * raw
+        }
+    }
+}
+
 /// Represents an open connection to a touchscreen input device
 ///
 /// Provides methods to read touch events and query touch state.
-/// The device is opened in non-blocking mode by default.
+/// The device is opened in non-blocking mode by default, use
+/// [`TouchScreen::open_blocking`] or [`TouchScreen::set_nonblocking`]
+/// to wait for touch events instead.
+///
+/// # Thread safety
+///
+/// A TouchScreen is `Send` and `Sync`. Reading requires `&mut self`, so only
+/// one thread consumes the touch events. Methods taking `&self`, such as
+/// [`TouchScreen::wait_readable`], only query the descriptor and can be
+/// called from several threads.
 pub struct TouchScreen {
-    fd: c_int,
+    fd: DeviceFd,
+    calibration: Option<Calibration>,
+    /// Received bytes not yet returned, holding whole samples or the start of
+    /// one whose read was cut short, see [`TouchScreen::read_samples`]
+    pending: [u8; PENDING_SIZE],
+    pending_len: usize,
+    #[cfg(feature = "mock")]
+    mock: MockQueue,
 }
 
 impl Default for TouchPoint {
@@ -56,13 +383,18 @@ impl Default for TouchPoint {
 }
 
 impl TouchPoint {
+    /// The flags of this touch point as a [`TouchFlags`] set
+    pub fn flags_typed(&self) -> TouchFlags {
+        TouchFlags::from_bits(self.flags)
+    }
+
     /// Checks if the touch point has valid position data
     ///
     /// # Returns
     /// true if the TOUCH_POS_VALID flag is set, indicating the x/y coordinates
     /// are valid
     pub fn is_pos_valid(&self) -> bool {
-        self.flags & (TOUCH_POS_VALID as u8) != 0
+        self.flags_typed().contains(TouchFlags::POS_VALID)
     }
 
     /// Checks if this touch point represents a new touch down event
@@ -70,7 +402,7 @@ impl TouchPoint {
     /// # Returns
     /// true if the TOUCH_DOWN flag is set, indicating a new touch contact
     pub fn is_touch_down(&self) -> bool {
-        self.flags & (TOUCH_DOWN as u8) != 0
+        self.flags_typed().contains(TouchFlags::DOWN)
     }
 
     /// Checks if this touch point represents a movement event
@@ -78,7 +410,7 @@ impl TouchPoint {
     /// # Returns
     /// true if the TOUCH_MOVE flag is set, indicating movement with previously reported contact
     pub fn is_touch_move(&self) -> bool {
-        self.flags & (TOUCH_MOVE as u8) != 0
+        self.flags_typed().contains(TouchFlags::MOVE)
     }
 
     /// Checks if this touch point represents a touch release event
@@ -86,7 +418,7 @@ impl TouchPoint {
     /// # Returns
     /// true if the TOUCH_UP flag is set, indicating the touch contact was lost
     pub fn is_touch_up(&self) -> bool {
-        self.flags & (TOUCH_UP as u8) != 0
+        self.flags_typed().contains(TouchFlags::UP)
     }
 
     /// Checks if the touch point ID is valid
@@ -94,7 +426,7 @@ impl TouchPoint {
     /// # Returns
     /// true if the TOUCH_ID_VALID flag is set, indicating the touch ID is certain
     pub fn is_id_valid(&self) -> bool {
-        self.flags & (TOUCH_ID_VALID as u8) != 0
+        self.flags_typed().contains(TouchFlags::ID_VALID)
     }
 
     /// Checks if the touch point pressure data is valid
@@ -102,7 +434,7 @@ impl TouchPoint {
     /// # Returns
     /// true if the TOUCH_PRESSURE_VALID flag is set, indicating the pressure value is valid
     pub fn is_pressure_valid(&self) -> bool {
-        self.flags & (TOUCH_PRESSURE_VALID as u8) != 0
+        self.flags_typed().contains(TouchFlags::PRESSURE_VALID)
     }
 
     /// Checks if the touch point size data is valid
@@ -110,7 +442,7 @@ impl TouchPoint {
     /// # Returns
     /// true if the TOUCH_SIZE_VALID flag is set, indicating the width/height values are valid
     pub fn is_size_valid(&self) -> bool {
-        self.flags & (TOUCH_SIZE_VALID as u8) != 0
+        self.flags_typed().contains(TouchFlags::SIZE_VALID)
     }
 
     /// Checks if the touch point gesture data is valid
@@ -118,7 +450,143 @@ impl TouchPoint {
     /// # Returns
     /// true if the TOUCH_GESTURE_VALID flag is set, indicating the gesture value is valid
     pub fn is_gesture_valid(&self) -> bool {
-        self.flags & (TOUCH_GESTURE_VALID as u8) != 0
+        self.flags_typed().contains(TouchFlags::GESTURE_VALID)
+    }
+
+    /// The position of the touch point
+    ///
+    /// # Returns
+    /// - Some((x, y)) if the TOUCH_POS_VALID flag is set
+    /// - None if the `x` and `y` fields hold no valid data
+    pub fn position(&self) -> Option<(Coord, Coord)> {
+        self.is_pos_valid().then_some((self.x, self.y))
+    }
+
+    /// The size of the contact area of the touch point
+    ///
+    /// # Returns
+    /// - Some((w, h)) if the TOUCH_SIZE_VALID flag is set
+    /// - None if the `w` and `h` fields hold no valid data
+    pub fn size(&self) -> Option<(Coord, Coord)> {
+        self.is_size_valid().then_some((self.w, self.h))
+    }
+
+    /// The pressure of the touch point, in driver specific units
+    ///
+    /// # Returns
+    /// - Some(pressure) if the TOUCH_PRESSURE_VALID flag is set
+    /// - None if the `pressure` field holds no valid data
+    pub fn pressure(&self) -> Option<u16> {
+        self.is_pressure_valid().then_some(self.pressure)
+    }
+
+    /// Decodes the gesture reported with this touch point
+    ///
+    /// # Returns
+    /// - Some(Gesture) if the TOUCH_GESTURE_VALID flag is set
+    /// - None if the hardware did not report a gesture
+    pub fn gesture(&self) -> Option<Gesture> {
+        if !self.is_gesture_valid() {
+            return None;
+        }
+
+        Some(Gesture::from(self.gesture))
+    }
+
+    /// Converts the raw timestamp of this touch point into a Duration
+    ///
+    /// Drivers report the timestamp in microseconds.
+    ///
+    /// The result is relative to the same epoch as the raw timestamp, so it is
+    /// mainly useful for computing intervals between touch events.
+    pub fn timestamp_duration(&self) -> Duration {
+        Duration::from_micros(self.timestamp)
+    }
+}
+
+impl TouchSample {
+    /// Returns an iterator over the touch points reported in this sample
+    ///
+    /// Yields exactly `npoints` entries (bounded by the size of the `point`
+    /// array), so stale entries beyond `npoints` are never visited.
+    ///
+    /// As `point` holds a single entry, at most one point is yielded, even if
+    /// a multi-touch driver reports more. Use [`TouchScreen::read_points`] to
+    /// receive all of them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nuttx::input::touchscreen::{TouchPoint, TouchSample};
+    ///
+    /// let mut sample = TouchSample {
+    ///     npoints: 0,
+    ///     point: [TouchPoint::default(); 1],
+    /// };
+    /// assert_eq!(sample.points().count(), 0);
+    ///
+    /// sample.npoints = 2;
+    /// assert_eq!(sample.points().count(), 1);
+    /// ```
+    pub fn points(&self) -> impl Iterator<Item = &TouchPoint> {
+        reported_points(self.npoints, &self.point)
+    }
+
+    /// Returns an iterator over the points of this sample that are in contact
+    ///
+    /// Only points with the TOUCH_DOWN or TOUCH_MOVE flag set are yielded.
+    /// Like [`TouchSample::points`], at most one point is yielded.
+    pub fn active_points(&self) -> impl Iterator<Item = &TouchPoint> {
+        active_points(self.npoints, &self.point)
+    }
+
+    /// Returns true if the sample reports no touch points, i.e. npoints = 0
+    pub fn is_empty(&self) -> bool {
+        self.npoints <= 0
+    }
+
+    /// The latest timestamp among the touch points of this sample
+    ///
+    /// The raw value is returned, see [`TouchPoint::timestamp_duration`] for
+    /// its unit.
+    ///
+    /// # Returns
+    /// The largest `timestamp` of the reported points, or None if the sample
+    /// is empty
+    pub fn max_timestamp(&self) -> Option<u64> {
+        self.points().map(|point| point.timestamp).max()
+    }
+
+    /// Compares two samples by their latest timestamp
+    ///
+    /// Empty samples order before all others. Use it to sort samples, e.g.
+    /// `samples.sort_by(|a, b| b.cmp_timestamp(a))` puts the newest first, or
+    /// to drop samples read twice with `dedup_by`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use nuttx::input::touchscreen::{TouchPoint, TouchSample};
+    ///
+    /// let sample = |timestamp| TouchSample {
+    ///     npoints: 1,
+    ///     point: [TouchPoint { timestamp, ..TouchPoint::default() }],
+    /// };
+    ///
+    /// let mut samples = [sample(10), sample(30), sample(20)];
+    /// samples.sort_by(|a, b| b.cmp_timestamp(a));
+    /// assert_eq!(samples[0].max_timestamp(), Some(30));
+    /// assert!(samples[0].is_newer_than(&samples[1]));
+    /// ```
+    pub fn cmp_timestamp(&self, other: &TouchSample) -> Ordering {
+        self.max_timestamp().cmp(&other.max_timestamp())
+    }
+
+    /// Returns true if this sample was reported after `other`
+    ///
+    /// Compares the samples like [`TouchSample::cmp_timestamp`].
+    pub fn is_newer_than(&self, other: &TouchSample) -> bool {
+        self.cmp_timestamp(other) == Ordering::Greater
     }
 }
 
@@ -130,60 +598,911 @@ impl TouchScreen {
     ///
     /// # Returns
     /// - Ok(TouchScreen) on success
-    /// - Err(i32) with error code if the device could not be opened
-    pub fn open(path: &CStr) -> Result<Self, i32> {
-        let fd = unsafe { open(path.as_ptr(), O_RDONLY | O_NONBLOCK) };
-        if fd < 0 {
-            return Err(fd);
+    /// - Err(InputError::OpenFailed) with the negative errno if the device could
+    ///   not be opened
+    pub fn open(path: &CStr) -> Result<Self, InputError> {
+        Self::open_with_flags(path, O_RDONLY | O_NONBLOCK)
+    }
+
+    /// Opens a touchscreen device at the specified path in blocking mode
+    ///
+    /// Unlike [`TouchScreen::open`], reads on the returned device wait until
+    /// a touch event is available instead of returning an empty sample.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the touch device as a C string (e.g. "/dev/input0")
+    ///
+    /// # Returns
+    /// - Ok(TouchScreen) on success
+    /// - Err(InputError::OpenFailed) with the negative errno if the device could
+    ///   not be opened
+    pub fn open_blocking(path: &CStr) -> Result<Self, InputError> {
+        Self::open_with_flags(path, O_RDONLY)
+    }
+
+    #[cfg(not(feature = "mock"))]
+    fn open_with_flags(path: &CStr, flags: c_int) -> Result<Self, InputError> {
+        let fd =
+            errno_result(unsafe { open(path.as_ptr(), flags) }).map_err(InputError::OpenFailed)?;
+
+        // SAFETY: The descriptor was just opened
+        Ok(Self::from_device_fd(unsafe { adopt(fd) }))
+    }
+
+    /// The mock backend opens `/dev/null` instead of the device, so the
+    /// descriptor based traits keep working without hardware
+    #[cfg(feature = "mock")]
+    fn open_with_flags(_path: &CStr, flags: c_int) -> Result<Self, InputError> {
+        let fd = errno_result(unsafe { open(c"/dev/null".as_ptr(), flags) })
+            .map_err(InputError::OpenFailed)?;
+
+        // SAFETY: The descriptor was just opened
+        Ok(Self::from_device_fd(unsafe { adopt(fd) }))
+    }
+
+    /// Adopts an already opened touchscreen file descriptor
+    ///
+    /// Ownership of `fd` is transferred to the returned TouchScreen, which closes
+    /// it when dropped. With the `std` feature, prefer converting an `OwnedFd`,
+    /// which needs no unsafe code.
+    ///
+    /// # Arguments
+    /// * `fd` - Open file descriptor of a touchscreen device
+    ///
+    /// # Safety
+    /// `fd` must be an open descriptor owned by the caller, which must not close
+    /// it or use it as an owner afterwards.
+    pub unsafe fn from_raw_fd(fd: RawFd) -> Self {
+        // SAFETY: The caller transfers ownership of the descriptor
+        Self::from_device_fd(unsafe { adopt(fd) })
+    }
+
+    fn from_device_fd(fd: DeviceFd) -> Self {
+        TouchScreen {
+            fd,
+            calibration: None,
+            pending: [0; PENDING_SIZE],
+            pending_len: 0,
+            #[cfg(feature = "mock")]
+            mock: MockQueue::new(),
         }
+    }
+
+    /// Applies a coordinate calibration to all touch points read from the device
+    ///
+    /// Once set, [`TouchScreen::read_sample`], [`TouchScreen::read_samples`]
+    /// and [`TouchScreen::read_points`] return calibrated coordinates. Use [`TouchScreen::read_sample_raw`] to
+    /// bypass the calibration.
+    ///
+    /// # Arguments
+    /// * `cal` - Calibration mapping raw coordinates to display pixels
+    pub fn with_calibration(mut self, cal: Calibration) -> Self {
+        self.calibration = Some(cal);
+        self
+    }
+
+    /// Switches the device between blocking and non-blocking mode
+    ///
+    /// # Arguments
+    /// * `nonblock` - true to return immediately when no touch data is pending,
+    ///   false to wait for touch data in [`TouchScreen::read_sample`]
+    ///
+    /// # Returns
+    /// - Ok(()) on success
+    /// - Err(InputError) if the file status flags could not be changed
+    pub fn set_nonblocking(&mut self, nonblock: bool) -> Result<(), InputError> {
+        let flags =
+            errno_result(unsafe { fcntl(self.as_raw_fd(), F_GETFL) }).map_err(InputError::io)?;
+
+        let flags = if nonblock {
+            flags | O_NONBLOCK
+        } else {
+            flags & !O_NONBLOCK
+        };
 
-        Ok(TouchScreen { fd })
+        errno_result(unsafe { fcntl(self.as_raw_fd(), F_SETFL, flags) }).map_err(InputError::io)?;
+        Ok(())
+    }
+
+    /// Waits until a touch event is ready to be read from the device
+    ///
+    /// This polls the device for readability, allowing a single thread to wait on
+    /// several input devices without spinning. Samples kept from an earlier
+    /// read are ready without polling the device, see
+    /// [`TouchScreen::read_samples`].
+    ///
+    /// # Arguments
+    /// * `timeout` - Maximum time to wait, or None to wait indefinitely. Timeouts
+    ///   are rounded up to whole milliseconds.
+    ///
+    /// # Returns
+    /// - Ok(true) if touch data is ready to be read, or a pending error will be
+    ///   reported by the next read
+    /// - Ok(false) if the timeout expired before touch data became available
+    /// - Err(InputError) if polling failed
+    ///
+    /// # Errors
+    /// Returns `InputError::Interrupted` if the wait was interrupted by a signal,
+    /// in which case the wait can be retried.
+    #[cfg(not(feature = "mock"))]
+    pub fn wait_readable(&self, timeout: Option<Duration>) -> Result<bool, InputError> {
+        if self.has_kept_sample() {
+            return Ok(true);
+        }
+
+        let timeout_ms = match timeout {
+            None => -1,
+            Some(timeout) => {
+                let ms = timeout.as_nanos().div_ceil(1_000_000);
+                ms.min(c_int::MAX as u128) as c_int
+            }
+        };
+
+        let mut fd = pollfd {
+            fd: self.as_raw_fd(),
+            events: POLLIN,
+            revents: 0,
+        };
+
+        let ret = errno_result(unsafe { poll(&mut fd, 1, timeout_ms) }).map_err(InputError::io)?;
+
+        Ok(ret > 0)
+    }
+
+    /// The mock backend never waits, the device is ready if a read is queued
+    #[cfg(feature = "mock")]
+    pub fn wait_readable(&self, _timeout: Option<Duration>) -> Result<bool, InputError> {
+        Ok(self.has_kept_sample() || self.mock.len > 0)
+    }
+
+    /// Requests a signal whenever touch data becomes available
+    ///
+    /// The touchscreen interface has no registration ioctl of its own, unlike
+    /// the button driver, so the signal is requested through the generic
+    /// `F_SETSIG`/`F_SETOWN` file controls, naming the calling task as the
+    /// receiver. Together with `sigwaitinfo` on the blocked signal, this allows
+    /// a task to sleep until the panel is touched. If the receiver cannot be
+    /// set, the signal is reset to 0, so a failed request leaves none behind.
+    ///
+    /// Many NuttX configurations don't deliver signals for character drivers.
+    /// The portable fallback is to wait with [`TouchScreen::wait_readable`] or
+    /// a [`Poller`](crate::input::Poller), which rely on `poll` and are
+    /// supported by every touchscreen driver.
+    ///
+    /// # Arguments
+    /// * `signo` - The signal to deliver, e.g. `SIGUSR1`
+    ///
+    /// # Returns
+    /// - Ok(()) on success
+    /// - Err(InputError::Io) with `-ENOTSUP` if the driver or the file system
+    ///   cannot deliver signals
+    /// - Err(InputError) if the file controls failed otherwise
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use core::ffi::CStr;
+    /// use nuttx::input::touchscreen::TouchScreen;
+    ///
+    /// let ts = TouchScreen::open(CStr::from_bytes_with_nul(b"/dev/input0\0").unwrap()).unwrap();
+    /// match ts.notify_signal(libc::SIGUSR1) {
+    ///     // Block SIGUSR1 and wait for it with sigwaitinfo
+    ///     Ok(()) => {}
+    ///     // Wait with poll instead
+    ///     Err(_) => {
+    ///         ts.wait_readable(None).unwrap();
+    ///     }
+    /// }
+    /// ```
+    #[cfg(not(feature = "mock"))]
+    pub fn notify_signal(&self, signo: c_int) -> Result<(), InputError> {
+        let fd = self.as_raw_fd();
+        let result = errno_result(unsafe { fcntl(fd, F_SETSIG, signo) }).and_then(|_| {
+            errno_result(unsafe { fcntl(fd, F_SETOWN, getpid()) }).inspect_err(|_| {
+                // Leave no signal selected for a descriptor without an owner
+                unsafe { fcntl(fd, F_SETSIG, 0) };
+            })
+        });
+
+        match result {
+            Ok(_) => Ok(()),
+            // Reported for file controls or descriptors without signal support
+            Err(err) if [-ENOSYS, -EINVAL, -ENOTTY, -ENOTSUP].contains(&err) => {
+                Err(InputError::Io(-ENOTSUP))
+            }
+            Err(err) => Err(InputError::io(err)),
+        }
+    }
+
+    #[cfg(feature = "mock")]
+    pub fn notify_signal(&self, _signo: c_int) -> Result<(), InputError> {
+        Err(InputError::Io(-ENOTSUP))
     }
 
     /// Reads a touch sample from the device
     ///
     /// This reads the next available touch event from the device. The device is opened
     /// in non-blocking mode by default, so if no touch data is available this will
-    /// return immediately with a sample containing npoints = 0.
+    /// return immediately, either with `InputError::WouldBlock` if the driver
+    /// reports `EAGAIN` or with a sample containing npoints = 0 if it returns no
+    /// data.
+    ///
+    /// In blocking mode this waits until touch data is available and never returns
+    /// a sample with npoints = 0, unless the wait is interrupted by a signal.
     ///
     /// # Returns
     /// - Ok(TouchSample) containing the touch data. The sample will have:
-    ///   - npoints = 0 if no touch data is available
+    ///   - npoints = 0 if the driver returned no data
     ///   - npoints = 1 for single-touch devices
     ///   - npoints > 1 for multi-touch devices (if supported)
-    /// - Err(i32) with the error code if the read operation failed
+    /// - Err(InputError) if the read operation failed
     ///
     /// # Errors
     /// Returns an error if:
     /// - The device is not properly opened
-    /// - The read operation fails
-    /// - The buffer is too small for the received data
+    /// - The read operation fails (`InputError::ReadFailed`)
+    /// - No data is pending on a non-blocking device and the driver reports
+    ///   `EAGAIN` (`InputError::WouldBlock`)
+    /// - The driver returned only part of a sample (`InputError::IncompleteRead`),
+    ///   in which case the part is kept and completed by the next read
+    /// - A blocking read is interrupted by a signal (`InputError::Interrupted`),
+    ///   in which case the read can be retried
     ///
     /// # Notes
     /// - The TouchSample structure uses a fixed-size array for touch points, but
     ///   multi-touch devices may report more points than can be stored. In this case,
-    ///   only the first point will be available.
+    ///   only the first point will be available, use [`TouchScreen::read_points`]
+    ///   to receive all of them.
     /// - Check the flags field in each TouchPoint to determine if the data is valid
-    pub fn read_sample(&mut self) -> Result<TouchSample, i32> {
+    /// - If a [`Calibration`] was set, it is applied to the coordinates of every
+    ///   point in the sample
+    pub fn read_sample(&mut self) -> Result<TouchSample, InputError> {
+        let mut sample = self.read_sample_raw()?;
+        self.calibrate(&mut sample);
+        Ok(sample)
+    }
+
+    /// Reads as many touch samples as are available, up to `buf.len()`
+    ///
+    /// High-frequency controllers can queue several samples between polls,
+    /// which are received with a single `read` rather than one per sample.
+    /// The samples are calibrated like [`TouchScreen::read_sample`].
+    ///
+    /// Only whole samples are returned. If a read ends within a sample, the
+    /// received part is kept and completed by the next read, so no sample is
+    /// lost or misaligned. The kept bytes are shared by all read methods, so
+    /// mixing them with [`TouchScreen::read_sample`], [`TouchScreen::read_points`]
+    /// or [`TouchScreen::drain`] keeps the samples in order.
+    ///
+    /// # Arguments
+    /// * `buf` - Buffer receiving the samples
+    ///
+    /// # Returns
+    /// - Ok(usize) with the number of samples stored at the start of `buf`,
+    ///   which may be 0 if the driver returned no data or only part of a
+    ///   sample
+    /// - Err(InputError) if the read operation failed, see
+    ///   [`TouchScreen::read_sample`]
+    ///
+    /// # Notes
+    /// Like [`TouchScreen::read_sample`], each sample holds a single point.
+    /// Use [`TouchScreen::read_points`] to receive all points of multi-touch
+    /// samples.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use core::ffi::CStr;
+    /// use nuttx::input::touchscreen::{TouchSample, TouchScreen};
+    ///
+    /// let mut ts = TouchScreen::open(CStr::from_bytes_with_nul(b"/dev/input0\0").unwrap()).unwrap();
+    /// // SAFETY: Touch samples are plain C structures
+    /// let mut samples: [TouchSample; 8] = unsafe { core::mem::zeroed() };
+    /// let count = ts.read_samples(&mut samples).unwrap_or(0);
+    /// for sample in &samples[..count] {
+    ///     // Handle the sample
+    /// }
+    /// ```
+    pub fn read_samples(&mut self, buf: &mut [TouchSample]) -> Result<usize, InputError> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        let count = if self.pending_len == 0 {
+            self.read_samples_into(buf)?
+        } else {
+            // Kept bytes precede any new ones, so they are completed first
+            if !self.has_kept_sample() {
+                self.fill_pending()?;
+            }
+
+            let mut count = 0;
+            // SAFETY: Touch samples are plain C structures
+            while count < buf.len()
+                && self
+                    .take_sample(unsafe { sample_bytes(&mut buf[count]) })
+                    .is_some()
+            {
+                count += 1;
+            }
+            count
+        };
+
+        for sample in &mut buf[..count] {
+            self.calibrate(sample);
+        }
+        Ok(count)
+    }
+
+    /// Reads samples straight into `buf` while no bytes are kept
+    ///
+    /// Multi-touch samples are longer than a [`TouchSample`], so each sample
+    /// is moved to its slot with its first point only. The trailing part of a
+    /// sample is kept for the next read.
+    fn read_samples_into(&mut self, buf: &mut [TouchSample]) -> Result<usize, InputError> {
+        // SAFETY: Touch samples are plain C structures
+        let bytes = unsafe {
+            core::slice::from_raw_parts_mut(buf.as_mut_ptr() as *mut u8, size_of_val(buf))
+        };
+        // SAFETY: `bytes` is valid for writes of its length
+        let received =
+            unsafe { self.read_raw(bytes.as_mut_ptr(), bytes.len()) }.map_err(InputError::read)?;
+
+        let mut offset = 0;
+        let mut count = 0;
+        while let Some(size) = first_sample_size(&bytes[offset..received]) {
+            // Samples are at least SAMPLE_SIZE long, so slots never pass them
+            bytes.copy_within(offset..offset + SAMPLE_SIZE, count * SAMPLE_SIZE);
+            offset += size;
+            count += 1;
+        }
+
+        let rest = received - offset;
+        self.pending[..rest].copy_from_slice(&bytes[offset..received]);
+        self.pending_len = rest;
+        Ok(count)
+    }
+
+    /// Applies the calibration, if any, to the points of a sample
+    fn calibrate(&self, sample: &mut TouchSample) {
+        if let Some(cal) = &self.calibration {
+            let npoints = sample.points().count();
+            sample.point[..npoints]
+                .iter_mut()
+                .for_each(|point| cal.apply(point));
+        }
+    }
+
+    /// Reads a touch sample if one is pending
+    ///
+    /// Intended for non-blocking polling: unlike [`TouchScreen::read_sample`],
+    /// the absence of data is not reported through `InputError::WouldBlock` or
+    /// an empty sample, so callers don't need to check `npoints`.
+    ///
+    /// # Returns
+    /// - Ok(Some(TouchSample)) if touch data was read, calibrated like
+    ///   [`TouchScreen::read_sample`]
+    /// - Ok(None) if no data is pending, i.e. the driver reported `EAGAIN` or
+    ///   returned no data
+    /// - Err(InputError) if the read operation failed
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use core::ffi::CStr;
+    /// use nuttx::input::touchscreen::TouchScreen;
+    ///
+    /// let mut ts = TouchScreen::open(CStr::from_bytes_with_nul(b"/dev/input0\0").unwrap()).unwrap();
+    /// while let Some(sample) = ts.poll_sample().unwrap() {
+    ///     for point in sample.points() {
+    ///         // Handle the point
+    ///     }
+    /// }
+    /// ```
+    pub fn poll_sample(&mut self) -> Result<Option<TouchSample>, InputError> {
+        match self.read_sample() {
+            Ok(sample) if sample.is_empty() => Ok(None),
+            Ok(sample) => Ok(Some(sample)),
+            Err(InputError::WouldBlock) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Waits up to `timeout` for a touch sample and reads it
+    ///
+    /// Combines [`TouchScreen::wait_readable`] and [`TouchScreen::poll_sample`],
+    /// so it works the same in blocking and non-blocking mode. A zero timeout
+    /// checks for a pending sample without waiting.
+    ///
+    /// # Arguments
+    /// * `timeout` - Maximum time to wait, rounded up to whole milliseconds
+    ///
+    /// # Returns
+    /// - Ok(Some(TouchSample)) if touch data was read
+    /// - Ok(None) if no touch data arrived within the timeout
+    /// - Err(InputError) if polling or reading failed
+    ///
+    /// # Errors
+    /// Returns `InputError::Interrupted` if the wait was interrupted by a signal,
+    /// in which case the call can be retried.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use core::ffi::CStr;
+    /// use core::time::Duration;
+    /// use nuttx::input::touchscreen::TouchScreen;
+    ///
+    /// let mut ts = TouchScreen::open(CStr::from_bytes_with_nul(b"/dev/input0\0").unwrap()).unwrap();
+    /// loop {
+    ///     match ts.read_timeout(Duration::from_millis(16)).unwrap() {
+    ///         Some(sample) => { /* Handle the touch */ }
+    ///         None => { /* Redraw the next frame */ }
+    ///     }
+    /// }
+    /// ```
+    pub fn read_timeout(&mut self, timeout: Duration) -> Result<Option<TouchSample>, InputError> {
+        if !self.wait_readable(Some(timeout))? {
+            return Ok(None);
+        }
+
+        self.poll_sample()
+    }
+
+    /// Reads a touch sample without blocking the calling task
+    ///
+    /// Returns a future for async executors such as `embassy`, which must not
+    /// be stalled by a blocking read. NuttX offers no reactor to register the
+    /// descriptor with, so each poll of the future checks for pending touch
+    /// data without waiting and, if there is none, wakes itself and yields to
+    /// the executor. It works the same in blocking and non-blocking mode.
+    ///
+    /// As the future is woken on every poll while no data is pending, an
+    /// executor that sleeps when idle won't sleep while it is awaited.
+    ///
+    /// # Returns
+    /// A future resolving to:
+    /// - Ok(TouchSample) once touch data was read, calibrated like
+    ///   [`TouchScreen::read_sample`]
+    /// - Err(InputError) if polling or reading failed
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use nuttx::input::InputError;
+    /// use nuttx::input::touchscreen::TouchScreen;
+    ///
+    /// async fn track(ts: &mut TouchScreen) -> Result<(), InputError> {
+    ///     loop {
+    ///         let sample = ts.read_async().await?;
+    ///         for point in sample.points() {
+    ///             // Handle the point
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    #[cfg(feature = "async")]
+    pub fn read_async(&mut self) -> impl Future<Output = Result<TouchSample, InputError>> + '_ {
+        core::future::poll_fn(move |cx| {
+            match self.wait_readable(Some(Duration::ZERO)) {
+                Ok(true) => {
+                    if let Some(sample) = self.poll_sample()? {
+                        return Poll::Ready(Ok(sample));
+                    }
+                }
+                Ok(false) | Err(InputError::Interrupted) => {}
+                Err(err) => return Poll::Ready(Err(err)),
+            }
+
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        })
+    }
+
+    /// Reads a touch sample from the device without applying the calibration
+    ///
+    /// Behaves like [`TouchScreen::read_sample`] but always returns the raw
+    /// coordinates reported by the driver, for callers that calibrate elsewhere.
+    pub fn read_sample_raw(&mut self) -> Result<TouchSample, InputError> {
         let mut sample: TouchSample = unsafe { core::mem::zeroed() };
 
-        let bytes_read = unsafe {
-            read(
-                self.fd,
-                &mut sample as *mut _ as *mut c_void,
-                size_of::<TouchSample>(),
-            )
+        // A read of 0 bytes means the driver had no data, which leaves the
+        // zeroed sample with npoints = 0. Only a partial sample is incomplete.
+        // SAFETY: TouchSample is a plain C structure
+        self.next_sample(unsafe { sample_bytes(&mut sample) })?;
+        Ok(sample)
+    }
+
+    /// Reads all touch points of the next sample from a multi-touch device
+    ///
+    /// The driver delivers a sample as an `npoints` header followed by the point
+    /// array. The header is decoded to learn how many points were reported, and
+    /// up to `buf.len()` of them are copied into `buf`.
+    ///
+    /// # Arguments
+    /// * `buf` - Destination for the touch points of the sample
+    ///
+    /// # Returns
+    /// - Ok(usize) with the number of points stored in `buf`. If the driver reports
+    ///   more points than `buf` can hold, the count is truncated to `buf.len()`.
+    ///   0 if the driver returned no data.
+    /// - Err(InputError) if the read operation failed
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// - The read operation fails, see [`TouchScreen::read_sample`]
+    /// - Only part of the sample is received (`InputError::IncompleteRead`), in
+    ///   which case the part is kept and completed by the next read
+    ///
+    /// # Notes
+    /// - Exactly one sample is consumed, as sized by its `npoints` header. Any
+    ///   further samples returned by the same read are kept for later reads.
+    /// - At most [`MAX_TOUCH_POINTS`] points are received per sample.
+    /// - If a [`Calibration`] was set, it is applied to the returned points.
+    pub fn read_points(&mut self, buf: &mut [TouchPoint]) -> Result<usize, InputError> {
+        let mut sample: MultiTouchSample = unsafe { core::mem::zeroed() };
+
+        // SAFETY: MultiTouchSample is a plain C structure
+        let Some(size) = self.next_sample(unsafe { sample_bytes(&mut sample) })? else {
+            return Ok(0);
         };
 
+        let received = (size - HEADER_LEN) / size_of::<TouchPoint>();
+        let count = (sample.npoints.max(0) as usize)
+            .min(received)
+            .min(buf.len());
+
+        buf[..count].copy_from_slice(&sample.point[..count]);
+
+        if let Some(cal) = &self.calibration {
+            buf[..count].iter_mut().for_each(|point| cal.apply(point));
+        }
+        Ok(count)
+    }
+
+    /// Discards all touch samples queued by the driver
+    ///
+    /// Useful before entering a new screen, so touches made earlier are not
+    /// acted upon. The device is switched to non-blocking mode while reading,
+    /// and its previous mode is restored afterwards.
+    ///
+    /// # Returns
+    /// - Ok(usize) with the number of samples discarded
+    /// - Err(InputError) if reading or changing the file status flags failed
+    pub fn drain(&mut self) -> Result<usize, InputError> {
+        let fd = self.as_raw_fd();
+        let flags = errno_result(unsafe { fcntl(fd, F_GETFL) }).map_err(InputError::io)?;
+        if flags & O_NONBLOCK == 0 {
+            errno_result(unsafe { fcntl(fd, F_SETFL, flags | O_NONBLOCK) })
+                .map_err(InputError::io)?;
+        }
+
+        // Samples are consumed whole, as sized by their headers, so each one
+        // is counted once however many the driver returns per read
+        let mut count = 0;
+        let result = loop {
+            match self.next_sample(&mut []) {
+                Ok(Some(_)) => count += 1,
+                Ok(None) | Err(InputError::WouldBlock) => break Ok(count),
+                Err(InputError::Interrupted | InputError::IncompleteRead) => continue,
+                Err(err) => break Err(err),
+            }
+        };
+
+        if flags & O_NONBLOCK == 0 {
+            errno_result(unsafe { fcntl(fd, F_SETFL, flags) }).map_err(InputError::io)?;
+        }
+        result
+    }
+
+    /// Moves the next sample out of the kept bytes, if they hold all of it
+    ///
+    /// Copies up to `dst.len()` bytes of the sample into `dst` and discards
+    /// the rest of it.
+    ///
+    /// # Returns
+    /// The size of the sample, or None if no whole sample is kept
+    fn take_sample(&mut self, dst: &mut [u8]) -> Option<usize> {
+        let size = first_sample_size(&self.pending[..self.pending_len])?;
+        let len = size.min(dst.len());
+
+        dst[..len].copy_from_slice(&self.pending[..len]);
+        self.pending.copy_within(size..self.pending_len, 0);
+        self.pending_len -= size;
+        Some(size)
+    }
+
+    /// Returns true if the kept bytes hold a whole sample
+    fn has_kept_sample(&self) -> bool {
+        first_sample_size(&self.pending[..self.pending_len]).is_some()
+    }
+
+    /// Reads from the device into the free space after the kept bytes
+    ///
+    /// # Returns
+    /// - Ok(usize) with the number of bytes read
+    /// - Err(InputError) if the read failed
+    fn fill_pending(&mut self) -> Result<usize, InputError> {
+        let len = PENDING_SIZE - self.pending_len;
+        let dst = self.pending[self.pending_len..].as_mut_ptr();
+
+        // SAFETY: `dst` points to the `len` free bytes of `pending`
+        let ret = unsafe { self.read_raw(dst, len) }.map_err(InputError::read)?;
+        self.pending_len += ret;
+        Ok(ret)
+    }
+
+    /// Receives the next whole sample, see [`TouchScreen::take_sample`]
+    ///
+    /// Kept samples are returned first, the device is only read when none is
+    /// left.
+    ///
+    /// # Returns
+    /// - Ok(Some(usize)) with the size of the sample
+    /// - Ok(None) if the driver returned no data
+    /// - Err(InputError::IncompleteRead) if only part of a sample was
+    ///   received, which is kept and completed by the next read
+    /// - Err(InputError) if the read failed
+    fn next_sample(&mut self, dst: &mut [u8]) -> Result<Option<usize>, InputError> {
+        if let Some(size) = self.take_sample(dst) {
+            return Ok(Some(size));
+        }
+
+        if self.fill_pending()? == 0 {
+            return Ok(None);
+        }
+        self.take_sample(dst)
+            .map(Some)
+            .ok_or(InputError::IncompleteRead)
+    }
+
+    /// Reads up to `len` bytes from the device into `dst`
+    ///
+    /// # Safety
+    /// `dst` must be valid for writes of `len` bytes.
+    #[cfg(not(feature = "mock"))]
+    unsafe fn read_raw(&mut self, dst: *mut u8, len: usize) -> Result<usize, i32> {
+        let bytes_read = unsafe { read(self.as_raw_fd(), dst as *mut c_void, len) };
+
         if bytes_read < 0 {
-            return Err(bytes_read as i32);
-        } else if bytes_read as usize != size_of::<TouchSample>() {
-            return Err(-libc::EIO); // Input/output error for incomplete read
+            Err(errno())
+        } else {
+            Ok(bytes_read as usize)
+        }
+    }
+
+    /// The mock backend pops as many queued samples as fit into `len` bytes,
+    /// and reports `-EAGAIN` if none is queued
+    ///
+    /// Each sample is written as the driver would, sized by its `npoints`
+    /// header. Points beyond the first are not queued and read as zeros. A
+    /// sample cut short, an empty read or a failure ends the read.
+    ///
+    /// # Safety
+    /// `dst` must be valid for writes of `len` bytes.
+    #[cfg(feature = "mock")]
+    unsafe fn read_raw(&mut self, dst: *mut u8, len: usize) -> Result<usize, i32> {
+        let mut written = 0;
+        while let Some(entry) = self.mock.peek() {
+            let (sample, start, end) = match entry {
+                MockRead::Sample { sample, start, end } => (sample, start, end),
+                // Only reported by a read that returned no sample before
+                MockRead::Empty if written == 0 => {
+                    self.mock.pop();
+                    return Ok(0);
+                }
+                MockRead::Error(errno) if written == 0 => {
+                    self.mock.pop();
+                    return Err(errno);
+                }
+                _ => break,
+            };
+            if len - written < end - start {
+                break;
+            }
+
+            let mut bytes = [0u8; size_of::<MultiTouchSample>()];
+            // SAFETY: `bytes` has room for a sample, and the caller guarantees
+            // room for `len` bytes at `dst`
+            unsafe {
+                core::ptr::copy_nonoverlapping(
+                    &sample as *const TouchSample as *const u8,
+                    bytes.as_mut_ptr(),
+                    SAMPLE_SIZE,
+                );
+                core::ptr::copy_nonoverlapping(
+                    bytes[start..end].as_ptr(),
+                    dst.add(written),
+                    end - start,
+                );
+            }
+            self.mock.pop();
+            written += end - start;
+
+            if end < sample_size(sample.npoints) {
+                break;
+            }
+        }
+
+        if written == 0 && self.mock.len == 0 {
+            return Err(-libc::EAGAIN);
+        }
+        Ok(written)
+    }
+}
+
+/// Number of reads the mock backend can queue
+#[cfg(feature = "mock")]
+pub const MOCK_QUEUE_LEN: usize = 16;
+
+/// A read queued with the `mock` feature
+#[cfg(feature = "mock")]
+#[derive(Clone, Copy)]
+enum MockRead {
+    /// Bytes `start..end` of a sample as delivered by the driver
+    Sample {
+        sample: TouchSample,
+        start: usize,
+        end: usize,
+    },
+    /// A read returning no data
+    Empty,
+    /// A read failing with the negative errno
+    Error(i32),
+}
+
+/// In-memory queue replacing the driver with the `mock` feature
+#[cfg(feature = "mock")]
+struct MockQueue {
+    reads: [MockRead; MOCK_QUEUE_LEN],
+    head: usize,
+    len: usize,
+}
+
+#[cfg(feature = "mock")]
+impl MockQueue {
+    fn new() -> Self {
+        MockQueue {
+            reads: [MockRead::Empty; MOCK_QUEUE_LEN],
+            head: 0,
+            len: 0,
+        }
+    }
+
+    /// Queues all `reads`, or none of them if they do not fit
+    fn push(&mut self, reads: &[MockRead]) -> Result<(), InputError> {
+        if MOCK_QUEUE_LEN - self.len < reads.len() {
+            return Err(InputError::Io(-libc::ENOSPC));
+        }
+
+        for read in reads {
+            self.reads[(self.head + self.len) % MOCK_QUEUE_LEN] = *read;
+            self.len += 1;
+        }
+        Ok(())
+    }
+
+    fn peek(&self) -> Option<MockRead> {
+        (self.len > 0).then(|| self.reads[self.head])
+    }
+
+    fn pop(&mut self) {
+        if self.len > 0 {
+            self.head = (self.head + 1) % MOCK_QUEUE_LEN;
+            self.len -= 1;
         }
-        Ok(sample)
     }
 }
 
+#[cfg(feature = "mock")]
+impl TouchScreen {
+    /// Queues a sample to be returned by the next read
+    ///
+    /// Only available with the `mock` feature, which replaces the driver with
+    /// an in-memory queue so code using a TouchScreen can be tested without
+    /// hardware. Samples are read in the order they were pushed, and reads of
+    /// an empty queue fail with `InputError::WouldBlock`. Reads with other
+    /// outcomes can be queued with [`TouchScreen::mock_push_partial`],
+    /// [`TouchScreen::mock_push_empty`] and [`TouchScreen::mock_push_error`].
+    ///
+    /// # Arguments
+    /// * `sample` - The sample to queue, as the driver would report it
+    ///
+    /// # Returns
+    /// - Ok(()) on success
+    /// - Err(InputError::Io) with `-ENOSPC` if [`MOCK_QUEUE_LEN`] reads are
+    ///   already queued
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use core::ffi::CStr;
+    /// use nuttx::input::touchscreen::{TouchPoint, TouchSample, TouchScreen};
+    ///
+    /// let mut ts = TouchScreen::open(CStr::from_bytes_with_nul(b"/dev/input0\0").unwrap()).unwrap();
+    /// let point = TouchPoint { x: 10, y: 20, ..TouchPoint::default() };
+    /// ts.mock_push(TouchSample { npoints: 1, point: [point] }).unwrap();
+    ///
+    /// let sample = ts.poll_sample().unwrap().unwrap();
+    /// assert_eq!(sample.point[0].x, 10);
+    /// assert!(ts.poll_sample().unwrap().is_none());
+    /// ```
+    pub fn mock_push(&mut self, sample: TouchSample) -> Result<(), InputError> {
+        let end = sample_size(sample.npoints);
+        self.mock.push(&[MockRead::Sample {
+            sample,
+            start: 0,
+            end,
+        }])
+    }
+
+    /// Queues a sample that the driver delivers over two reads
+    ///
+    /// The first read ends after `len` bytes of the sample, and the next one
+    /// returns the rest of it, as when a read is cut short.
+    ///
+    /// # Arguments
+    /// * `sample` - The sample to queue, as the driver would report it
+    /// * `len` - The number of bytes returned by the first read
+    ///
+    /// # Returns
+    /// - Ok(()) on success
+    /// - Err(InputError::Io) with `-EINVAL` if `len` does not fall within the
+    ///   sample
+    /// - Err(InputError::Io) with `-ENOSPC` if the queue cannot hold both reads
+    pub fn mock_push_partial(&mut self, sample: TouchSample, len: usize) -> Result<(), InputError> {
+        let size = sample_size(sample.npoints);
+        if len == 0 || len >= size {
+            return Err(InputError::Io(-libc::EINVAL));
+        }
+
+        self.mock.push(&[
+            MockRead::Sample {
+                sample,
+                start: 0,
+                end: len,
+            },
+            MockRead::Sample {
+                sample,
+                start: len,
+                end: size,
+            },
+        ])
+    }
+
+    /// Queues a read returning no data, as a driver with nothing to report
+    ///
+    /// # Returns
+    /// - Ok(()) on success
+    /// - Err(InputError::Io) with `-ENOSPC` if [`MOCK_QUEUE_LEN`] reads are
+    ///   already queued
+    pub fn mock_push_empty(&mut self) -> Result<(), InputError> {
+        self.mock.push(&[MockRead::Empty])
+    }
+
+    /// Queues a read failing with `errno`
+    ///
+    /// # Arguments
+    /// * `errno` - The negative errno reported by the read
+    ///
+    /// # Returns
+    /// - Ok(()) on success
+    /// - Err(InputError::Io) with `-ENOSPC` if [`MOCK_QUEUE_LEN`] reads are
+    ///   already queued
+    pub fn mock_push_error(&mut self, errno: i32) -> Result<(), InputError> {
+        self.mock.push(&[MockRead::Error(errno)])
+    }
+}
+
+impl AsRawFd for TouchScreen {
+    /// Returns the file descriptor of the touchscreen device, e.g. for use in an
+    /// external poll loop. The descriptor stays owned by the TouchScreen.
+    fn as_raw_fd(&self) -> RawFd {
+        crate::fd::raw(&self.fd)
+    }
+}
+
+#[cfg(not(feature = "std"))]
 impl Drop for TouchScreen {
     /// Automatically closes the touchscreen device when the TouchScreen instance goes out of scope
     ///
@@ -199,3 +1518,166 @@ impl Drop for TouchScreen {
         unsafe { libc::close(self.fd) };
     }
 }
+
+#[cfg(feature = "std")]
+impl std::os::fd::AsFd for TouchScreen {
+    /// Borrows the descriptor of the touchscreen device
+    fn as_fd(&self) -> std::os::fd::BorrowedFd<'_> {
+        std::os::fd::AsFd::as_fd(&self.fd)
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<std::os::fd::OwnedFd> for TouchScreen {
+    /// Adopts an owned touchscreen descriptor, without calibration
+    fn from(fd: std::os::fd::OwnedFd) -> Self {
+        TouchScreen::from_device_fd(fd)
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<TouchScreen> for std::os::fd::OwnedFd {
+    /// Releases the descriptor of the touchscreen device, which stays open
+    fn from(ts: TouchScreen) -> Self {
+        ts.fd
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::vec::Vec;
+
+    fn point(x: i16, flags: TouchFlags) -> TouchPoint {
+        TouchPoint {
+            x,
+            flags: flags.bits(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn points_of_multi_touch_sample() {
+        let backing = [
+            point(1, TouchFlags::DOWN),
+            point(2, TouchFlags::UP),
+            point(3, TouchFlags::MOVE),
+        ];
+
+        let xs = |points: &mut dyn Iterator<Item = &TouchPoint>| {
+            points.map(|point| point.x).collect::<Vec<_>>()
+        };
+
+        // Entries beyond npoints are stale and never visited
+        assert_eq!(xs(&mut reported_points(2, &backing)), [1, 2]);
+        assert_eq!(xs(&mut active_points(2, &backing)), [1]);
+        assert_eq!(xs(&mut active_points(3, &backing)), [1, 3]);
+        assert!(xs(&mut reported_points(-1, &backing)).is_empty());
+    }
+
+    #[test]
+    fn gesture_codes() {
+        let gesture = |code| {
+            let point = TouchPoint {
+                flags: TouchFlags::GESTURE_VALID.bits(),
+                gesture: code,
+                ..Default::default()
+            };
+            point.gesture()
+        };
+
+        assert_eq!(gesture(TOUCH_PALM as u16), Some(Gesture::Palm));
+        // Codes beyond a byte are not truncated into a defined gesture
+        let code = 0x100 | TOUCH_PALM as u16;
+        assert_eq!(gesture(code), Some(Gesture::Unknown(code)));
+        assert_eq!(u16::from(Gesture::Unknown(code)), code);
+        assert_eq!(TouchPoint::default().gesture(), None);
+    }
+
+    #[cfg(feature = "mock")]
+    fn mock_screen() -> TouchScreen {
+        TouchScreen::open(c"/dev/input0").unwrap()
+    }
+
+    #[cfg(feature = "mock")]
+    fn sample(x: i16) -> TouchSample {
+        TouchSample {
+            npoints: 1,
+            point: [point(x, TouchFlags::DOWN)],
+        }
+    }
+
+    #[cfg(feature = "mock")]
+    #[test]
+    fn read_without_data() {
+        let mut ts = mock_screen();
+        let mut points = [TouchPoint::default(); 2];
+
+        // Nothing pending on a non-blocking device
+        assert_eq!(ts.read_sample().err(), Some(InputError::WouldBlock));
+        assert_eq!(ts.read_points(&mut points), Err(InputError::WouldBlock));
+        assert!(ts.poll_sample().unwrap().is_none());
+
+        // The driver returning no data
+        ts.mock_push_empty().unwrap();
+        assert!(ts.read_sample().unwrap().is_empty());
+        ts.mock_push_empty().unwrap();
+        assert_eq!(ts.read_points(&mut points), Ok(0));
+    }
+
+    #[cfg(feature = "mock")]
+    #[test]
+    fn read_error() {
+        let mut ts = mock_screen();
+
+        ts.mock_push_error(-libc::EIO).unwrap();
+        assert_eq!(
+            ts.read_sample().err(),
+            Some(InputError::ReadFailed(-libc::EIO))
+        );
+
+        ts.mock_push_error(-libc::EINTR).unwrap();
+        assert_eq!(ts.read_sample().err(), Some(InputError::Interrupted));
+
+        // A failure does not affect the samples queued after it
+        ts.mock_push_error(-libc::EIO).unwrap();
+        ts.mock_push(sample(7)).unwrap();
+        assert!(ts.read_sample().is_err());
+        assert_eq!(ts.read_sample().unwrap().point[0].x, 7);
+    }
+
+    #[cfg(feature = "mock")]
+    #[test]
+    fn read_incomplete() {
+        let mut ts = mock_screen();
+
+        ts.mock_push_partial(sample(5), 10).unwrap();
+        ts.mock_push(sample(6)).unwrap();
+        assert_eq!(ts.read_sample().err(), Some(InputError::IncompleteRead));
+
+        // The received part is completed by the next read
+        assert_eq!(ts.read_sample().unwrap().point[0].x, 5);
+        assert_eq!(ts.read_sample().unwrap().point[0].x, 6);
+        assert_eq!(ts.read_sample().err(), Some(InputError::WouldBlock));
+    }
+
+    #[cfg(feature = "mock")]
+    #[test]
+    fn kept_sample_is_readable() {
+        let mut ts = mock_screen();
+
+        // The rest of the first sample and the whole second one are received
+        // by a single read, leaving the second one kept
+        ts.mock_push_partial(sample(5), 10).unwrap();
+        ts.mock_push(sample(6)).unwrap();
+        assert_eq!(ts.read_sample().err(), Some(InputError::IncompleteRead));
+        assert_eq!(ts.read_sample().unwrap().point[0].x, 5);
+
+        // Nothing is queued in the driver anymore
+        assert_eq!(ts.wait_readable(Some(Duration::ZERO)), Ok(true));
+        let sample = ts.read_timeout(Duration::ZERO).unwrap().unwrap();
+        assert_eq!(sample.point[0].x, 6);
+        assert_eq!(ts.wait_readable(Some(Duration::ZERO)), Ok(false));
+        assert!(ts.read_timeout(Duration::ZERO).unwrap().is_none());
+    }
+}
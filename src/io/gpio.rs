@@ -0,0 +1,443 @@
+//! GPIO character driver interface
+//!
+//! This module provides Rust bindings for the NuttX GPIO character driver.
+//! It allows reading and driving individual pins exposed as `/dev/gpioN`, and
+//! waiting for interrupts on input pins.
+//!
+//! The driver has no multi-pin operations: each node, including the pins of
+//! an I/O expander, is a single pin with its own ioctls. [`GpioBank`] groups
+//! several pins to read and write them as a bitset, one pin after another.
+//!
+//! The implementation matches the NuttX GPIO interface defined in
+//! `nuttx/include/nuttx/ioexpander/gpio.h`.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use core::ffi::CStr;
+//! use nuttx::io::gpio::Gpio;
+//!
+//! let led = Gpio::open(CStr::from_bytes_with_nul(b"/dev/gpio0\0").unwrap()).unwrap();
+//! led.write(true).unwrap();
+//! ```
+
+use core::cell::Cell;
+use core::ffi::CStr;
+use core::mem::zeroed;
+use core::ptr;
+use core::time::Duration;
+use libc::{EAGAIN, EINVAL, O_RDWR, c_int, c_ulong, c_void, ioctl, open};
+
+use crate::bindings::{
+    SIG_BLOCK, SIGEV_SIGNAL, gpio_pintype_e, gpio_pintype_e_GPIO_INPUT_PIN,
+    gpio_pintype_e_GPIO_INPUT_PIN_PULLDOWN, gpio_pintype_e_GPIO_INPUT_PIN_PULLUP,
+    gpio_pintype_e_GPIO_INTERRUPT_BOTH_PIN, gpio_pintype_e_GPIO_INTERRUPT_FALLING_PIN,
+    gpio_pintype_e_GPIO_INTERRUPT_HIGH_PIN, gpio_pintype_e_GPIO_INTERRUPT_LOW_PIN,
+    gpio_pintype_e_GPIO_INTERRUPT_PIN, gpio_pintype_e_GPIO_INTERRUPT_RISING_PIN,
+    gpio_pintype_e_GPIO_OUTPUT_PIN, gpio_pintype_e_GPIO_OUTPUT_PIN_OPENDRAIN, sigaddset,
+    sigemptyset, sigevent, sigprocmask, sigset_t, sigtimedwait, sigwaitinfo, timespec,
+};
+use crate::errno::errno;
+use crate::fd::{AsRawFd, RawFd};
+use crate::io::ioctl::IoctlRequest;
+
+/// IOCTL command to set the value of an output pin
+///
+/// Matches C's GPIOC_WRITE
+const GPIOC_WRITE: IoctlRequest = crate::bindings::GPIOC_WRITE as IoctlRequest;
+
+/// IOCTL command to read the value of a pin
+///
+/// Matches C's GPIOC_READ
+const GPIOC_READ: IoctlRequest = crate::bindings::GPIOC_READ as IoctlRequest;
+
+/// IOCTL command to get the configured type of a pin
+///
+/// Matches C's GPIOC_PINTYPE
+const GPIOC_PINTYPE: IoctlRequest = crate::bindings::GPIOC_PINTYPE as IoctlRequest;
+
+/// IOCTL command to register for a signal on interrupts of an input pin
+///
+/// Matches C's GPIOC_REGISTER
+const GPIOC_REGISTER: IoctlRequest = crate::bindings::GPIOC_REGISTER as IoctlRequest;
+
+/// IOCTL command to stop signalling interrupts of an input pin
+///
+/// Matches C's GPIOC_UNREGISTER
+const GPIOC_UNREGISTER: IoctlRequest = crate::bindings::GPIOC_UNREGISTER as IoctlRequest;
+
+/// Result type for GPIO operations, errors hold the negative errno
+pub type GpioResult<T> = Result<T, i32>;
+
+/// Configuration of a GPIO pin
+///
+/// Mirrors C's `enum gpio_pintype_e`. Types this crate does not know about
+/// are preserved as `Unknown`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PinType {
+    /// GPIO_INPUT_PIN
+    Input,
+    /// GPIO_INPUT_PIN_PULLUP
+    InputPullUp,
+    /// GPIO_INPUT_PIN_PULLDOWN
+    InputPullDown,
+    /// GPIO_OUTPUT_PIN
+    Output,
+    /// GPIO_OUTPUT_PIN_OPENDRAIN
+    OutputOpenDrain,
+    /// GPIO_INTERRUPT_PIN
+    Interrupt,
+    /// GPIO_INTERRUPT_HIGH_PIN, interrupt on a high level
+    InterruptHigh,
+    /// GPIO_INTERRUPT_LOW_PIN, interrupt on a low level
+    InterruptLow,
+    /// GPIO_INTERRUPT_RISING_PIN, interrupt on a rising edge
+    InterruptRising,
+    /// GPIO_INTERRUPT_FALLING_PIN, interrupt on a falling edge
+    InterruptFalling,
+    /// GPIO_INTERRUPT_BOTH_PIN, interrupt on both edges
+    InterruptBoth,
+    /// A pin type without a known definition
+    Unknown(u32),
+}
+
+impl From<gpio_pintype_e> for PinType {
+    /// Decodes a pin type as reported by GPIOC_PINTYPE
+    #[allow(non_upper_case_globals)]
+    fn from(pintype: gpio_pintype_e) -> Self {
+        match pintype {
+            gpio_pintype_e_GPIO_INPUT_PIN => PinType::Input,
+            gpio_pintype_e_GPIO_INPUT_PIN_PULLUP => PinType::InputPullUp,
+            gpio_pintype_e_GPIO_INPUT_PIN_PULLDOWN => PinType::InputPullDown,
+            gpio_pintype_e_GPIO_OUTPUT_PIN => PinType::Output,
+            gpio_pintype_e_GPIO_OUTPUT_PIN_OPENDRAIN => PinType::OutputOpenDrain,
+            gpio_pintype_e_GPIO_INTERRUPT_PIN => PinType::Interrupt,
+            gpio_pintype_e_GPIO_INTERRUPT_HIGH_PIN => PinType::InterruptHigh,
+            gpio_pintype_e_GPIO_INTERRUPT_LOW_PIN => PinType::InterruptLow,
+            gpio_pintype_e_GPIO_INTERRUPT_RISING_PIN => PinType::InterruptRising,
+            gpio_pintype_e_GPIO_INTERRUPT_FALLING_PIN => PinType::InterruptFalling,
+            gpio_pintype_e_GPIO_INTERRUPT_BOTH_PIN => PinType::InterruptBoth,
+            _ => PinType::Unknown(pintype),
+        }
+    }
+}
+
+impl PinType {
+    /// Checks if the pin is driven by this side
+    pub fn is_output(&self) -> bool {
+        matches!(self, PinType::Output | PinType::OutputOpenDrain)
+    }
+
+    /// Checks if the pin can signal interrupts
+    ///
+    /// Only the `Interrupt*` pin types support [`Gpio::register_signal`].
+    pub fn is_interrupt(&self) -> bool {
+        matches!(
+            self,
+            PinType::Interrupt
+                | PinType::InterruptHigh
+                | PinType::InterruptLow
+                | PinType::InterruptRising
+                | PinType::InterruptFalling
+                | PinType::InterruptBoth
+        )
+    }
+}
+
+/// Represents an open GPIO pin device
+///
+/// The underlying device is closed when the Gpio is dropped.
+#[derive(Debug)]
+pub struct Gpio {
+    fd: c_int,
+    signo: Cell<Option<c_int>>,
+}
+
+impl Gpio {
+    /// Opens a GPIO pin device at the specified path
+    ///
+    /// # Arguments
+    /// * `path` - Path to the GPIO device as a C string (e.g. "/dev/gpio0")
+    ///
+    /// # Errors
+    /// Returns the negative errno if the device could not be opened
+    pub fn open(path: &CStr) -> GpioResult<Self> {
+        let fd = unsafe { open(path.as_ptr(), O_RDWR) };
+        if fd < 0 {
+            return Err(errno());
+        }
+        Ok(Gpio {
+            fd,
+            signo: Cell::new(None),
+        })
+    }
+
+    /// Reads the current value of the pin
+    ///
+    /// # Returns
+    /// true if the pin is high, false if it is low
+    ///
+    /// # Errors
+    /// Returns the negative errno if the ioctl fails
+    pub fn read(&self) -> GpioResult<bool> {
+        let mut value = false;
+
+        // SAFETY: GPIOC_READ stores a bool through the passed pointer
+        let result = unsafe {
+            ioctl(
+                self.fd,
+                GPIOC_READ as _,
+                &mut value as *mut bool as *mut c_void,
+            )
+        };
+
+        if result < 0 { Err(errno()) } else { Ok(value) }
+    }
+
+    /// Drives the pin high or low
+    ///
+    /// Only output pins can be written, see [`Gpio::pintype`].
+    ///
+    /// # Arguments
+    /// * `value` - true to drive the pin high, false to drive it low
+    ///
+    /// # Errors
+    /// Returns the negative errno if the ioctl fails
+    pub fn write(&self, value: bool) -> GpioResult<()> {
+        // SAFETY: GPIOC_WRITE takes the value itself rather than a pointer
+        let result = unsafe { ioctl(self.fd, GPIOC_WRITE as _, value as c_ulong) };
+
+        if result < 0 { Err(errno()) } else { Ok(()) }
+    }
+
+    /// Gets the configured type of the pin
+    ///
+    /// # Errors
+    /// Returns the negative errno if the ioctl fails
+    pub fn pintype(&self) -> GpioResult<PinType> {
+        let mut pintype: gpio_pintype_e = 0;
+
+        // SAFETY: GPIOC_PINTYPE stores a gpio_pintype_e through the passed pointer
+        let result = unsafe {
+            ioctl(
+                self.fd,
+                GPIOC_PINTYPE as _,
+                &mut pintype as *mut gpio_pintype_e as *mut c_void,
+            )
+        };
+
+        if result < 0 {
+            Err(errno())
+        } else {
+            Ok(PinType::from(pintype))
+        }
+    }
+
+    /// Registers the calling task to receive a signal on each interrupt of the pin
+    ///
+    /// The signal is blocked for the calling thread, so that edges are queued for
+    /// [`Gpio::wait_edge`] instead of invoking the default signal action. Only pins
+    /// configured as one of the `Interrupt*` [`PinType`]s can be registered.
+    ///
+    /// # Arguments
+    /// * `signo` - The signal to deliver, e.g. `SIGUSR1`
+    ///
+    /// # Errors
+    /// - `-EINVAL` if the pin does not support interrupts
+    /// - The negative errno if the ioctl fails
+    pub fn register_signal(&self, signo: c_int) -> GpioResult<()> {
+        if !self.pintype()?.is_interrupt() {
+            return Err(-EINVAL);
+        }
+
+        // SAFETY: The signal set is initialized by sigemptyset before use
+        unsafe {
+            let mut set: sigset_t = zeroed();
+            sigemptyset(&mut set);
+            sigaddset(&mut set, signo);
+            if sigprocmask(SIG_BLOCK as c_int, &set, ptr::null_mut()) < 0 {
+                return Err(errno());
+            }
+        }
+
+        // SAFETY: A zeroed sigevent is valid, the driver copies it during the ioctl
+        let mut event: sigevent = unsafe { zeroed() };
+        event.sigev_notify = SIGEV_SIGNAL as _;
+        event.sigev_signo = signo as _;
+
+        let result = unsafe {
+            ioctl(
+                self.fd,
+                GPIOC_REGISTER as _,
+                &mut event as *mut sigevent as *mut c_void,
+            )
+        };
+
+        if result < 0 {
+            return Err(errno());
+        }
+        self.signo.set(Some(signo));
+        Ok(())
+    }
+
+    /// Stops signalling interrupts of the pin
+    ///
+    /// The signal stays blocked for the calling thread.
+    ///
+    /// # Errors
+    /// Returns the negative errno if the ioctl fails
+    pub fn unregister(&self) -> GpioResult<()> {
+        // SAFETY: GPIOC_UNREGISTER takes no argument
+        let result = unsafe { ioctl(self.fd, GPIOC_UNREGISTER as _, 0) };
+
+        if result < 0 {
+            return Err(errno());
+        }
+        self.signo.set(None);
+        Ok(())
+    }
+
+    /// Waits for the next interrupt of the pin
+    ///
+    /// The pin must have been registered with [`Gpio::register_signal`] from the
+    /// calling thread.
+    ///
+    /// # Arguments
+    /// * `timeout` - Maximum time to wait, or None to wait indefinitely
+    ///
+    /// # Returns
+    /// - Ok(true) if an interrupt occurred
+    /// - Ok(false) if the timeout expired first
+    ///
+    /// # Errors
+    /// - `-EINVAL` if no signal is registered
+    /// - The negative errno if waiting fails, e.g. `-EINTR` when interrupted by
+    ///   another signal
+    pub fn wait_edge(&self, timeout: Option<Duration>) -> GpioResult<bool> {
+        let Some(signo) = self.signo.get() else {
+            return Err(-EINVAL);
+        };
+
+        // SAFETY: The signal set is initialized by sigemptyset before use, and
+        // no signal information is requested
+        let result = unsafe {
+            let mut set: sigset_t = zeroed();
+            sigemptyset(&mut set);
+            sigaddset(&mut set, signo);
+
+            match timeout {
+                Some(timeout) => {
+                    let ts = timespec {
+                        tv_sec: timeout.as_secs() as _,
+                        tv_nsec: timeout.subsec_nanos() as _,
+                    };
+                    sigtimedwait(&set, ptr::null_mut(), &ts)
+                }
+                None => sigwaitinfo(&set, ptr::null_mut()),
+            }
+        };
+
+        if result >= 0 {
+            return Ok(true);
+        }
+
+        match errno() {
+            err if err == -EAGAIN => Ok(false),
+            err => Err(err),
+        }
+    }
+}
+
+impl AsRawFd for Gpio {
+    /// Returns the file descriptor of the GPIO device. The descriptor stays owned
+    /// by the Gpio.
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd
+    }
+}
+
+impl Drop for Gpio {
+    /// Closes the GPIO device when the Gpio goes out of scope
+    fn drop(&mut self) {
+        unsafe { libc::close(self.fd) };
+    }
+}
+
+/// Several GPIO pins read and written together as a bitset
+///
+/// Bit `i` of the bitsets corresponds to the pin at index `i`, so a bank
+/// holds at most 32 pins. The pins are accessed one after another, as the
+/// GPIO driver offers no multi-pin ioctls, so the operations are not atomic:
+/// other tasks can observe the pins in an intermediate state.
+///
+/// # Examples
+///
+/// ```no_run
+/// use core::ffi::CStr;
+/// use nuttx::io::gpio::{Gpio, GpioBank};
+///
+/// let open = |path: &[u8]| Gpio::open(CStr::from_bytes_with_nul(path).unwrap()).unwrap();
+/// let leds = GpioBank::new([open(b"/dev/gpio0\0"), open(b"/dev/gpio1\0"), open(b"/dev/gpio2\0")]);
+///
+/// // Switch on the first and the third LED, leave the second unchanged
+/// leds.write_mask(0b101, 0b101).unwrap();
+/// let lit = leds.read_mask().unwrap();
+/// ```
+#[derive(Debug)]
+pub struct GpioBank<const N: usize> {
+    pins: [Gpio; N],
+}
+
+impl<const N: usize> GpioBank<N> {
+    /// Groups the pins, the first being bit 0 of the bitsets
+    ///
+    /// A bank holding more than 32 pins fails to compile.
+    pub fn new(pins: [Gpio; N]) -> Self {
+        const { assert!(N <= u32::BITS as usize, "a GpioBank holds at most 32 pins") };
+        GpioBank { pins }
+    }
+
+    /// The pins of the bank
+    pub fn pins(&self) -> &[Gpio; N] {
+        &self.pins
+    }
+
+    /// Releases the pins of the bank
+    pub fn into_pins(self) -> [Gpio; N] {
+        self.pins
+    }
+
+    /// Reads the values of all pins
+    ///
+    /// # Returns
+    /// The bitset with the bits of the high pins set
+    ///
+    /// # Errors
+    /// Returns the negative errno of the first pin that cannot be read
+    pub fn read_mask(&self) -> GpioResult<u32> {
+        self.pins
+            .iter()
+            .enumerate()
+            .try_fold(0, |bits, (i, pin)| Ok(bits | (pin.read()? as u32) << i))
+    }
+
+    /// Drives the pins selected by `mask` to the values of `value`
+    ///
+    /// Pins whose bit in `mask` is clear are left unchanged, and bits beyond
+    /// the pins of the bank are ignored.
+    ///
+    /// # Arguments
+    /// * `mask` - The pins to write
+    /// * `value` - The values, a set bit driving the pin high
+    ///
+    /// # Errors
+    /// Returns the negative errno of the first pin that cannot be written. The
+    /// pins before it have been written already.
+    pub fn write_mask(&self, mask: u32, value: u32) -> GpioResult<()> {
+        self.pins
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| mask & 1 << i != 0)
+            .try_for_each(|(i, pin)| pin.write(value & 1 << i != 0))
+    }
+}
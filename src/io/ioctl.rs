@@ -0,0 +1,151 @@
+//! Typed ioctl helpers
+//!
+//! This module wraps `libc::ioctl` for the common ways a driver exchanges its
+//! argument: reading a structure, writing a structure, updating a structure in
+//! place, passing a value, or passing nothing. Failures are reported as the
+//! negative errno, so device modules can propagate them with `?`.
+//!
+//! The helpers are unsafe, as the meaning of the argument is defined by the
+//! driver for each request and cannot be checked here.
+//!
+//! Requests are taken as [`IoctlRequest`], the type of every ioctl command
+//! constant in this crate, and converted with `as` to the request type
+//! `libc::ioctl` expects on the target only when it is called.
+//!
+//! # Examples
+//!
+//! Commands are taken from bindings generated from the NuttX headers rather
+//! than hardcoded, so they follow the numbering of the NuttX build. Here
+//! `bindings` stands for the application's own bindgen output:
+//!
+//! ```ignore
+//! use core::ffi::CStr;
+//! use nuttx::fd::AsRawFd;
+//! use nuttx::io::ioctl::{IoctlRequest, ioctl_read};
+//! use nuttx::io::leds::UserLeds;
+//!
+//! const ULEDIOC_SUPPORTED: IoctlRequest = bindings::ULEDIOC_SUPPORTED as IoctlRequest;
+//!
+//! let leds = UserLeds::open(CStr::from_bytes_with_nul(b"/dev/userleds\0").unwrap()).unwrap();
+//!
+//! // SAFETY: ULEDIOC_SUPPORTED stores a userled_set_t through its argument
+//! let supported: u32 = unsafe { ioctl_read(leds.as_raw_fd(), ULEDIOC_SUPPORTED) }.unwrap();
+//! ```
+
+use core::mem::zeroed;
+use libc::{c_int, c_ulong, c_void, ioctl};
+
+use crate::errno::errno_result;
+use crate::fd::RawFd;
+
+/// Result type for ioctl operations, errors hold the negative errno
+pub type IoctlResult<T> = Result<T, i32>;
+
+/// Type of ioctl commands, as passed to the helpers of this module
+pub type IoctlRequest = c_ulong;
+
+/// Issues an ioctl that stores a `T` through its argument
+///
+/// # Arguments
+/// * `fd` - The device file descriptor
+/// * `request` - The ioctl command
+///
+/// # Returns
+/// The value stored by the driver, starting from a zeroed `T`
+///
+/// # Errors
+/// Returns the negative errno if the ioctl fails
+///
+/// # Safety
+/// The driver must expect a pointer to a structure with the layout of `T` for
+/// `request`, and `T` must be a plain C type for which zero, and any value the
+/// driver stores, is valid.
+pub unsafe fn ioctl_read<T>(fd: RawFd, request: IoctlRequest) -> IoctlResult<T> {
+    let mut value: T = unsafe { zeroed() };
+    unsafe { ioctl_readwrite(fd, request, &mut value) }?;
+    Ok(value)
+}
+
+/// Issues an ioctl that reads a `T` from its argument
+///
+/// # Arguments
+/// * `fd` - The device file descriptor
+/// * `request` - The ioctl command
+/// * `arg` - The argument passed to the driver
+///
+/// # Returns
+/// The non-negative result of the ioctl
+///
+/// # Errors
+/// Returns the negative errno if the ioctl fails
+///
+/// # Safety
+/// The driver must expect a pointer to a structure with the layout of `T` for
+/// `request`, and must not write through it. Pointers inside `T` must be valid
+/// for the accesses the driver performs.
+pub unsafe fn ioctl_write<T>(fd: RawFd, request: IoctlRequest, arg: &T) -> IoctlResult<c_int> {
+    errno_result(unsafe { ioctl(fd, request as _, arg as *const T as *mut c_void) })
+}
+
+/// Issues an ioctl that reads and updates a `T` through its argument
+///
+/// # Arguments
+/// * `fd` - The device file descriptor
+/// * `request` - The ioctl command
+/// * `arg` - The argument, read and/or written by the driver
+///
+/// # Returns
+/// The non-negative result of the ioctl
+///
+/// # Errors
+/// Returns the negative errno if the ioctl fails
+///
+/// # Safety
+/// The driver must expect a pointer to a structure with the layout of `T` for
+/// `request`, and any value the driver stores must be valid for `T`. Pointers
+/// inside `T` must be valid for the accesses the driver performs.
+pub unsafe fn ioctl_readwrite<T>(
+    fd: RawFd,
+    request: IoctlRequest,
+    arg: &mut T,
+) -> IoctlResult<c_int> {
+    errno_result(unsafe { ioctl(fd, request as _, arg as *mut T as *mut c_void) })
+}
+
+/// Issues an ioctl that takes its argument by value
+///
+/// # Arguments
+/// * `fd` - The device file descriptor
+/// * `request` - The ioctl command
+/// * `arg` - The value passed to the driver
+///
+/// # Returns
+/// The non-negative result of the ioctl
+///
+/// # Errors
+/// Returns the negative errno if the ioctl fails
+///
+/// # Safety
+/// The driver must interpret the argument of `request` as a value, not as a
+/// pointer.
+pub unsafe fn ioctl_value(fd: RawFd, request: IoctlRequest, arg: c_ulong) -> IoctlResult<c_int> {
+    errno_result(unsafe { ioctl(fd, request as _, arg) })
+}
+
+/// Issues an ioctl that takes no argument
+///
+/// # Arguments
+/// * `fd` - The device file descriptor
+/// * `request` - The ioctl command
+///
+/// # Returns
+/// The non-negative result of the ioctl
+///
+/// # Errors
+/// Returns the negative errno if the ioctl fails
+///
+/// # Safety
+/// The driver must ignore the argument of `request`.
+pub unsafe fn ioctl_none(fd: RawFd, request: IoctlRequest) -> IoctlResult<c_int> {
+    unsafe { ioctl_value(fd, request, 0) }
+}
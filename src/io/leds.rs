@@ -0,0 +1,178 @@
+//! User LED driver interface
+//!
+//! This module provides Rust bindings for the NuttX user LED driver, which
+//! exposes the board LEDs as a single device such as `/dev/userleds`.
+//!
+//! The implementation matches the NuttX user LED interface defined in
+//! `nuttx/include/nuttx/leds/userled.h`.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use core::ffi::CStr;
+//! use nuttx::io::leds::UserLeds;
+//!
+//! let leds = UserLeds::open(CStr::from_bytes_with_nul(b"/dev/userleds\0").unwrap()).unwrap();
+//! leds.set_led(0, true).unwrap();
+//! ```
+
+use core::ffi::CStr;
+use libc::{EINVAL, O_WRONLY, c_int, c_ulong, c_void, ioctl, open};
+
+use crate::bindings::{userled_s, userled_set_t};
+use crate::errno::errno;
+use crate::fd::{AsRawFd, RawFd};
+use crate::io::ioctl::IoctlRequest;
+
+/// IOCTL command to get the set of LEDs supported by the board
+///
+/// Matches C's ULEDIOC_SUPPORTED
+const ULEDIOC_SUPPORTED: IoctlRequest = crate::bindings::ULEDIOC_SUPPORTED as IoctlRequest;
+
+/// IOCTL command to set the state of a single LED
+///
+/// Matches C's ULEDIOC_SETLED
+const ULEDIOC_SETLED: IoctlRequest = crate::bindings::ULEDIOC_SETLED as IoctlRequest;
+
+/// IOCTL command to set the state of all LEDs
+///
+/// Matches C's ULEDIOC_SETALL
+const ULEDIOC_SETALL: IoctlRequest = crate::bindings::ULEDIOC_SETALL as IoctlRequest;
+
+/// Result type for user LED operations, errors hold the negative errno
+pub type LedResult<T> = Result<T, i32>;
+
+/// Represents an open user LED device
+///
+/// The underlying device is closed when the UserLeds is dropped.
+#[derive(Debug)]
+pub struct UserLeds {
+    fd: c_int,
+}
+
+impl UserLeds {
+    /// Opens the user LED device at the specified path
+    ///
+    /// # Arguments
+    /// * `path` - Path to the LED device as a C string (e.g. "/dev/userleds")
+    ///
+    /// # Errors
+    /// Returns the negative errno if the device could not be opened
+    pub fn open(path: &CStr) -> LedResult<Self> {
+        let fd = unsafe { open(path.as_ptr(), O_WRONLY) };
+        if fd < 0 {
+            return Err(errno());
+        }
+        Ok(UserLeds { fd })
+    }
+
+    /// Gets the set of LEDs supported by the board
+    ///
+    /// # Returns
+    /// A bitmask with bit N set if LED N is available
+    ///
+    /// # Errors
+    /// Returns the negative errno if the ioctl fails
+    pub fn supported(&self) -> LedResult<u32> {
+        let mut supported: userled_set_t = 0;
+
+        // SAFETY: ULEDIOC_SUPPORTED stores a userled_set_t through the passed pointer
+        let result = unsafe {
+            ioctl(
+                self.fd,
+                ULEDIOC_SUPPORTED as _,
+                &mut supported as *mut userled_set_t as *mut c_void,
+            )
+        };
+
+        if result < 0 {
+            Err(errno())
+        } else {
+            Ok(supported as u32)
+        }
+    }
+
+    /// Gets the width of the LED bitmask
+    ///
+    /// # Returns
+    /// The number of bits needed to address every supported LED, i.e. the index of
+    /// the highest supported LED plus one
+    ///
+    /// # Errors
+    /// Returns the negative errno if the ioctl fails
+    pub fn count(&self) -> LedResult<u8> {
+        Ok((u32::BITS - self.supported()?.leading_zeros()) as u8)
+    }
+
+    /// Sets the state of all LEDs at once
+    ///
+    /// # Arguments
+    /// * `bits` - Bitmask with bit N set to turn LED N on
+    ///
+    /// # Errors
+    /// - `-EINVAL` if `bits` addresses LEDs beyond [`UserLeds::count`]
+    /// - The negative errno if the ioctl fails
+    pub fn set_all(&self, bits: u32) -> LedResult<()> {
+        let count = self.count()?;
+        if u32::BITS - bits.leading_zeros() > count as u32 {
+            return Err(-EINVAL);
+        }
+
+        // SAFETY: ULEDIOC_SETALL takes the bitmask itself rather than a pointer
+        let result = unsafe {
+            ioctl(
+                self.fd,
+                ULEDIOC_SETALL as _,
+                bits as userled_set_t as c_ulong,
+            )
+        };
+
+        if result < 0 { Err(errno()) } else { Ok(()) }
+    }
+
+    /// Turns a single LED on or off
+    ///
+    /// # Arguments
+    /// * `led` - Index of the LED
+    /// * `on` - true to turn the LED on, false to turn it off
+    ///
+    /// # Errors
+    /// - `-EINVAL` if `led` is not below [`UserLeds::count`]
+    /// - The negative errno if the ioctl fails
+    pub fn set_led(&self, led: u8, on: bool) -> LedResult<()> {
+        if led >= self.count()? {
+            return Err(-EINVAL);
+        }
+
+        let mut state = userled_s {
+            ul_led: led as _,
+            ul_on: on,
+        };
+
+        // SAFETY: The driver reads the userled_s structure during the ioctl
+        let result = unsafe {
+            ioctl(
+                self.fd,
+                ULEDIOC_SETLED as _,
+                &mut state as *mut userled_s as *mut c_void,
+            )
+        };
+
+        if result < 0 { Err(errno()) } else { Ok(()) }
+    }
+}
+
+impl AsRawFd for UserLeds {
+    /// Returns the file descriptor of the LED device. The descriptor stays owned
+    /// by the UserLeds.
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd
+    }
+}
+
+impl Drop for UserLeds {
+    /// Closes the LED device when the UserLeds goes out of scope
+    fn drop(&mut self) {
+        unsafe { libc::close(self.fd) };
+    }
+}
@@ -0,0 +1,3 @@
+pub mod gpio;
+pub mod ioctl;
+pub mod leds;
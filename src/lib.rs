@@ -5,6 +5,9 @@
 
 #![no_std]
 
+#[cfg(any(feature = "std", test))]
+extern crate std;
+
 // Private module for generated bindings - not exposed in public API
 #[allow(
     non_snake_case,
@@ -16,5 +19,16 @@ mod bindings {
     include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
 }
 
+pub mod analog;
+pub mod audio;
+pub mod bus;
+pub mod drivers;
+mod errno;
+pub mod fd;
 pub mod input;
+pub mod io;
+pub mod power;
+pub mod sensors;
+pub mod serial;
+pub mod timers;
 pub mod video;
@@ -0,0 +1,264 @@
+//! Battery driver interface
+//!
+//! This module provides Rust bindings for the NuttX battery character drivers.
+//! It allows querying the charge state, health and measurements of a battery
+//! exposed by a fuel gauge or charger, e.g. `/dev/bat0`.
+//!
+//! The implementation matches the NuttX battery interface defined in
+//! `nuttx/include/nuttx/power/battery_ioctl.h`. Fixed point measurements are
+//! converted to `f32`. Not every driver supports every query: gauges usually
+//! don't report the health and chargers usually don't report the capacity, in
+//! which case the driver fails with `-ENOTTY` or `-ENOSYS`.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use core::ffi::CStr;
+//! use nuttx::power::battery::{Battery, ChargeState};
+//!
+//! let battery = Battery::open(CStr::from_bytes_with_nul(b"/dev/bat0\0").unwrap()).unwrap();
+//! if battery.state().unwrap() == ChargeState::Discharging {
+//!     let capacity = battery.capacity().unwrap();
+//!     // capacity is in percent of the full charge
+//! }
+//! ```
+
+use core::ffi::CStr;
+use core::mem::zeroed;
+use libc::{O_RDONLY, c_int, c_void, ioctl, open};
+
+use crate::bindings::{
+    battery_health_e, battery_health_e_BATTERY_HEALTH_COLD, battery_health_e_BATTERY_HEALTH_DEAD,
+    battery_health_e_BATTERY_HEALTH_DISCONNECTED, battery_health_e_BATTERY_HEALTH_GOOD,
+    battery_health_e_BATTERY_HEALTH_OVERHEAT, battery_health_e_BATTERY_HEALTH_OVERVOLTAGE,
+    battery_health_e_BATTERY_HEALTH_SAFE_TMR_EXP, battery_health_e_BATTERY_HEALTH_UNSPEC_FAIL,
+    battery_health_e_BATTERY_HEALTH_WD_TMR_EXP, battery_status_e,
+    battery_status_e_BATTERY_CHARGING, battery_status_e_BATTERY_DISCHARGING,
+    battery_status_e_BATTERY_FAULT, battery_status_e_BATTERY_FULL, battery_status_e_BATTERY_IDLE,
+};
+use crate::errno::errno;
+use crate::fd::{AsRawFd, RawFd};
+use crate::io::ioctl::IoctlRequest;
+
+/// IOCTL command to get the charge state
+///
+/// Matches C's BATIOC_STATE
+const BATIOC_STATE: IoctlRequest = crate::bindings::BATIOC_STATE as IoctlRequest;
+
+/// IOCTL command to get the health
+///
+/// Matches C's BATIOC_HEALTH
+const BATIOC_HEALTH: IoctlRequest = crate::bindings::BATIOC_HEALTH as IoctlRequest;
+
+/// IOCTL command to get the voltage
+///
+/// Matches C's BATIOC_VOLTAGE
+const BATIOC_VOLTAGE: IoctlRequest = crate::bindings::BATIOC_VOLTAGE as IoctlRequest;
+
+/// IOCTL command to get the current
+///
+/// Matches C's BATIOC_CURRENT
+const BATIOC_CURRENT: IoctlRequest = crate::bindings::BATIOC_CURRENT as IoctlRequest;
+
+/// IOCTL command to get the remaining capacity
+///
+/// Matches C's BATIOC_CAPACITY
+const BATIOC_CAPACITY: IoctlRequest = crate::bindings::BATIOC_CAPACITY as IoctlRequest;
+
+/// IOCTL command to get the temperature
+///
+/// Matches C's BATIOC_TEMPERATURE
+const BATIOC_TEMPERATURE: IoctlRequest = crate::bindings::BATIOC_TEMPERATURE as IoctlRequest;
+
+/// Result type for battery operations, errors hold the negative errno
+pub type BatteryResult<T> = Result<T, i32>;
+
+/// Charge state of a battery
+///
+/// Mirrors C's `enum battery_status_e`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChargeState {
+    /// BATTERY_UNKNOWN, or a state without a known definition
+    Unknown,
+    /// BATTERY_IDLE, neither full, charging nor discharging
+    Idle,
+    /// BATTERY_CHARGING
+    Charging,
+    /// BATTERY_DISCHARGING
+    Discharging,
+    /// BATTERY_FULL
+    Full,
+    /// BATTERY_FAULT, see [`Battery::health`] for the cause
+    Fault,
+}
+
+impl From<battery_status_e> for ChargeState {
+    /// Decodes a charge state as reported by BATIOC_STATE
+    #[allow(non_upper_case_globals)]
+    fn from(state: battery_status_e) -> Self {
+        match state {
+            battery_status_e_BATTERY_IDLE => ChargeState::Idle,
+            battery_status_e_BATTERY_CHARGING => ChargeState::Charging,
+            battery_status_e_BATTERY_DISCHARGING => ChargeState::Discharging,
+            battery_status_e_BATTERY_FULL => ChargeState::Full,
+            battery_status_e_BATTERY_FAULT => ChargeState::Fault,
+            _ => ChargeState::Unknown,
+        }
+    }
+}
+
+/// Health of a battery
+///
+/// Mirrors C's `enum battery_health_e`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Health {
+    /// BATTERY_HEALTH_UNKNOWN, or a health without a known definition
+    Unknown,
+    /// BATTERY_HEALTH_GOOD
+    Good,
+    /// BATTERY_HEALTH_DEAD
+    Dead,
+    /// BATTERY_HEALTH_OVERHEAT
+    Overheat,
+    /// BATTERY_HEALTH_OVERVOLTAGE
+    Overvoltage,
+    /// BATTERY_HEALTH_UNSPEC_FAIL, an unspecified failure
+    Failure,
+    /// BATTERY_HEALTH_COLD
+    Cold,
+    /// BATTERY_HEALTH_WD_TMR_EXP, the charger watchdog expired
+    WatchdogExpired,
+    /// BATTERY_HEALTH_SAFE_TMR_EXP, the charger safety timer expired
+    SafetyTimerExpired,
+    /// BATTERY_HEALTH_DISCONNECTED
+    Disconnected,
+}
+
+impl From<battery_health_e> for Health {
+    /// Decodes a health as reported by BATIOC_HEALTH
+    #[allow(non_upper_case_globals)]
+    fn from(health: battery_health_e) -> Self {
+        match health {
+            battery_health_e_BATTERY_HEALTH_GOOD => Health::Good,
+            battery_health_e_BATTERY_HEALTH_DEAD => Health::Dead,
+            battery_health_e_BATTERY_HEALTH_OVERHEAT => Health::Overheat,
+            battery_health_e_BATTERY_HEALTH_OVERVOLTAGE => Health::Overvoltage,
+            battery_health_e_BATTERY_HEALTH_UNSPEC_FAIL => Health::Failure,
+            battery_health_e_BATTERY_HEALTH_COLD => Health::Cold,
+            battery_health_e_BATTERY_HEALTH_WD_TMR_EXP => Health::WatchdogExpired,
+            battery_health_e_BATTERY_HEALTH_SAFE_TMR_EXP => Health::SafetyTimerExpired,
+            battery_health_e_BATTERY_HEALTH_DISCONNECTED => Health::Disconnected,
+            _ => Health::Unknown,
+        }
+    }
+}
+
+/// Represents an open battery device
+#[derive(Debug)]
+pub struct Battery {
+    fd: c_int,
+}
+
+impl Battery {
+    /// Opens a battery device at the specified path
+    ///
+    /// # Arguments
+    /// * `path` - Path to the battery device as a C string (e.g. "/dev/bat0")
+    ///
+    /// # Errors
+    /// Returns the negative errno if the device could not be opened
+    pub fn open(path: &CStr) -> BatteryResult<Self> {
+        let fd = unsafe { open(path.as_ptr(), O_RDONLY) };
+        if fd < 0 {
+            return Err(errno());
+        }
+        Ok(Battery { fd })
+    }
+
+    /// Gets the charge state of the battery
+    ///
+    /// # Errors
+    /// Returns the negative errno if the ioctl fails
+    pub fn state(&self) -> BatteryResult<ChargeState> {
+        let state: c_int = self.get(BATIOC_STATE)?;
+        Ok(ChargeState::from(state as battery_status_e))
+    }
+
+    /// Gets the health of the battery
+    ///
+    /// # Errors
+    /// Returns the negative errno if the ioctl fails
+    pub fn health(&self) -> BatteryResult<Health> {
+        let health: c_int = self.get(BATIOC_HEALTH)?;
+        Ok(Health::from(health as battery_health_e))
+    }
+
+    /// Gets the voltage of the battery in volts
+    ///
+    /// # Errors
+    /// Returns the negative errno if the ioctl fails
+    pub fn voltage(&self) -> BatteryResult<f32> {
+        self.get(BATIOC_VOLTAGE).map(b16_to_f32)
+    }
+
+    /// Gets the current through the battery in amperes
+    ///
+    /// The current is negative while discharging.
+    ///
+    /// # Errors
+    /// Returns the negative errno if the ioctl fails
+    pub fn current(&self) -> BatteryResult<f32> {
+        self.get(BATIOC_CURRENT).map(b16_to_f32)
+    }
+
+    /// Gets the remaining capacity of the battery in percent of the full charge
+    ///
+    /// # Errors
+    /// Returns the negative errno if the ioctl fails
+    pub fn capacity(&self) -> BatteryResult<f32> {
+        self.get(BATIOC_CAPACITY).map(b16_to_f32)
+    }
+
+    /// Gets the temperature of the battery in degrees Celsius
+    ///
+    /// # Errors
+    /// Returns the negative errno if the ioctl fails
+    pub fn temperature(&self) -> BatteryResult<f32> {
+        self.get(BATIOC_TEMPERATURE).map(b8_to_f32)
+    }
+
+    /// Issues an ioctl storing a value of type T through the passed pointer
+    fn get<T: Copy>(&self, cmd: IoctlRequest) -> BatteryResult<T> {
+        // SAFETY: T is a plain integer type, for which zero is valid
+        let mut value: T = unsafe { zeroed() };
+
+        let result = unsafe { ioctl(self.fd, cmd as _, &mut value as *mut T as *mut c_void) };
+
+        if result < 0 { Err(errno()) } else { Ok(value) }
+    }
+}
+
+/// Converts a C `b16_t` fixed point number with 16 fractional bits
+fn b16_to_f32(value: i32) -> f32 {
+    value as f32 / 65536.0
+}
+
+/// Converts a C `b8_t` fixed point number with 8 fractional bits
+fn b8_to_f32(value: i16) -> f32 {
+    value as f32 / 256.0
+}
+
+impl AsRawFd for Battery {
+    /// Returns the file descriptor of the battery device. The descriptor stays
+    /// owned by the Battery.
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd
+    }
+}
+
+impl Drop for Battery {
+    /// Closes the battery device when the Battery goes out of scope
+    fn drop(&mut self) {
+        unsafe { libc::close(self.fd) };
+    }
+}
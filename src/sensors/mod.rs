@@ -0,0 +1,218 @@
+//! Sensor framework interface
+//!
+//! This module provides Rust bindings for the NuttX sensor framework. Sensors
+//! are exposed as `/dev/uorb/sensor_*` nodes, each delivering events as a
+//! fixed C structure, e.g. `struct sensor_accel` for accelerometers.
+//!
+//! The implementation matches the NuttX sensor interface defined in
+//! `nuttx/include/nuttx/sensors/ioctl.h` and `nuttx/include/nuttx/uorb.h`.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use core::ffi::CStr;
+//! use core::time::Duration;
+//! use nuttx::sensors::{Accelerometer, SensorAccel};
+//!
+//! let path = CStr::from_bytes_with_nul(b"/dev/uorb/sensor_accel0\0").unwrap();
+//! let mut accel = Accelerometer::open(path).unwrap();
+//! accel.set_interval(Duration::from_millis(10)).unwrap();
+//!
+//! // SAFETY: Sensor events are plain C structures
+//! let mut events: [SensorAccel; 8] = unsafe { core::mem::zeroed() };
+//! let count = accel.read_samples(&mut events).unwrap();
+//! for event in &events[..count] {
+//!     // event.x, event.y, event.z are in m/s^2
+//! }
+//! ```
+
+use core::ffi::CStr;
+use core::marker::PhantomData;
+use core::mem::{size_of, size_of_val};
+use core::time::Duration;
+use libc::{O_RDONLY, c_int, c_ulong, c_void, ioctl, open, read};
+
+use crate::bindings::{sensor_accel, sensor_baro, sensor_gyro, sensor_mag};
+use crate::errno::errno;
+use crate::fd::{AsRawFd, RawFd};
+use crate::io::ioctl::IoctlRequest;
+
+/// IOCTL command to enable or disable a sensor
+///
+/// Matches C's SNIOC_ACTIVATE
+const SNIOC_ACTIVATE: IoctlRequest = crate::bindings::SNIOC_ACTIVATE as IoctlRequest;
+
+/// IOCTL command to set the interval between sensor events
+///
+/// Matches C's SNIOC_SET_INTERVAL
+const SNIOC_SET_INTERVAL: IoctlRequest = crate::bindings::SNIOC_SET_INTERVAL as IoctlRequest;
+
+/// IOCTL command to set the maximum delay before buffered events are reported
+///
+/// Matches C's SNIOC_BATCH
+const SNIOC_BATCH: IoctlRequest = crate::bindings::SNIOC_BATCH as IoctlRequest;
+
+/// Result type for sensor operations, errors hold the negative errno
+pub type SensorResult<T> = Result<T, i32>;
+
+/// Accelerometer event, alias for C's `sensor_accel`
+pub type SensorAccel = sensor_accel;
+
+/// Gyroscope event, alias for C's `sensor_gyro`
+pub type SensorGyro = sensor_gyro;
+
+/// Magnetometer event, alias for C's `sensor_mag`
+pub type SensorMag = sensor_mag;
+
+/// Barometer event, alias for C's `sensor_baro`
+pub type SensorBaro = sensor_baro;
+
+/// Reader for accelerometer nodes, e.g. `/dev/uorb/sensor_accel0`
+pub type Accelerometer = Sensor<SensorAccel>;
+
+/// Reader for gyroscope nodes, e.g. `/dev/uorb/sensor_gyro0`
+pub type Gyroscope = Sensor<SensorGyro>;
+
+/// Reader for magnetometer nodes, e.g. `/dev/uorb/sensor_mag0`
+pub type Magnetometer = Sensor<SensorMag>;
+
+/// Reader for barometer nodes, e.g. `/dev/uorb/sensor_baro0`
+pub type Barometer = Sensor<SensorBaro>;
+
+/// Marker for C structures delivered by sensor nodes
+///
+/// # Safety
+/// Implementors must be plain C structures for which any bit pattern is a
+/// valid value, as events are copied from the driver byte by byte.
+pub unsafe trait SensorEvent: Copy {}
+
+unsafe impl SensorEvent for SensorAccel {}
+unsafe impl SensorEvent for SensorGyro {}
+unsafe impl SensorEvent for SensorMag {}
+unsafe impl SensorEvent for SensorBaro {}
+
+/// Represents an open sensor node delivering events of type `T`
+#[derive(Debug)]
+pub struct Sensor<T: SensorEvent> {
+    fd: c_int,
+    _event: PhantomData<T>,
+}
+
+impl<T: SensorEvent> Sensor<T> {
+    /// Opens a sensor node at the specified path
+    ///
+    /// The node must deliver events of type `T`, e.g. a [`SensorAccel`] for
+    /// `/dev/uorb/sensor_accel0`.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the sensor node as a C string
+    ///
+    /// # Errors
+    /// Returns the negative errno if the node could not be opened
+    pub fn open(path: &CStr) -> SensorResult<Self> {
+        let fd = unsafe { open(path.as_ptr(), O_RDONLY) };
+        if fd < 0 {
+            return Err(errno());
+        }
+        Ok(Sensor {
+            fd,
+            _event: PhantomData,
+        })
+    }
+
+    /// Starts the sensor producing events
+    ///
+    /// # Errors
+    /// Returns the negative errno if the ioctl fails
+    pub fn enable(&self) -> SensorResult<()> {
+        self.command(SNIOC_ACTIVATE, 1)
+    }
+
+    /// Stops the sensor producing events, e.g. to save power
+    ///
+    /// # Errors
+    /// Returns the negative errno if the ioctl fails
+    pub fn disable(&self) -> SensorResult<()> {
+        self.command(SNIOC_ACTIVATE, 0)
+    }
+
+    /// Sets the interval between sensor events
+    ///
+    /// # Arguments
+    /// * `period` - The interval, rounded up to whole microseconds
+    ///
+    /// # Errors
+    /// Returns `-EINVAL` if the interval exceeds the 32 bit microsecond range
+    /// of the driver, or the negative errno if the ioctl fails
+    pub fn set_interval(&self, period: Duration) -> SensorResult<()> {
+        self.command(SNIOC_SET_INTERVAL, micros(period)?)
+    }
+
+    /// Sets the maximum delay before buffered events are reported
+    ///
+    /// A latency of zero reports each event as soon as it is available.
+    ///
+    /// # Arguments
+    /// * `latency` - The latency, rounded up to whole microseconds
+    ///
+    /// # Errors
+    /// Returns `-EINVAL` if the latency exceeds the 32 bit microsecond range
+    /// of the driver, or the negative errno if the ioctl fails, e.g.
+    /// `-ENOTSUP` if the sensor does not support batching
+    pub fn set_batch_latency(&self, latency: Duration) -> SensorResult<()> {
+        self.command(SNIOC_BATCH, micros(latency)?)
+    }
+
+    /// Reads the available sensor events
+    ///
+    /// # Arguments
+    /// * `buf` - Buffer receiving the events
+    ///
+    /// # Returns
+    /// The number of events stored at the start of `buf`
+    ///
+    /// # Errors
+    /// Returns the negative errno if the read fails
+    pub fn read_samples(&mut self, buf: &mut [T]) -> SensorResult<usize> {
+        // SAFETY: T is a plain C structure valid for any bit pattern, and the
+        // read is bounded by the size of buf
+        let result = unsafe { read(self.fd, buf.as_mut_ptr() as *mut c_void, size_of_val(buf)) };
+
+        if result < 0 {
+            Err(errno())
+        } else {
+            Ok(result as usize / size_of::<T>())
+        }
+    }
+
+    /// Issues an ioctl that takes its argument by value
+    fn command(&self, cmd: IoctlRequest, arg: c_ulong) -> SensorResult<()> {
+        let result = unsafe { ioctl(self.fd, cmd as _, arg) };
+
+        if result < 0 { Err(errno()) } else { Ok(()) }
+    }
+}
+
+/// Converts a duration to the microseconds passed to the driver, which stores
+/// them as `uint32_t`
+fn micros(duration: Duration) -> SensorResult<c_ulong> {
+    let us = duration.as_nanos().div_ceil(1000);
+    u32::try_from(us)
+        .map(|us| us as c_ulong)
+        .map_err(|_| -libc::EINVAL)
+}
+
+impl<T: SensorEvent> AsRawFd for Sensor<T> {
+    /// Returns the file descriptor of the sensor node. The descriptor stays
+    /// owned by the Sensor.
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd
+    }
+}
+
+impl<T: SensorEvent> Drop for Sensor<T> {
+    /// Closes the sensor node when the Sensor goes out of scope
+    fn drop(&mut self) {
+        unsafe { libc::close(self.fd) };
+    }
+}
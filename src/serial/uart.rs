@@ -0,0 +1,284 @@
+//! Serial port interface
+//!
+//! This module provides a safe wrapper around NuttX serial devices such as
+//! `/dev/ttyS0`. It allows configuring the line settings through termios and
+//! exchanging data with the port.
+//!
+//! The implementation matches the NuttX termios interface defined in
+//! `nuttx/include/termios.h`.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use core::ffi::CStr;
+//! use nuttx::serial::uart::{DataBits, Parity, Serial, StopBits};
+//!
+//! let serial = Serial::open(CStr::from_bytes_with_nul(b"/dev/ttyS1\0").unwrap()).unwrap();
+//! serial
+//!     .configure(115200, DataBits::Eight, Parity::None, StopBits::One)
+//!     .unwrap();
+//! serial.write(b"hello\r\n").unwrap();
+//! ```
+
+use core::ffi::CStr;
+use core::mem::zeroed;
+use libc::{EINVAL, F_GETFL, F_SETFL, O_NONBLOCK, O_RDWR, c_int, c_void, fcntl, open, read, write};
+
+use crate::bindings::{
+    B0, B50, B75, B110, B134, B150, B200, B300, B600, B1200, B1800, B2400, B4800, B9600, B19200,
+    B38400, B57600, B115200, B230400, B460800, B500000, B576000, B921600, B1000000, B1152000,
+    B1500000, B2000000, B2500000, B3000000, B3500000, B4000000, CS5, CS6, CS7, CS8, CSIZE, CSTOPB,
+    PARENB, PARODD, TCIOFLUSH, TCSANOW, cfsetspeed, speed_t, tcflag_t, tcflush, tcgetattr,
+    tcsetattr, termios,
+};
+use crate::errno::errno;
+use crate::fd::{AsRawFd, RawFd};
+
+/// Result type for serial operations, errors hold the negative errno
+pub type SerialResult<T> = Result<T, i32>;
+
+/// Number of data bits per character
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataBits {
+    /// CS5
+    Five,
+    /// CS6
+    Six,
+    /// CS7
+    Seven,
+    /// CS8
+    Eight,
+}
+
+/// Parity bit appended to each character
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Parity {
+    /// No parity bit
+    None,
+    /// Even parity
+    Even,
+    /// Odd parity
+    Odd,
+}
+
+/// Number of stop bits after each character
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopBits {
+    /// One stop bit
+    One,
+    /// Two stop bits, CSTOPB
+    Two,
+}
+
+/// Represents an open serial port
+///
+/// The underlying device is closed when the Serial is dropped.
+#[derive(Debug)]
+pub struct Serial {
+    fd: c_int,
+}
+
+impl Serial {
+    /// Opens a serial port at the specified path
+    ///
+    /// # Arguments
+    /// * `path` - Path to the serial device as a C string (e.g. "/dev/ttyS1")
+    ///
+    /// # Errors
+    /// Returns the negative errno if the device could not be opened
+    pub fn open(path: &CStr) -> SerialResult<Self> {
+        let fd = unsafe { open(path.as_ptr(), O_RDWR) };
+        if fd < 0 {
+            return Err(errno());
+        }
+        Ok(Serial { fd })
+    }
+
+    /// Configures the line settings of the port
+    ///
+    /// Other terminal settings, such as line discipline flags, are preserved.
+    ///
+    /// # Arguments
+    /// * `baud` - The baud rate, one of the standard rates from 50 to 4000000
+    /// * `data_bits` - The number of data bits per character
+    /// * `parity` - The parity bit
+    /// * `stop_bits` - The number of stop bits
+    ///
+    /// # Errors
+    /// - `-EINVAL` if the baud rate is not a standard rate
+    /// - The negative errno if the port rejects the settings
+    pub fn configure(
+        &self,
+        baud: u32,
+        data_bits: DataBits,
+        parity: Parity,
+        stop_bits: StopBits,
+    ) -> SerialResult<()> {
+        let speed = baud_to_speed(baud).ok_or(-EINVAL)?;
+
+        // SAFETY: The termios structure is filled by tcgetattr before use
+        let mut tio: termios = unsafe { zeroed() };
+        if unsafe { tcgetattr(self.fd, &mut tio) } < 0 {
+            return Err(errno());
+        }
+
+        let size = match data_bits {
+            DataBits::Five => CS5,
+            DataBits::Six => CS6,
+            DataBits::Seven => CS7,
+            DataBits::Eight => CS8,
+        };
+        let parity = match parity {
+            Parity::None => 0,
+            Parity::Even => PARENB,
+            Parity::Odd => PARENB | PARODD,
+        };
+        let stop = match stop_bits {
+            StopBits::One => 0,
+            StopBits::Two => CSTOPB,
+        };
+
+        tio.c_cflag &= !((CSIZE | PARENB | PARODD | CSTOPB) as tcflag_t);
+        tio.c_cflag |= (size | parity | stop) as tcflag_t;
+
+        if unsafe { cfsetspeed(&mut tio, speed) } < 0 {
+            return Err(errno());
+        }
+        if unsafe { tcsetattr(self.fd, TCSANOW as c_int, &tio) } < 0 {
+            return Err(errno());
+        }
+        Ok(())
+    }
+
+    /// Reads received bytes from the port
+    ///
+    /// # Arguments
+    /// * `buf` - Buffer receiving the bytes
+    ///
+    /// # Returns
+    /// The number of bytes stored in `buf`
+    ///
+    /// # Errors
+    /// Returns the negative errno if the read fails, e.g. `-EAGAIN` if the port
+    /// is non-blocking and no data is available
+    pub fn read(&self, buf: &mut [u8]) -> SerialResult<usize> {
+        let ret = unsafe { read(self.fd, buf.as_mut_ptr() as *mut c_void, buf.len()) };
+
+        if ret < 0 {
+            Err(errno())
+        } else {
+            Ok(ret as usize)
+        }
+    }
+
+    /// Writes bytes to the port
+    ///
+    /// # Arguments
+    /// * `data` - The bytes to send
+    ///
+    /// # Returns
+    /// The number of bytes accepted by the port
+    ///
+    /// # Errors
+    /// Returns the negative errno if the write fails
+    pub fn write(&self, data: &[u8]) -> SerialResult<usize> {
+        let ret = unsafe { write(self.fd, data.as_ptr() as *const c_void, data.len()) };
+
+        if ret < 0 {
+            Err(errno())
+        } else {
+            Ok(ret as usize)
+        }
+    }
+
+    /// Switches the port between blocking and non-blocking mode
+    ///
+    /// # Arguments
+    /// * `nonblock` - true to return immediately from reads and writes that
+    ///   cannot make progress
+    ///
+    /// # Errors
+    /// Returns the negative errno if the file flags could not be changed
+    pub fn set_nonblocking(&self, nonblock: bool) -> SerialResult<()> {
+        let flags = unsafe { fcntl(self.fd, F_GETFL) };
+        if flags < 0 {
+            return Err(errno());
+        }
+
+        let flags = if nonblock {
+            flags | O_NONBLOCK
+        } else {
+            flags & !O_NONBLOCK
+        };
+
+        if unsafe { fcntl(self.fd, F_SETFL, flags) } < 0 {
+            return Err(errno());
+        }
+        Ok(())
+    }
+
+    /// Discards received data not yet read and data written but not yet sent
+    ///
+    /// # Errors
+    /// Returns the negative errno if the buffers could not be flushed
+    pub fn flush(&self) -> SerialResult<()> {
+        if unsafe { tcflush(self.fd, TCIOFLUSH as c_int) } < 0 {
+            return Err(errno());
+        }
+        Ok(())
+    }
+}
+
+/// Converts a baud rate into the matching termios speed
+fn baud_to_speed(baud: u32) -> Option<speed_t> {
+    let speed = match baud {
+        0 => B0,
+        50 => B50,
+        75 => B75,
+        110 => B110,
+        134 => B134,
+        150 => B150,
+        200 => B200,
+        300 => B300,
+        600 => B600,
+        1200 => B1200,
+        1800 => B1800,
+        2400 => B2400,
+        4800 => B4800,
+        9600 => B9600,
+        19200 => B19200,
+        38400 => B38400,
+        57600 => B57600,
+        115200 => B115200,
+        230400 => B230400,
+        460800 => B460800,
+        500000 => B500000,
+        576000 => B576000,
+        921600 => B921600,
+        1000000 => B1000000,
+        1152000 => B1152000,
+        1500000 => B1500000,
+        2000000 => B2000000,
+        2500000 => B2500000,
+        3000000 => B3000000,
+        3500000 => B3500000,
+        4000000 => B4000000,
+        _ => return None,
+    };
+    Some(speed as speed_t)
+}
+
+impl AsRawFd for Serial {
+    /// Returns the file descriptor of the serial device, e.g. for use in an
+    /// external poll loop. The descriptor stays owned by the Serial.
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd
+    }
+}
+
+impl Drop for Serial {
+    /// Closes the serial device when the Serial goes out of scope
+    fn drop(&mut self) {
+        unsafe { libc::close(self.fd) };
+    }
+}
@@ -0,0 +1,3 @@
+pub mod rtc;
+pub mod timer;
+pub mod watchdog;
@@ -0,0 +1,410 @@
+//! RTC character driver interface
+//!
+//! This module provides Rust bindings for the NuttX RTC driver interface.
+//! It allows reading and setting the calendar time of a real-time clock
+//! such as `/dev/rtc0`, and arming alarms that raise a signal.
+//!
+//! The implementation matches the NuttX RTC interface defined in
+//! `nuttx/include/nuttx/timers/rtc.h`.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use core::ffi::CStr;
+//! use nuttx::timers::rtc::{DateTime, Rtc};
+//!
+//! let rtc = Rtc::open(CStr::from_bytes_with_nul(b"/dev/rtc0\0").unwrap()).unwrap();
+//! let now = DateTime::from(rtc.read_time().unwrap());
+//! ```
+
+use core::ffi::CStr;
+use core::mem::zeroed;
+use kconfig::kconfig;
+use libc::{O_RDONLY, c_int, c_void, ioctl, open};
+
+use crate::bindings::rtc_time;
+use crate::errno::errno;
+use crate::fd::{AsRawFd, RawFd};
+use crate::io::ioctl::IoctlRequest;
+
+/// IOCTL command to read the current time
+///
+/// Matches C's RTC_RD_TIME
+const RTC_RD_TIME: IoctlRequest = crate::bindings::RTC_RD_TIME as IoctlRequest;
+
+/// IOCTL command to set the current time
+///
+/// Matches C's RTC_SET_TIME
+const RTC_SET_TIME: IoctlRequest = crate::bindings::RTC_SET_TIME as IoctlRequest;
+
+/// IOCTL command to arm an alarm at an absolute time
+///
+/// Matches C's RTC_SET_ALARM
+#[kconfig(CONFIG_RTC_ALARM = "y")]
+const RTC_SET_ALARM: IoctlRequest = crate::bindings::RTC_SET_ALARM as IoctlRequest;
+
+/// IOCTL command to cancel an alarm
+///
+/// Matches C's RTC_CANCEL_ALARM
+#[kconfig(CONFIG_RTC_ALARM = "y")]
+const RTC_CANCEL_ALARM: IoctlRequest = crate::bindings::RTC_CANCEL_ALARM as IoctlRequest;
+
+/// Calendar time as exchanged with the RTC driver
+///
+/// Alias for C's `struct rtc_time`, whose fields follow `struct tm`: the year
+/// counts from 1900 and the month from 0. See [`DateTime`] for a plain
+/// representation.
+pub type RtcTime = rtc_time;
+
+/// Result type for RTC operations, errors hold the negative errno
+pub type RtcResult<T> = Result<T, i32>;
+
+/// Broken-down calendar time
+///
+/// Unlike [`RtcTime`], the year is the full year and months and days count
+/// from 1.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DateTime {
+    /// Full year, e.g. 2024
+    pub year: i32,
+    /// Month, from 1 to 12
+    pub month: u8,
+    /// Day of the month, from 1 to 31
+    pub day: u8,
+    /// Hour, from 0 to 23
+    pub hour: u8,
+    /// Minute, from 0 to 59
+    pub min: u8,
+    /// Second, from 0 to 60 to allow for leap seconds
+    pub sec: u8,
+}
+
+impl From<RtcTime> for DateTime {
+    fn from(time: RtcTime) -> Self {
+        DateTime {
+            year: time.tm_year + 1900,
+            month: (time.tm_mon + 1) as u8,
+            day: time.tm_mday as u8,
+            hour: time.tm_hour as u8,
+            min: time.tm_min as u8,
+            sec: time.tm_sec as u8,
+        }
+    }
+}
+
+impl From<DateTime> for RtcTime {
+    /// Converts a DateTime, leaving the day of the week and of the year zero
+    fn from(time: DateTime) -> Self {
+        // SAFETY: A zeroed rtc_time is valid
+        let mut rtc: RtcTime = unsafe { zeroed() };
+        rtc.tm_year = (time.year - 1900) as _;
+        rtc.tm_mon = (time.month as i32 - 1) as _;
+        rtc.tm_mday = time.day as _;
+        rtc.tm_hour = time.hour as _;
+        rtc.tm_min = time.min as _;
+        rtc.tm_sec = time.sec as _;
+        rtc
+    }
+}
+
+/// Conversion to and from Unix time
+///
+/// The conversions use the proleptic Gregorian calendar in UTC and need no
+/// libc time functions. Leap seconds are not counted, as in Unix time.
+///
+/// # Examples
+///
+/// ```
+/// use nuttx::timers::rtc::{DateTime, RtcTime};
+///
+/// let leap_day = RtcTime::from(DateTime { year: 2024, month: 2, day: 29, hour: 12, min: 0, sec: 0 });
+/// assert_eq!(leap_day.to_unix(), 1_709_208_000);
+///
+/// let time = RtcTime::from_unix(1_709_208_000);
+/// assert_eq!(DateTime::from(time), DateTime { year: 2024, month: 2, day: 29, hour: 12, min: 0, sec: 0 });
+/// assert_eq!((time.tm_wday, time.tm_yday), (4, 59));
+///
+/// assert_eq!(RtcTime::from_unix(0).to_unix(), 0);
+/// assert_eq!(DateTime::from(RtcTime::from_unix(-1)).year, 1969);
+/// ```
+impl RtcTime {
+    /// Converts the calendar time into seconds since 1970-01-01 00:00:00 UTC
+    ///
+    /// Like `mktime`, fields outside of their range carry over, e.g. a month
+    /// of 12 is January of the next year. The day of the week and of the year
+    /// and the nanoseconds are ignored.
+    pub fn to_unix(self) -> i64 {
+        let month = self.tm_mon as i64;
+        let year = self.tm_year as i64 + 1900 + month.div_euclid(12);
+        let days = days_from_civil(year, month.rem_euclid(12) + 1, 1) + self.tm_mday as i64 - 1;
+
+        days * SECS_PER_DAY
+            + self.tm_hour as i64 * 3600
+            + self.tm_min as i64 * 60
+            + self.tm_sec as i64
+    }
+
+    /// Converts seconds since 1970-01-01 00:00:00 UTC into a calendar time
+    ///
+    /// All fields are set, including the day of the week and of the year.
+    /// Years outside of the range of `tm_year` are truncated.
+    pub fn from_unix(ts: i64) -> RtcTime {
+        let days = ts.div_euclid(SECS_PER_DAY);
+        let secs = ts.rem_euclid(SECS_PER_DAY);
+        let (year, month, day) = civil_from_days(days);
+
+        // SAFETY: A zeroed rtc_time is valid
+        let mut rtc: RtcTime = unsafe { zeroed() };
+        rtc.tm_year = (year - 1900) as _;
+        rtc.tm_mon = (month - 1) as _;
+        rtc.tm_mday = day as _;
+        rtc.tm_hour = (secs / 3600) as _;
+        rtc.tm_min = (secs / 60 % 60) as _;
+        rtc.tm_sec = (secs % 60) as _;
+        // 1970-01-01 was a Thursday
+        rtc.tm_wday = (days + 4).rem_euclid(7) as _;
+        rtc.tm_yday = (days - days_from_civil(year, 1, 1)) as _;
+        rtc
+    }
+}
+
+/// Number of seconds in a day
+const SECS_PER_DAY: i64 = 86_400;
+
+/// Number of days from 1970-01-01 to the given date
+///
+/// Counts in eras of 400 years starting in March, so that the leap day ends
+/// the year, see Howard Hinnant's `days_from_civil` algorithm.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Date of the day `days` after 1970-01-01, as year, month and day
+///
+/// Inverse of [`days_from_civil`].
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+/// Represents an open RTC device
+///
+/// The underlying device is closed when the Rtc is dropped.
+#[derive(Debug)]
+pub struct Rtc {
+    fd: c_int,
+}
+
+impl Rtc {
+    /// Opens an RTC device at the specified path
+    ///
+    /// # Arguments
+    /// * `path` - Path to the RTC device as a C string (e.g. "/dev/rtc0")
+    ///
+    /// # Errors
+    /// Returns the negative errno if the device could not be opened
+    pub fn open(path: &CStr) -> RtcResult<Self> {
+        let fd = unsafe { open(path.as_ptr(), O_RDONLY) };
+        if fd < 0 {
+            return Err(errno());
+        }
+        Ok(Rtc { fd })
+    }
+
+    /// Reads the current time
+    ///
+    /// # Errors
+    /// Returns the negative errno if the ioctl fails
+    pub fn read_time(&self) -> RtcResult<RtcTime> {
+        // SAFETY: A zeroed rtc_time is valid
+        let mut time: RtcTime = unsafe { zeroed() };
+
+        // SAFETY: RTC_RD_TIME fills the rtc_time structure
+        let result = unsafe {
+            ioctl(
+                self.fd,
+                RTC_RD_TIME as _,
+                &mut time as *mut RtcTime as *mut c_void,
+            )
+        };
+
+        if result < 0 { Err(errno()) } else { Ok(time) }
+    }
+
+    /// Sets the current time
+    ///
+    /// # Arguments
+    /// * `time` - The new time
+    ///
+    /// # Errors
+    /// Returns the negative errno if the ioctl fails
+    pub fn set_time(&self, time: &RtcTime) -> RtcResult<()> {
+        // SAFETY: The driver only reads the rtc_time structure
+        let result = unsafe {
+            ioctl(
+                self.fd,
+                RTC_SET_TIME as _,
+                time as *const RtcTime as *mut c_void,
+            )
+        };
+
+        if result < 0 { Err(errno()) } else { Ok(()) }
+    }
+
+    /// Arms an alarm that signals the calling task at the given time
+    ///
+    /// # Arguments
+    /// * `id` - The alarm to arm, from 0 to `CONFIG_RTC_NALARMS - 1`
+    /// * `time` - The time at which the alarm expires
+    /// * `signo` - The signal to deliver, e.g. `SIGUSR1`
+    ///
+    /// # Errors
+    /// Returns the negative errno if the ioctl fails
+    #[kconfig(CONFIG_RTC_ALARM = "y")]
+    pub fn set_alarm(&self, id: u8, time: &RtcTime, signo: c_int) -> RtcResult<()> {
+        // SAFETY: A zeroed rtc_setalarm_s is valid, the driver copies it during
+        // the ioctl
+        let mut alarm: crate::bindings::rtc_setalarm_s = unsafe { zeroed() };
+        alarm.id = id;
+        alarm.pid = 0;
+        alarm.event.sigev_notify = crate::bindings::SIGEV_SIGNAL as _;
+        alarm.event.sigev_signo = signo as _;
+        alarm.time = *time;
+
+        let result = unsafe {
+            ioctl(
+                self.fd,
+                RTC_SET_ALARM as _,
+                &mut alarm as *mut crate::bindings::rtc_setalarm_s as *mut c_void,
+            )
+        };
+
+        if result < 0 { Err(errno()) } else { Ok(()) }
+    }
+
+    #[kconfig(CONFIG_RTC_ALARM = "n")]
+    pub fn set_alarm(&self, _id: u8, _time: &RtcTime, _signo: c_int) -> RtcResult<()> {
+        Err(-libc::ENOTSUP)
+    }
+
+    /// Cancels an armed alarm
+    ///
+    /// # Arguments
+    /// * `id` - The alarm to cancel
+    ///
+    /// # Errors
+    /// Returns the negative errno if the ioctl fails
+    #[kconfig(CONFIG_RTC_ALARM = "y")]
+    pub fn cancel_alarm(&self, id: u8) -> RtcResult<()> {
+        // SAFETY: RTC_CANCEL_ALARM takes the alarm ID itself rather than a pointer
+        let result = unsafe { ioctl(self.fd, RTC_CANCEL_ALARM as _, id as libc::c_ulong) };
+
+        if result < 0 { Err(errno()) } else { Ok(()) }
+    }
+
+    #[kconfig(CONFIG_RTC_ALARM = "n")]
+    pub fn cancel_alarm(&self, _id: u8) -> RtcResult<()> {
+        Err(-libc::ENOTSUP)
+    }
+}
+
+impl AsRawFd for Rtc {
+    /// Returns the file descriptor of the RTC device. The descriptor stays
+    /// owned by the Rtc.
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd
+    }
+}
+
+impl Drop for Rtc {
+    /// Closes the RTC device when the Rtc goes out of scope
+    fn drop(&mut self) {
+        unsafe { libc::close(self.fd) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u8, day: u8, hour: u8, min: u8, sec: u8) -> DateTime {
+        DateTime {
+            year,
+            month,
+            day,
+            hour,
+            min,
+            sec,
+        }
+    }
+
+    #[test]
+    fn unix_epoch() {
+        let time = RtcTime::from_unix(0);
+        assert_eq!(DateTime::from(time), date(1970, 1, 1, 0, 0, 0));
+        // 1970-01-01 was a Thursday
+        assert_eq!((time.tm_wday, time.tm_yday), (4, 0));
+        assert_eq!(time.to_unix(), 0);
+    }
+
+    #[test]
+    fn unix_leap_day() {
+        let leap_day = RtcTime::from(date(2024, 2, 29, 12, 0, 0));
+        assert_eq!(leap_day.to_unix(), 1_709_208_000);
+
+        let time = RtcTime::from_unix(1_709_208_000);
+        assert_eq!(DateTime::from(time), date(2024, 2, 29, 12, 0, 0));
+        assert_eq!((time.tm_wday, time.tm_yday), (4, 59));
+
+        // The day after is March 1st
+        let next = RtcTime::from_unix(1_709_208_000 + SECS_PER_DAY);
+        assert_eq!(DateTime::from(next), date(2024, 3, 1, 12, 0, 0));
+    }
+
+    #[test]
+    fn unix_year_rollover() {
+        let last = RtcTime::from(date(2023, 12, 31, 23, 59, 59)).to_unix();
+        assert_eq!(last, 1_704_067_199);
+
+        let time = RtcTime::from_unix(last);
+        assert_eq!(time.tm_yday, 364);
+        let time = RtcTime::from_unix(last + 1);
+        assert_eq!(DateTime::from(time), date(2024, 1, 1, 0, 0, 0));
+        assert_eq!((time.tm_wday, time.tm_yday), (1, 0));
+
+        // Months past December carry over into the next year
+        let mut carried = RtcTime::from(date(2023, 1, 1, 0, 0, 0));
+        carried.tm_mon = 12;
+        assert_eq!(carried.to_unix(), last + 1);
+    }
+
+    #[test]
+    fn unix_before_epoch() {
+        // Negative timestamps count back from the epoch
+        let time = RtcTime::from_unix(-1);
+        assert_eq!(DateTime::from(time), date(1969, 12, 31, 23, 59, 59));
+        assert_eq!((time.tm_wday, time.tm_yday), (3, 364));
+        assert_eq!(time.to_unix(), -1);
+
+        let time = RtcTime::from(date(1900, 3, 1, 0, 0, 0));
+        assert_eq!(time.to_unix(), -2_203_891_200);
+        assert_eq!(
+            DateTime::from(RtcTime::from_unix(-2_203_891_200)),
+            date(1900, 3, 1, 0, 0, 0)
+        );
+    }
+}
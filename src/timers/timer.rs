@@ -0,0 +1,272 @@
+//! Timer driver interface
+//!
+//! This module provides Rust bindings for the NuttX timer driver interface.
+//! It allows programming hardware timers exposed as `/dev/timerN` and being
+//! notified through a signal each time the timer expires.
+//!
+//! The implementation matches the NuttX timer interface defined in
+//! `nuttx/include/nuttx/timers/timer.h`.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use core::ffi::CStr;
+//! use nuttx::timers::timer::Timer;
+//!
+//! const SIGTIMER: i32 = 17;
+//!
+//! let timer = Timer::open(CStr::from_bytes_with_nul(b"/dev/timer0\0").unwrap()).unwrap();
+//! timer.set_timeout(10_000).unwrap();
+//! timer.notify(SIGTIMER).unwrap();
+//! timer.start().unwrap();
+//!
+//! loop {
+//!     timer.wait().unwrap();
+//!     // ... periodic work, every 10ms
+//! }
+//! ```
+
+use core::cell::Cell;
+use core::ffi::CStr;
+use core::mem::zeroed;
+use core::ptr;
+use libc::{EINVAL, O_RDONLY, c_int, c_ulong, c_void, ioctl, open};
+
+use crate::bindings::{
+    SIG_BLOCK, SIGEV_SIGNAL, TCFLAGS_ACTIVE, sigaddset, sigemptyset, sigprocmask, sigset_t,
+    sigwaitinfo, timer_notify_s, timer_status_s,
+};
+use crate::errno::errno;
+use crate::fd::{AsRawFd, RawFd};
+use crate::io::ioctl::IoctlRequest;
+
+/// IOCTL command to start the timer
+///
+/// Matches C's TCIOC_START
+const TCIOC_START: IoctlRequest = crate::bindings::TCIOC_START as IoctlRequest;
+
+/// IOCTL command to stop the timer
+///
+/// Matches C's TCIOC_STOP
+const TCIOC_STOP: IoctlRequest = crate::bindings::TCIOC_STOP as IoctlRequest;
+
+/// IOCTL command to get the status of the timer
+///
+/// Matches C's TCIOC_GETSTATUS
+const TCIOC_GETSTATUS: IoctlRequest = crate::bindings::TCIOC_GETSTATUS as IoctlRequest;
+
+/// IOCTL command to set the timeout
+///
+/// Matches C's TCIOC_SETTIMEOUT
+const TCIOC_SETTIMEOUT: IoctlRequest = crate::bindings::TCIOC_SETTIMEOUT as IoctlRequest;
+
+/// IOCTL command to register for a signal on expiry
+///
+/// Matches C's TCIOC_NOTIFICATION
+const TCIOC_NOTIFICATION: IoctlRequest = crate::bindings::TCIOC_NOTIFICATION as IoctlRequest;
+
+/// Status of the timer
+///
+/// Alias for C's `timer_status_s`. Times are in microseconds.
+pub type TimerStatus = timer_status_s;
+
+/// Result type for timer operations, errors hold the negative errno
+pub type TimerResult<T> = Result<T, i32>;
+
+impl TimerStatus {
+    /// Checks if the timer is running
+    pub fn is_active(&self) -> bool {
+        self.flags & TCFLAGS_ACTIVE != 0
+    }
+}
+
+/// Represents an open timer device
+#[derive(Debug)]
+pub struct Timer {
+    fd: c_int,
+    signo: Cell<Option<c_int>>,
+}
+
+impl Timer {
+    /// Opens a timer device at the specified path
+    ///
+    /// # Arguments
+    /// * `path` - Path to the timer device as a C string (e.g. "/dev/timer0")
+    ///
+    /// # Errors
+    /// Returns the negative errno if the device could not be opened
+    pub fn open(path: &CStr) -> TimerResult<Self> {
+        let fd = unsafe { open(path.as_ptr(), O_RDONLY) };
+        if fd < 0 {
+            return Err(errno());
+        }
+        Ok(Timer {
+            fd,
+            signo: Cell::new(None),
+        })
+    }
+
+    /// Sets the interval after which the timer expires
+    ///
+    /// # Arguments
+    /// * `us` - The timeout in microseconds
+    ///
+    /// # Errors
+    /// Returns the negative errno if the ioctl fails, e.g. `-EINVAL` if the
+    /// timeout exceeds the maximum supported by the hardware
+    pub fn set_timeout(&self, us: u32) -> TimerResult<()> {
+        self.command(TCIOC_SETTIMEOUT, us as c_ulong)
+    }
+
+    /// Starts the timer
+    ///
+    /// # Errors
+    /// Returns the negative errno if the ioctl fails
+    pub fn start(&self) -> TimerResult<()> {
+        self.command(TCIOC_START, 0)
+    }
+
+    /// Stops the timer
+    ///
+    /// # Errors
+    /// Returns the negative errno if the ioctl fails
+    pub fn stop(&self) -> TimerResult<()> {
+        self.command(TCIOC_STOP, 0)
+    }
+
+    /// Gets the status of the timer
+    ///
+    /// # Errors
+    /// Returns the negative errno if the ioctl fails
+    pub fn get_status(&self) -> TimerResult<TimerStatus> {
+        let mut status = TimerStatus {
+            flags: 0,
+            timeout: 0,
+            timeleft: 0,
+        };
+
+        // SAFETY: TCIOC_GETSTATUS fills the timer_status_s structure
+        let result = unsafe {
+            ioctl(
+                self.fd,
+                TCIOC_GETSTATUS as _,
+                &mut status as *mut TimerStatus as *mut c_void,
+            )
+        };
+
+        if result < 0 { Err(errno()) } else { Ok(status) }
+    }
+
+    /// Registers the calling task to receive a signal each time the timer expires
+    ///
+    /// The timer keeps running after each expiry. The signal is blocked for the
+    /// calling thread, so that expiries are queued for [`Timer::wait`] instead of
+    /// invoking the default signal action.
+    ///
+    /// # Arguments
+    /// * `signo` - The signal to deliver, e.g. `SIGUSR1`
+    ///
+    /// # Errors
+    /// Returns the negative errno if blocking the signal or the ioctl fails
+    pub fn notify(&self, signo: c_int) -> TimerResult<()> {
+        self.register(signo, true)
+    }
+
+    /// Registers the calling task to receive a signal when the timer next expires
+    ///
+    /// Same as [`Timer::notify`], except that the timer stops after the first
+    /// expiry, making it a oneshot timer.
+    ///
+    /// # Arguments
+    /// * `signo` - The signal to deliver, e.g. `SIGUSR1`
+    ///
+    /// # Errors
+    /// Returns the negative errno if blocking the signal or the ioctl fails
+    pub fn notify_oneshot(&self, signo: c_int) -> TimerResult<()> {
+        self.register(signo, false)
+    }
+
+    /// Blocks until the timer next expires
+    ///
+    /// The timer must have been registered with [`Timer::notify`] or
+    /// [`Timer::notify_oneshot`] from the calling thread. Expiries that occurred
+    /// since the last call are not lost, the call then returns immediately.
+    ///
+    /// # Errors
+    /// - `-EINVAL` if no signal is registered
+    /// - The negative errno if waiting fails, e.g. `-EINTR` when interrupted by
+    ///   another signal
+    pub fn wait(&self) -> TimerResult<()> {
+        let Some(signo) = self.signo.get() else {
+            return Err(-EINVAL);
+        };
+
+        // SAFETY: The signal set is initialized by sigemptyset before use, and
+        // no signal information is requested
+        let result = unsafe {
+            let mut set: sigset_t = zeroed();
+            sigemptyset(&mut set);
+            sigaddset(&mut set, signo);
+            sigwaitinfo(&set, ptr::null_mut())
+        };
+
+        if result < 0 { Err(errno()) } else { Ok(()) }
+    }
+
+    /// Blocks the signal and registers it for expiries of the timer
+    fn register(&self, signo: c_int, periodic: bool) -> TimerResult<()> {
+        // SAFETY: The signal set is initialized by sigemptyset before use
+        unsafe {
+            let mut set: sigset_t = zeroed();
+            sigemptyset(&mut set);
+            sigaddset(&mut set, signo);
+            if sigprocmask(SIG_BLOCK as c_int, &set, ptr::null_mut()) < 0 {
+                return Err(errno());
+            }
+        }
+
+        // SAFETY: A zeroed timer_notify_s is valid, a pid of 0 selects the
+        // calling task and the driver copies the structure during the ioctl
+        let mut notify: timer_notify_s = unsafe { zeroed() };
+        notify.pid = 0;
+        notify.periodic = periodic;
+        notify.event.sigev_notify = SIGEV_SIGNAL as _;
+        notify.event.sigev_signo = signo as _;
+
+        let result = unsafe {
+            ioctl(
+                self.fd,
+                TCIOC_NOTIFICATION as _,
+                &mut notify as *mut timer_notify_s as *mut c_void,
+            )
+        };
+
+        if result < 0 {
+            return Err(errno());
+        }
+        self.signo.set(Some(signo));
+        Ok(())
+    }
+
+    /// Issues an ioctl that takes its argument by value
+    fn command(&self, cmd: IoctlRequest, arg: c_ulong) -> TimerResult<()> {
+        let result = unsafe { ioctl(self.fd, cmd as _, arg) };
+
+        if result < 0 { Err(errno()) } else { Ok(()) }
+    }
+}
+
+impl AsRawFd for Timer {
+    /// Returns the file descriptor of the timer device. The descriptor stays
+    /// owned by the Timer.
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd
+    }
+}
+
+impl Drop for Timer {
+    /// Closes the timer device when the Timer goes out of scope
+    fn drop(&mut self) {
+        unsafe { libc::close(self.fd) };
+    }
+}
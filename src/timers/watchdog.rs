@@ -0,0 +1,191 @@
+//! Watchdog timer interface
+//!
+//! This module provides Rust bindings for the NuttX watchdog driver interface.
+//! It allows starting and stopping a watchdog such as `/dev/watchdog0`,
+//! configuring its timeout and keeping it alive.
+//!
+//! The implementation matches the NuttX watchdog interface defined in
+//! `nuttx/include/nuttx/timers/watchdog.h`.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use core::ffi::CStr;
+//! use nuttx::timers::watchdog::Watchdog;
+//!
+//! let wdog = Watchdog::open(CStr::from_bytes_with_nul(b"/dev/watchdog0\0").unwrap()).unwrap();
+//! wdog.set_timeout(2000).unwrap();
+//! wdog.start().unwrap();
+//!
+//! loop {
+//!     let remaining = wdog.keepalive().unwrap();
+//!     // ... do work, well within `remaining` milliseconds
+//! }
+//! ```
+
+use core::ffi::CStr;
+use libc::{O_RDONLY, c_int, c_ulong, c_void, ioctl, open};
+
+use crate::bindings::{WDFLAGS_ACTIVE, WDFLAGS_RESET, watchdog_status_s};
+use crate::errno::errno;
+use crate::fd::{AsRawFd, RawFd};
+use crate::io::ioctl::IoctlRequest;
+
+/// IOCTL command to start the watchdog
+///
+/// Matches C's WDIOC_START
+const WDIOC_START: IoctlRequest = crate::bindings::WDIOC_START as IoctlRequest;
+
+/// IOCTL command to stop the watchdog
+///
+/// Matches C's WDIOC_STOP
+const WDIOC_STOP: IoctlRequest = crate::bindings::WDIOC_STOP as IoctlRequest;
+
+/// IOCTL command to get the status of the watchdog
+///
+/// Matches C's WDIOC_GETSTATUS
+const WDIOC_GETSTATUS: IoctlRequest = crate::bindings::WDIOC_GETSTATUS as IoctlRequest;
+
+/// IOCTL command to set the timeout
+///
+/// Matches C's WDIOC_SETTIMEOUT
+const WDIOC_SETTIMEOUT: IoctlRequest = crate::bindings::WDIOC_SETTIMEOUT as IoctlRequest;
+
+/// IOCTL command to reset the watchdog timer
+///
+/// Matches C's WDIOC_KEEPALIVE
+const WDIOC_KEEPALIVE: IoctlRequest = crate::bindings::WDIOC_KEEPALIVE as IoctlRequest;
+
+/// Status of the watchdog
+///
+/// Alias for C's `watchdog_status_s`. Times are in milliseconds.
+pub type WatchdogStatus = watchdog_status_s;
+
+/// Result type for watchdog operations, errors hold the negative errno
+pub type WatchdogResult<T> = Result<T, i32>;
+
+impl WatchdogStatus {
+    /// Checks if the watchdog is running
+    pub fn is_active(&self) -> bool {
+        self.flags & WDFLAGS_ACTIVE != 0
+    }
+
+    /// Checks if the watchdog resets the system on expiry
+    pub fn is_reset(&self) -> bool {
+        self.flags & WDFLAGS_RESET != 0
+    }
+}
+
+/// Represents an open watchdog device
+///
+/// Dropping the Watchdog closes the device but does not stop the watchdog, so
+/// a task that exits or panics without stopping it still triggers a reset.
+/// Call [`Watchdog::stop`] explicitly to disarm it.
+#[derive(Debug)]
+pub struct Watchdog {
+    fd: c_int,
+}
+
+impl Watchdog {
+    /// Opens a watchdog device at the specified path
+    ///
+    /// # Arguments
+    /// * `path` - Path to the watchdog device as a C string (e.g. "/dev/watchdog0")
+    ///
+    /// # Errors
+    /// Returns the negative errno if the device could not be opened
+    pub fn open(path: &CStr) -> WatchdogResult<Self> {
+        let fd = unsafe { open(path.as_ptr(), O_RDONLY) };
+        if fd < 0 {
+            return Err(errno());
+        }
+        Ok(Watchdog { fd })
+    }
+
+    /// Starts the watchdog
+    ///
+    /// # Errors
+    /// Returns the negative errno if the ioctl fails
+    pub fn start(&self) -> WatchdogResult<()> {
+        self.command(WDIOC_START, 0)
+    }
+
+    /// Stops the watchdog
+    ///
+    /// # Errors
+    /// Returns the negative errno if the ioctl fails
+    pub fn stop(&self) -> WatchdogResult<()> {
+        self.command(WDIOC_STOP, 0)
+    }
+
+    /// Sets the time after which the watchdog expires unless kept alive
+    ///
+    /// # Arguments
+    /// * `ms` - The timeout in milliseconds
+    ///
+    /// # Errors
+    /// Returns the negative errno if the ioctl fails, e.g. `-EINVAL` if the
+    /// hardware cannot represent the timeout
+    pub fn set_timeout(&self, ms: u32) -> WatchdogResult<()> {
+        self.command(WDIOC_SETTIMEOUT, ms as c_ulong)
+    }
+
+    /// Resets the watchdog timer to prevent it from expiring
+    ///
+    /// # Returns
+    /// The time left until the watchdog expires, in milliseconds
+    ///
+    /// # Errors
+    /// Returns the negative errno if an ioctl fails
+    pub fn keepalive(&self) -> WatchdogResult<u32> {
+        self.command(WDIOC_KEEPALIVE, 0)?;
+        Ok(self.status()?.timeleft)
+    }
+
+    /// Gets the status of the watchdog
+    ///
+    /// # Errors
+    /// Returns the negative errno if the ioctl fails
+    pub fn status(&self) -> WatchdogResult<WatchdogStatus> {
+        let mut status = WatchdogStatus {
+            flags: 0,
+            timeout: 0,
+            timeleft: 0,
+        };
+
+        // SAFETY: WDIOC_GETSTATUS fills the watchdog_status_s structure
+        let result = unsafe {
+            ioctl(
+                self.fd,
+                WDIOC_GETSTATUS as _,
+                &mut status as *mut WatchdogStatus as *mut c_void,
+            )
+        };
+
+        if result < 0 { Err(errno()) } else { Ok(status) }
+    }
+
+    /// Issues an ioctl that takes its argument by value
+    fn command(&self, cmd: IoctlRequest, arg: c_ulong) -> WatchdogResult<()> {
+        let result = unsafe { ioctl(self.fd, cmd as _, arg) };
+
+        if result < 0 { Err(errno()) } else { Ok(()) }
+    }
+}
+
+impl AsRawFd for Watchdog {
+    /// Returns the file descriptor of the watchdog device. The descriptor stays
+    /// owned by the Watchdog.
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd
+    }
+}
+
+impl Drop for Watchdog {
+    /// Closes the watchdog device when the Watchdog goes out of scope
+    ///
+    /// The watchdog keeps running, see [`Watchdog`].
+    fn drop(&mut self) {
+        unsafe { libc::close(self.fd) };
+    }
+}
@@ -17,17 +17,46 @@
 //! let info = fb.get_video_info().unwrap();
 //! assert_eq!(info.fmt, FB_FMT_RGB16_565);
 //! ```
+//!
+//! # Pixel formats
+//!
+//! The `FB_FMT_*` constants are re-exported for every format known to fb.h.
+//! They are not tied to the configuration: NuttX has no per-format Kconfig
+//! options, and fb.h defines all format codes whenever the framebuffer
+//! interface is included. Which formats are actually available depends on the
+//! display driver, and is reported at runtime through the `fmt` field of
+//! [`VideoInfo`]. Bindings restricted with `NUTTX_BINDGEN_ALLOWLIST` must keep
+//! the `FB_.*` constants for this module to build.
 
 use crate::bindings;
+use crate::errno::{errno, errno_result, errno_result_ptr};
+use crate::fd::{self, AsRawFd, DeviceFd, RawFd};
+use crate::io::ioctl::{self, IoctlRequest};
+use crate::video::text;
 use core::ffi::{CStr, c_void};
-use kconfig::kconfig;
+use core::fmt;
+use core::ptr;
+use core::sync::atomic::AtomicI32;
+use kconfig::{kconfig, kconfig_enabled};
 
-// Re-export only RGB family of framebuffer format constants
+// Re-export RGB family of framebuffer format constants
 pub use bindings::{
     FB_FMT_RGB4, FB_FMT_RGB8, FB_FMT_RGB8_222, FB_FMT_RGB8_332, FB_FMT_RGB12_444, FB_FMT_RGB16_555,
     FB_FMT_RGB16_565, FB_FMT_RGB24, FB_FMT_RGB32, FB_FMT_RGBA16, FB_FMT_RGBA32,
 };
 
+// Re-export grayscale framebuffer format constants
+pub use bindings::{
+    FB_FMT_GREY, FB_FMT_Y1, FB_FMT_Y2, FB_FMT_Y4, FB_FMT_Y8, FB_FMT_Y16, FB_FMT_Y800,
+};
+
+// Re-export packed and planar YUV framebuffer format constants
+pub use bindings::{
+    FB_FMT_AYUV, FB_FMT_I420, FB_FMT_IYUV, FB_FMT_NV12, FB_FMT_NV21, FB_FMT_UYNV, FB_FMT_UYVY,
+    FB_FMT_Y41P, FB_FMT_Y411, FB_FMT_Y422, FB_FMT_YUNV, FB_FMT_YUV9, FB_FMT_YUY2, FB_FMT_YUYV,
+    FB_FMT_YV12, FB_FMT_YV16, FB_FMT_YVU9, FB_FMT_YVYU,
+};
+
 /// Coordinate type used in framebuffer structures
 ///
 /// Matches C's `fb_coord_t` which is a uint16_t
@@ -48,128 +77,3146 @@ pub type PlaneInfo = bindings::fb_planeinfo_s;
 /// Alias for C's `fb_area_s`
 pub type Area = bindings::fb_area_s;
 
+// The ioctl commands are taken from the bindings rather than hardcoded, so they
+// follow the numbering of the NuttX build, and a missing command fails to
+// compile instead of issuing the wrong request. Commands that fb.h only defines
+// for some configurations are gated like the methods using them.
+
 /// IOCTL command to get video information
 ///
 /// Matches C's FBIOGET_VIDEOINFO
-const FBIOGET_VIDEOINFO: i32 = 0x2801;
+const FBIOGET_VIDEOINFO: IoctlRequest = bindings::FBIOGET_VIDEOINFO as IoctlRequest;
 
 /// IOCTL command to get plane information
 ///
 /// Matches C's FBIOGET_PLANEINFO
-const FBIOGET_PLANEINFO: i32 = 0x2802;
+const FBIOGET_PLANEINFO: IoctlRequest = bindings::FBIOGET_PLANEINFO as IoctlRequest;
+
+/// IOCTL command to get the color lookup table
+///
+/// Matches C's FBIOGET_CMAP
+#[kconfig(CONFIG_FB_CMAP = "y")]
+const FBIOGET_CMAP: IoctlRequest = bindings::FBIOGET_CMAP as IoctlRequest;
+
+/// IOCTL command to set the color lookup table
+///
+/// Matches C's FBIOPUT_CMAP
+#[kconfig(CONFIG_FB_CMAP = "y")]
+const FBIOPUT_CMAP: IoctlRequest = bindings::FBIOPUT_CMAP as IoctlRequest;
+
+/// IOCTL command to get hardware cursor attributes
+///
+/// Matches C's FBIOGET_CURSOR
+#[kconfig(CONFIG_FB_HWCURSOR = "y")]
+const FBIOGET_CURSOR: IoctlRequest = bindings::FBIOGET_CURSOR as IoctlRequest;
+
+/// IOCTL command to set hardware cursor attributes
+///
+/// Matches C's FBIOPUT_CURSOR
+#[kconfig(CONFIG_FB_HWCURSOR = "y")]
+const FBIOPUT_CURSOR: IoctlRequest = bindings::FBIOPUT_CURSOR as IoctlRequest;
 
 /// IOCTL command to update a rectangular region in the framebuffer
 ///
 /// Matches C's FBIO_UPDATE
-#[allow(dead_code)]
-const FBIO_UPDATE: i32 = 0x2807;
+#[kconfig(CONFIG_FB_UPDATE = "y")]
+const FBIO_UPDATE: IoctlRequest = bindings::FBIO_UPDATE as IoctlRequest;
+
+/// IOCTL command to wait for the next vertical sync
+///
+/// Matches C's FBIO_WAITFORVSYNC
+#[kconfig(CONFIG_FB_SYNC = "y")]
+const FBIO_WAITFORVSYNC: IoctlRequest = bindings::FBIO_WAITFORVSYNC as IoctlRequest;
+
+/// IOCTL command to get overlay information
+///
+/// Matches C's FBIOGET_OVERLAYINFO
+#[kconfig(CONFIG_FB_OVERLAY = "y")]
+const FBIOGET_OVERLAYINFO: IoctlRequest = bindings::FBIOGET_OVERLAYINFO as IoctlRequest;
+
+/// IOCTL command to select the overlay used by subsequent mmap calls
+///
+/// Matches C's FBIO_SELECT_OVERLAY
+#[kconfig(CONFIG_FB_OVERLAY = "y")]
+const FBIO_SELECT_OVERLAY: IoctlRequest = bindings::FBIO_SELECT_OVERLAY as IoctlRequest;
+
+/// IOCTL command to set the transparency of an overlay
+///
+/// Matches C's FBIOSET_TRANSP
+#[kconfig(CONFIG_FB_OVERLAY = "y")]
+const FBIOSET_TRANSP: IoctlRequest = bindings::FBIOSET_TRANSP as IoctlRequest;
+
+/// IOCTL command to set the chroma key of an overlay
+///
+/// Matches C's FBIOSET_CHROMAKEY
+#[kconfig(CONFIG_FB_OVERLAY = "y")]
+const FBIOSET_CHROMAKEY: IoctlRequest = bindings::FBIOSET_CHROMAKEY as IoctlRequest;
+
+/// IOCTL command to set the visible area of an overlay
+///
+/// Matches C's FBIOSET_AREA
+#[kconfig(CONFIG_FB_OVERLAY = "y")]
+const FBIOSET_AREA: IoctlRequest = bindings::FBIOSET_AREA as IoctlRequest;
+
+/// IOCTL command to set the panel power level
+///
+/// Matches C's FBIOSET_POWER
+const FBIOSET_POWER: IoctlRequest = bindings::FBIOSET_POWER as IoctlRequest;
+
+/// IOCTL command to get the panel power level
+///
+/// Matches C's FBIOGET_POWER
+const FBIOGET_POWER: IoctlRequest = bindings::FBIOGET_POWER as IoctlRequest;
+
+/// IOCTL command to pan the visible region within the virtual resolution
+///
+/// Matches C's FBIOPAN_DISPLAY
+const FBIOPAN_DISPLAY: IoctlRequest = bindings::FBIOPAN_DISPLAY as IoctlRequest;
+
+/// IOCTL command to change the video mode
+///
+/// Matches C's FBIOPUT_VSCREENINFO
+#[kconfig(CONFIG_VIDEO_FB = "y")]
+const FBIOPUT_VSCREENINFO: IoctlRequest = bindings::FBIOPUT_VSCREENINFO as IoctlRequest;
 
-/// Result type for framebuffer operations
+/// Result type for framebuffer operations, errors hold the negative errno
 pub type FrameBufferResult<T> = Result<T, i32>;
 
-/// FrameBuffer structure wrapping the framebuffer functionality
+/// Overlay information structure
+///
+/// Alias for C's `fb_overlayinfo_s`
+#[kconfig(CONFIG_FB_OVERLAY = "y")]
+pub type OverlayInfo = bindings::fb_overlayinfo_s;
+
+/// Hardware overlay (layer) composited on top of the framebuffer planes
+///
+/// Obtained from [`FrameBuffer::overlay`]. Setters update the cached
+/// [`OverlayInfo`] and apply it to the hardware.
+#[kconfig(CONFIG_FB_OVERLAY = "y")]
 #[derive(Debug)]
-pub struct FrameBuffer {
-    fd: i32,
+pub struct Overlay<'a> {
+    fb: &'a FrameBuffer,
+    info: OverlayInfo,
 }
 
-impl FrameBuffer {
-    /// Open the framebuffer device
-    pub fn new(path: &CStr) -> FrameBufferResult<Self> {
-        let fd = unsafe { libc::open(path.as_ptr(), libc::O_RDWR) };
-        if fd < 0 {
-            return Err(fd);
-        }
-        Ok(Self { fd })
+#[kconfig(CONFIG_FB_OVERLAY = "y")]
+impl Overlay<'_> {
+    /// Overlay information as last read from or applied to the hardware
+    pub fn info(&self) -> &OverlayInfo {
+        &self.info
     }
 
-    /// Get video information from the framebuffer device
+    /// Plane information describing the memory of the overlay
+    pub fn plane_info(&self) -> PlaneInfo {
+        let mut plane = unsafe { core::mem::zeroed::<PlaneInfo>() };
+        plane.fbmem = self.info.fbmem;
+        plane.fblen = self.info.fblen;
+        plane.stride = self.info.stride;
+        plane.bpp = self.info.bpp;
+        plane
+    }
+
+    /// Set the transparency of the overlay
     ///
-    /// # Returns
-    /// `VideoInfo` structure containing the framebuffer's video information
+    /// # Arguments
+    /// * `transp` - Transparency level, 0 is fully transparent
+    /// * `mode` - Transparency mode, as defined by the display driver
     ///
     /// # Errors
-    /// Returns `FrameBufferError::PlaneInfoFailed` if the ioctl fails
-    pub fn get_video_info(&self) -> FrameBufferResult<VideoInfo> {
-        let mut info = unsafe { core::mem::zeroed::<VideoInfo>() };
-
-        // SAFETY: We're passing valid pointers to the ioctl
-        let result = unsafe {
-            libc::ioctl(
-                self.fd,
-                FBIOGET_VIDEOINFO.try_into().unwrap(),
-                &mut info as *mut VideoInfo as *mut c_void,
-            )
-        };
+    /// Returns the negative errno if the ioctl fails
+    pub fn set_transparency(&mut self, transp: u8, mode: u8) -> FrameBufferResult<()> {
+        self.info.transp.transp = transp;
+        self.info.transp.transp_mode = mode;
+        self.apply(FBIOSET_TRANSP)
+    }
 
-        if result < 0 { Err(result) } else { Ok(info) }
+    /// Set the chroma key of the overlay
+    ///
+    /// Pixels matching the chroma key are treated as transparent.
+    ///
+    /// # Errors
+    /// Returns the negative errno if the ioctl fails
+    pub fn set_chroma_key(&mut self, key: u32) -> FrameBufferResult<()> {
+        self.info.chromakey = key;
+        self.apply(FBIOSET_CHROMAKEY)
     }
 
-    /// Get plane information from the framebuffer device
+    /// Set the area of the overlay that is shown
     ///
-    /// # Returns
-    /// `PlaneInfo` structure containing the framebuffer's plane information
+    /// # Errors
+    /// Returns the negative errno if the ioctl fails
+    pub fn set_area(&mut self, area: &Area) -> FrameBufferResult<()> {
+        self.info.sarea = *area;
+        self.apply(FBIOSET_AREA)
+    }
+
+    /// Map the overlay memory for direct pixel access
     ///
     /// # Errors
-    /// Returns a libc error code if the ioctl fails
-    pub fn get_plane_info(&self) -> FrameBufferResult<PlaneInfo> {
-        let mut info = unsafe { core::mem::zeroed::<PlaneInfo>() };
-
-        // SAFETY: We're passing valid pointers to the ioctl
-        let result = unsafe {
-            libc::ioctl(
-                self.fd,
-                FBIOGET_PLANEINFO.try_into().unwrap(),
-                &mut info as *mut PlaneInfo as *mut c_void,
-            )
+    /// Returns the negative errno if the overlay cannot be selected, or the
+    /// negative errno if the mapping fails
+    pub fn map(&self) -> FrameBufferResult<FrameBufferMmap> {
+        self.fb.select_overlay(self.info.overlay)?;
+        self.fb.map_plane(self.plane_info())
+    }
+
+    fn apply(&self, cmd: IoctlRequest) -> FrameBufferResult<()> {
+        // SAFETY: The overlay ioctls read a fb_overlayinfo_s
+        unsafe { ioctl::ioctl_write(self.fb.as_raw_fd(), cmd, &self.info) }?;
+        Ok(())
+    }
+}
+
+/// Range of color lookup table entries for palette-indexed formats
+///
+/// Entry `first + i` of the table consists of `red[i]`, `green[i]`, `blue[i]`
+/// and, with CONFIG_FB_TRANSPARENCY, `transp[i]`. All channel slices must have
+/// the same length. The channels are filled in by [`FrameBuffer::get_cmap`],
+/// use [`ColorMapRef`] to write entries.
+#[derive(Debug)]
+pub struct ColorMap<'a> {
+    /// Index of the first table entry
+    pub first: u16,
+    /// Red channel of the entries
+    pub red: &'a mut [u8],
+    /// Green channel of the entries
+    pub green: &'a mut [u8],
+    /// Blue channel of the entries
+    pub blue: &'a mut [u8],
+    /// Optional transparency of the entries
+    pub transp: Option<&'a mut [u8]>,
+}
+
+impl ColorMap<'_> {
+    /// Number of table entries described by this map
+    pub fn len(&self) -> usize {
+        self.red.len()
+    }
+
+    /// Whether this map describes no table entries
+    pub fn is_empty(&self) -> bool {
+        self.red.is_empty()
+    }
+
+    /// Build the C structure, with channel pointers the driver may write through
+    #[kconfig(CONFIG_FB_CMAP = "y")]
+    fn as_raw_mut(&mut self) -> FrameBufferResult<bindings::fb_cmap_s> {
+        let transp = self
+            .transp
+            .as_mut()
+            .map(|transp| (transp.as_mut_ptr(), transp.len()));
+        raw_cmap(
+            self.first,
+            [
+                (self.red.as_mut_ptr(), self.red.len()),
+                (self.green.as_mut_ptr(), self.green.len()),
+                (self.blue.as_mut_ptr(), self.blue.len()),
+            ],
+            transp,
+        )
+    }
+}
+
+/// Read-only range of color lookup table entries, written by
+/// [`FrameBuffer::put_cmap`]
+///
+/// Laid out like [`ColorMap`], with all channel slices of the same length.
+#[derive(Debug, Clone, Copy)]
+pub struct ColorMapRef<'a> {
+    /// Index of the first table entry
+    pub first: u16,
+    /// Red channel of the entries
+    pub red: &'a [u8],
+    /// Green channel of the entries
+    pub green: &'a [u8],
+    /// Blue channel of the entries
+    pub blue: &'a [u8],
+    /// Optional transparency of the entries
+    pub transp: Option<&'a [u8]>,
+}
+
+impl ColorMapRef<'_> {
+    /// Number of table entries described by this map
+    pub fn len(&self) -> usize {
+        self.red.len()
+    }
+
+    /// Whether this map describes no table entries
+    pub fn is_empty(&self) -> bool {
+        self.red.is_empty()
+    }
+
+    /// Build the C structure, whose channels the driver only reads
+    #[kconfig(CONFIG_FB_CMAP = "y")]
+    fn as_raw(&self) -> FrameBufferResult<bindings::fb_cmap_s> {
+        let channel = |bytes: &[u8]| (bytes.as_ptr() as *mut u8, bytes.len());
+        raw_cmap(
+            self.first,
+            [channel(self.red), channel(self.green), channel(self.blue)],
+            self.transp.map(channel),
+        )
+    }
+}
+
+impl<'a> From<&'a ColorMap<'_>> for ColorMapRef<'a> {
+    fn from(cmap: &'a ColorMap<'_>) -> Self {
+        ColorMapRef {
+            first: cmap.first,
+            red: cmap.red,
+            green: cmap.green,
+            blue: cmap.blue,
+            transp: cmap.transp.as_deref(),
+        }
+    }
+}
+
+/// Build the C structure of a color map from its channel pointers and lengths,
+/// checking that all channels have the same length
+#[kconfig(CONFIG_FB_CMAP = "y")]
+fn raw_cmap(
+    first: u16,
+    rgb: [(*mut u8, usize); 3],
+    transp: Option<(*mut u8, usize)>,
+) -> FrameBufferResult<bindings::fb_cmap_s> {
+    let [(red, len), (green, green_len), (blue, blue_len)] = rgb;
+    let transp_len = transp.map_or(len, |(_, transp_len)| transp_len);
+    if green_len != len || blue_len != len || transp_len != len {
+        return Err(-libc::EINVAL);
+    }
+
+    let mut cmap = unsafe { core::mem::zeroed::<bindings::fb_cmap_s>() };
+    cmap.first = first;
+    cmap.len = len.try_into().map_err(|_| -libc::EINVAL)?;
+    cmap.red = red;
+    cmap.green = green;
+    cmap.blue = blue;
+    set_raw_transp(&mut cmap, transp.map(|(transp, _)| transp))?;
+    Ok(cmap)
+}
+
+#[kconfig(CONFIG_FB_CMAP = "y", CONFIG_FB_TRANSPARENCY = "y")]
+fn set_raw_transp(
+    cmap: &mut bindings::fb_cmap_s,
+    transp: Option<*mut u8>,
+) -> FrameBufferResult<()> {
+    if let Some(transp) = transp {
+        cmap.transp = transp;
+    }
+    Ok(())
+}
+
+#[kconfig(CONFIG_FB_CMAP = "y", CONFIG_FB_TRANSPARENCY = "n")]
+fn set_raw_transp(
+    _cmap: &mut bindings::fb_cmap_s,
+    transp: Option<*mut u8>,
+) -> FrameBufferResult<()> {
+    match transp {
+        Some(_) => Err(-libc::ENOTSUP),
+        None => Ok(()),
+    }
+}
+
+/// Hardware cursor attributes
+///
+/// Returned by [`FrameBuffer::get_cursor`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CursorInfo {
+    /// Column of the cursor hotspot
+    pub x: Coord,
+    /// Row of the cursor hotspot
+    pub y: Coord,
+}
+
+/// Number of bits used by a pixel of the given format
+///
+/// # Arguments
+/// * `fmt` - One of the `FB_FMT_*` format constants
+///
+/// # Returns
+/// The bits per pixel, or None if the format is unknown. For subsampled YUV
+/// formats this is the average over a block of pixels.
+pub fn bits_per_pixel(fmt: u8) -> Option<usize> {
+    match fmt as u32 {
+        FB_FMT_Y1 => Some(1),
+        FB_FMT_Y2 => Some(2),
+        FB_FMT_Y4 | FB_FMT_RGB4 => Some(4),
+        FB_FMT_Y8 | FB_FMT_RGB8 | FB_FMT_RGB8_222 | FB_FMT_RGB8_332 => Some(8),
+        FB_FMT_YVU9 | FB_FMT_YUV9 => Some(9),
+        FB_FMT_RGB12_444 | FB_FMT_Y41P | FB_FMT_Y411 | FB_FMT_YV12 | FB_FMT_I420 | FB_FMT_NV12
+        | FB_FMT_NV21 => Some(12),
+        FB_FMT_Y16 | FB_FMT_RGB16_555 | FB_FMT_RGB16_565 | FB_FMT_RGBA16 | FB_FMT_UYVY
+        | FB_FMT_YUYV | FB_FMT_YVYU | FB_FMT_YV16 => Some(16),
+        FB_FMT_RGB24 => Some(24),
+        FB_FMT_RGB32 | FB_FMT_RGBA32 | FB_FMT_AYUV => Some(32),
+        _ => None,
+    }
+}
+
+/// Whether the format is a grayscale format
+pub fn is_grayscale(fmt: u8) -> bool {
+    matches!(
+        fmt as u32,
+        FB_FMT_Y1 | FB_FMT_Y2 | FB_FMT_Y4 | FB_FMT_Y8 | FB_FMT_Y16
+    )
+}
+
+/// Whether the format is a packed or planar YUV format
+pub fn is_yuv(fmt: u8) -> bool {
+    matches!(
+        fmt as u32,
+        FB_FMT_AYUV | FB_FMT_UYVY | FB_FMT_YUYV | FB_FMT_YVYU | FB_FMT_Y41P | FB_FMT_Y411
+    ) || is_yuv_planar(fmt)
+}
+
+/// Whether the format stores luma and chroma in separate planes
+fn is_yuv_planar(fmt: u8) -> bool {
+    matches!(
+        fmt as u32,
+        FB_FMT_YVU9
+            | FB_FMT_YUV9
+            | FB_FMT_YV16
+            | FB_FMT_YV12
+            | FB_FMT_I420
+            | FB_FMT_NV12
+            | FB_FMT_NV21
+    )
+}
+
+/// Number of bytes used by a pixel of the given format
+///
+/// # Arguments
+/// * `fmt` - One of the `FB_FMT_*` format constants
+///
+/// # Returns
+/// The bytes per pixel, or None if the format is unknown, packs pixels in
+/// units smaller than a byte (e.g. `FB_FMT_Y4` and `FB_FMT_RGB12_444`), or is
+/// a planar YUV format. Packed YUV 4:2:2 formats report the average of 2 bytes
+/// per pixel.
+pub fn bytes_per_pixel(fmt: u8) -> Option<usize> {
+    if is_yuv_planar(fmt) {
+        return None;
+    }
+
+    bits_per_pixel(fmt)
+        .filter(|bits| bits % 8 == 0)
+        .map(|bits| bits / 8)
+}
+
+/// Short name of a pixel format, e.g. "RGB565" for `FB_FMT_RGB16_565`
+///
+/// # Arguments
+/// * `fmt` - One of the `FB_FMT_*` format constants
+///
+/// # Returns
+/// The name, or None if the format is unknown. Aliases of a format, such as
+/// `FB_FMT_GREY` for `FB_FMT_Y8`, share its name.
+pub fn format_name(fmt: u8) -> Option<&'static str> {
+    let name = match fmt as u32 {
+        FB_FMT_Y1 => "Y1",
+        FB_FMT_Y2 => "Y2",
+        FB_FMT_Y4 => "Y4",
+        FB_FMT_Y8 => "Y8",
+        FB_FMT_Y16 => "Y16",
+        FB_FMT_RGB4 => "RGB4",
+        FB_FMT_RGB8 => "RGB8",
+        FB_FMT_RGB8_222 => "RGB222",
+        FB_FMT_RGB8_332 => "RGB332",
+        FB_FMT_RGB12_444 => "RGB444",
+        FB_FMT_RGB16_555 => "RGB555",
+        FB_FMT_RGB16_565 => "RGB565",
+        FB_FMT_RGB24 => "RGB24",
+        FB_FMT_RGB32 => "RGB32",
+        FB_FMT_RGBA16 => "RGBA16",
+        FB_FMT_RGBA32 => "RGBA32",
+        FB_FMT_AYUV => "AYUV",
+        FB_FMT_UYVY => "UYVY",
+        FB_FMT_YUYV => "YUYV",
+        FB_FMT_YVYU => "YVYU",
+        FB_FMT_Y41P => "Y41P",
+        FB_FMT_Y411 => "Y411",
+        FB_FMT_YVU9 => "YVU9",
+        FB_FMT_YUV9 => "YUV9",
+        FB_FMT_YV16 => "YV16",
+        FB_FMT_YV12 => "YV12",
+        FB_FMT_I420 => "I420",
+        FB_FMT_NV12 => "NV12",
+        FB_FMT_NV21 => "NV21",
+        _ => return None,
+    };
+    Some(name)
+}
+
+/// Color with 8-bit red, green, blue and alpha components
+///
+/// An alpha of 0xff is opaque. Use [`Color::pack`] to convert a color into the
+/// pixel value of a video format and [`Color::unpack`] to convert it back.
+///
+/// # Examples
+///
+/// ```
+/// use nuttx::video::fb::{Color, FB_FMT_RGB16_565};
+///
+/// let fmt = FB_FMT_RGB16_565 as u8;
+/// assert_eq!(Color::RED.pack(fmt), Some(0xf800));
+/// assert_eq!(Color::unpack(0xf800, fmt), Some(Color::RED));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Color {
+    /// Red component
+    pub r: u8,
+    /// Green component
+    pub g: u8,
+    /// Blue component
+    pub b: u8,
+    /// Alpha component, 0 is transparent and 0xff opaque
+    pub a: u8,
+}
+
+impl Color {
+    /// Opaque black
+    pub const BLACK: Color = Color::rgb(0x00, 0x00, 0x00);
+    /// Opaque white
+    pub const WHITE: Color = Color::rgb(0xff, 0xff, 0xff);
+    /// Opaque red
+    pub const RED: Color = Color::rgb(0xff, 0x00, 0x00);
+    /// Opaque green
+    pub const GREEN: Color = Color::rgb(0x00, 0xff, 0x00);
+    /// Opaque blue
+    pub const BLUE: Color = Color::rgb(0x00, 0x00, 0xff);
+    /// Opaque yellow
+    pub const YELLOW: Color = Color::rgb(0xff, 0xff, 0x00);
+    /// Opaque cyan
+    pub const CYAN: Color = Color::rgb(0x00, 0xff, 0xff);
+    /// Opaque magenta
+    pub const MAGENTA: Color = Color::rgb(0xff, 0x00, 0xff);
+    /// Opaque mid gray
+    pub const GRAY: Color = Color::rgb(0x80, 0x80, 0x80);
+    /// Fully transparent black
+    pub const TRANSPARENT: Color = Color::rgba(0x00, 0x00, 0x00, 0x00);
+
+    /// Creates an opaque color
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Color { r, g, b, a: 0xff }
+    }
+
+    /// Creates a color with the given alpha
+    pub const fn rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Color { r, g, b, a }
+    }
+
+    /// Creates an opaque color from 0xRRGGBB notation
+    pub const fn from_rgb888(rgb: u32) -> Self {
+        Color::rgb((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8)
+    }
+
+    /// Composites the color over a background color
+    ///
+    /// Uses the "source over" operator with straight (non-premultiplied)
+    /// alpha: each component becomes `(src * a + dst * (255 - a)) / 255`,
+    /// rounded to nearest, and the alpha becomes `a + dst.a * (255 - a) / 255`.
+    /// An opaque background therefore stays opaque.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nuttx::video::fb::Color;
+    ///
+    /// let dst = Color::BLACK;
+    ///
+    /// // Transparent colors leave the background unchanged
+    /// assert_eq!(Color::rgba(0xff, 0xff, 0xff, 0).over(dst), dst);
+    ///
+    /// // Half transparent white over black gives mid gray
+    /// assert_eq!(Color::rgba(0xff, 0xff, 0xff, 128).over(dst), Color::rgb(128, 128, 128));
+    /// assert_eq!(Color::rgba(0xff, 0x00, 0x40, 128).over(Color::WHITE), Color::rgb(255, 127, 159));
+    ///
+    /// // Opaque colors replace the background
+    /// assert_eq!(Color::RED.over(dst), Color::RED);
+    /// ```
+    pub fn over(&self, dst: Color) -> Color {
+        let alpha = self.a as u32;
+        let mix = |src: u8, dst: u8| {
+            ((src as u32 * alpha + dst as u32 * (0xff - alpha) + 0x7f) / 0xff) as u8
         };
 
-        if result < 0 { Err(result) } else { Ok(info) }
+        Color {
+            r: mix(self.r, dst.r),
+            g: mix(self.g, dst.g),
+            b: mix(self.b, dst.b),
+            a: (alpha + (dst.a as u32 * (0xff - alpha) + 0x7f) / 0xff) as u8,
+        }
     }
 
-    /// Update a rectangular region in the framebuffer
+    /// Packs the color into the pixel value of a video format
+    ///
+    /// The components are truncated to the width used by the format, e.g. 5, 6
+    /// and 5 bits for `FB_FMT_RGB16_565`. `FB_FMT_RGBA16` and `FB_FMT_RGBA32`
+    /// store the alpha above the color components, with 4 and 8 bits, the other
+    /// formats drop it. `FB_FMT_Y8` and `FB_FMT_Y16` store the luma of the color
+    /// (ITU-R BT.601 weights).
+    ///
+    /// # Precision
+    /// After a round trip through [`Color::unpack`], each component differs
+    /// from the original by at most `255 >> bits`, where `bits` is its width in
+    /// the format:
+    ///
+    /// | Format             | Bits (R, G, B, A) | Largest error (R, G, B, A) |
+    /// |--------------------|-------------------|----------------------------|
+    /// | `FB_FMT_RGB8_222`  | 2, 2, 2, -        | 63, 63, 63, -              |
+    /// | `FB_FMT_RGB8_332`  | 3, 3, 2, -        | 31, 31, 63, -              |
+    /// | `FB_FMT_RGB12_444` | 4, 4, 4, -        | 15, 15, 15, -              |
+    /// | `FB_FMT_RGB16_555` | 5, 5, 5, -        | 7, 7, 7, -                 |
+    /// | `FB_FMT_RGB16_565` | 5, 6, 5, -        | 7, 3, 7, -                 |
+    /// | `FB_FMT_RGBA16`    | 4, 4, 4, 4        | 15, 15, 15, 15             |
+    /// | `FB_FMT_RGB24`     | 8, 8, 8, -        | 0, 0, 0, -                 |
+    /// | `FB_FMT_RGB32`     | 8, 8, 8, -        | 0, 0, 0, -                 |
+    /// | `FB_FMT_RGBA32`    | 8, 8, 8, 8        | 0, 0, 0, 0                 |
+    ///
+    /// Formats without alpha unpack to opaque colors. Grayscale formats keep
+    /// gray colors exactly and reduce others to their luma.
     ///
     /// # Arguments
-    /// * `area` - The rectangular region to update
+    /// * `fmt` - One of the `FB_FMT_*` format constants
     ///
-    /// # Errors
-    /// Returns a libc error code if the ioctl fails
-    #[kconfig(CONFIG_FB_UPDATE = "y")]
-    pub fn update_area(&self, area: &Area) -> FrameBufferResult<()> {
-        // SAFETY: We're passing valid pointers to the ioctl
-        let result = unsafe {
-            libc::ioctl(
-                self.fd,
-                FBIO_UPDATE as libc::c_ulong,
-                area as *const Area as *mut c_void,
-            )
+    /// # Returns
+    /// The pixel value in the low bits, or None for palette-indexed, YUV and
+    /// sub-byte grayscale formats
+    pub fn pack(&self, fmt: u8) -> Option<u32> {
+        let (r, g, b, a) = (self.r as u32, self.g as u32, self.b as u32, self.a as u32);
+
+        let value = match fmt as u32 {
+            FB_FMT_RGB8_222 => (r >> 6) << 4 | (g >> 6) << 2 | b >> 6,
+            FB_FMT_RGB8_332 => (r >> 5) << 5 | (g >> 5) << 2 | b >> 6,
+            FB_FMT_RGB12_444 => (r >> 4) << 8 | (g >> 4) << 4 | b >> 4,
+            FB_FMT_RGB16_555 => (r >> 3) << 10 | (g >> 3) << 5 | b >> 3,
+            FB_FMT_RGB16_565 => (r >> 3) << 11 | (g >> 2) << 5 | b >> 3,
+            FB_FMT_RGBA16 => (a >> 4) << 12 | (r >> 4) << 8 | (g >> 4) << 4 | b >> 4,
+            FB_FMT_RGB24 | FB_FMT_RGB32 => r << 16 | g << 8 | b,
+            FB_FMT_RGBA32 => a << 24 | r << 16 | g << 8 | b,
+            FB_FMT_Y8 => (r * 299 + g * 587 + b * 114) / 1000,
+            FB_FMT_Y16 => (r * 299 + g * 587 + b * 114) * 0x101 / 1000,
+            _ => return None,
         };
+        Some(value)
+    }
 
-        if result < 0 {
-            Err(result as libc::c_int)
-        } else {
-            Ok(())
+    /// Unpacks the pixel value of a video format into a color
+    ///
+    /// Truncated components are scaled back to 8 bits, so packing and
+    /// unpacking a color only loses the truncated precision. Formats without
+    /// alpha yield opaque colors, and grayscale formats yield gray colors.
+    ///
+    /// # Arguments
+    /// * `value` - The pixel value in the low bits
+    /// * `fmt` - One of the `FB_FMT_*` format constants
+    ///
+    /// # Returns
+    /// The color, or None for the formats not supported by [`Color::pack`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nuttx::video::fb::{Color, FB_FMT_RGB16_565};
+    ///
+    /// let fmt = FB_FMT_RGB16_565 as u8;
+    /// let color = Color::unpack(Color::rgb(0x12, 0x34, 0x56).pack(fmt).unwrap(), fmt).unwrap();
+    /// assert_eq!(color, Color::rgb(0x10, 0x35, 0x52));
+    /// ```
+    pub fn unpack(value: u32, fmt: u8) -> Option<Color> {
+        let color = match fmt as u32 {
+            FB_FMT_RGB8_222 => Color::rgb(
+                expand(value >> 4, 2),
+                expand(value >> 2, 2),
+                expand(value, 2),
+            ),
+            FB_FMT_RGB8_332 => Color::rgb(
+                expand(value >> 5, 3),
+                expand(value >> 2, 3),
+                expand(value, 2),
+            ),
+            FB_FMT_RGB12_444 => Color::rgb(
+                expand(value >> 8, 4),
+                expand(value >> 4, 4),
+                expand(value, 4),
+            ),
+            FB_FMT_RGB16_555 => Color::rgb(
+                expand(value >> 10, 5),
+                expand(value >> 5, 5),
+                expand(value, 5),
+            ),
+            FB_FMT_RGB16_565 => Color::rgb(
+                expand(value >> 11, 5),
+                expand(value >> 5, 6),
+                expand(value, 5),
+            ),
+            FB_FMT_RGBA16 => Color::rgba(
+                expand(value >> 8, 4),
+                expand(value >> 4, 4),
+                expand(value, 4),
+                expand(value >> 12, 4),
+            ),
+            FB_FMT_RGB24 | FB_FMT_RGB32 => Color::from_rgb888(value),
+            FB_FMT_RGBA32 => Color {
+                a: (value >> 24) as u8,
+                ..Color::from_rgb888(value)
+            },
+            FB_FMT_Y8 => {
+                let luma = value as u8;
+                Color::rgb(luma, luma, luma)
+            }
+            FB_FMT_Y16 => {
+                let luma = expand(value >> 8, 8);
+                Color::rgb(luma, luma, luma)
+            }
+            _ => return None,
+        };
+        Some(color)
+    }
+}
+
+/// Scales the `bits` wide component in the low bits of `value` to 8 bits
+fn expand(value: u32, bits: u32) -> u8 {
+    let max = (1 << bits) - 1;
+    (((value & max) * 0xff + max / 2) / max) as u8
+}
+
+impl VideoInfo {
+    /// Resolution of the display as (xres, yres)
+    pub fn resolution(&self) -> (Coord, Coord) {
+        (self.xres, self.yres)
+    }
+
+    /// Pixel format of the display, one of the `FB_FMT_*` constants
+    pub fn pixel_format(&self) -> u8 {
+        self.fmt
+    }
+
+    /// Number of color planes
+    pub fn nplanes(&self) -> u8 {
+        self.nplanes
+    }
+
+    /// Short name of the pixel format, see [`format_name`]
+    pub fn format_name(&self) -> Option<&'static str> {
+        format_name(self.fmt)
+    }
+
+    /// Number of bits used by a pixel of the video format
+    ///
+    /// See [`bits_per_pixel`]
+    pub fn bits_per_pixel(&self) -> Option<usize> {
+        bits_per_pixel(self.fmt)
+    }
+
+    /// Number of bytes used by a pixel of the video format
+    ///
+    /// See [`bytes_per_pixel`]
+    pub fn bytes_per_pixel(&self) -> Option<usize> {
+        bytes_per_pixel(self.fmt)
+    }
+
+    /// Whether the video format is a grayscale format
+    pub fn is_grayscale(&self) -> bool {
+        is_grayscale(self.fmt)
+    }
+
+    /// Whether the video format is a YUV format
+    pub fn is_yuv(&self) -> bool {
+        is_yuv(self.fmt)
+    }
+}
+
+/// Summarizes the video mode, e.g. "320x240 RGB565, 1 plane"
+impl fmt::Display for VideoInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}x{} ", self.xres, self.yres)?;
+        match self.format_name() {
+            Some(name) => f.write_str(name)?,
+            None => write!(f, "format {:#04x}", self.fmt)?,
         }
+
+        let plural = if self.nplanes == 1 { "" } else { "s" };
+        write!(f, ", {} plane{}", self.nplanes, plural)
     }
+}
 
-    #[kconfig(CONFIG_FB_UPDATE = "n")]
-    pub fn update_area(&self, _area: &Area) -> FrameBufferResult<()> {
-        Ok(())
+impl PlaneInfo {
+    /// Length of a line in bytes, including padding
+    pub fn stride(&self) -> Coord {
+        self.stride
+    }
+
+    /// Bits per pixel
+    pub fn bpp(&self) -> u8 {
+        self.bpp
+    }
+
+    /// Length of the framebuffer memory in bytes
+    pub fn fblen(&self) -> usize {
+        self.fblen
+    }
+
+    /// Virtual resolution of the plane as (xres_virtual, yres_virtual)
+    pub fn virtual_resolution(&self) -> (u32, u32) {
+        (self.xres_virtual, self.yres_virtual)
     }
 }
 
-impl Drop for FrameBuffer {
-    /// Automatically closes the framebuffer device when the FrameBuffer instance goes out of scope
+/// Summarizes the plane layout, e.g. "stride 640, 16 bpp, 153600 bytes"
+impl fmt::Display for PlaneInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "stride {}, {} bpp, {} bytes",
+            self.stride, self.bpp, self.fblen
+        )
+    }
+}
+
+/// Whether the kernel passes [`FrameBuffer::update_area`] to the driver
+const HAS_UPDATE: bool = kconfig_enabled!(CONFIG_FB_UPDATE);
+
+/// Whether the kernel supports hardware overlays
+const HAS_OVERLAY: bool = kconfig_enabled!(CONFIG_FB_OVERLAY);
+
+/// Whether the kernel supports color lookup tables
+const HAS_CMAP: bool = kconfig_enabled!(CONFIG_FB_CMAP);
+
+/// Capabilities of a framebuffer, as returned by [`FrameBuffer::capabilities`]
+///
+/// Combines the video and plane information with values derived from them and
+/// the features enabled in the kernel configuration.
+#[derive(Debug, Clone, Copy)]
+pub struct FbCaps {
+    /// Video information of the display
+    pub video: VideoInfo,
+    /// Information of the first color plane
+    pub plane: PlaneInfo,
+    /// Bytes per pixel of the video format, see [`bytes_per_pixel`]
+    pub bytes_per_pixel: Option<usize>,
+    /// Size in bytes of the framebuffer memory
+    pub size: usize,
+    /// Whether updated regions are passed to the driver (CONFIG_FB_UPDATE)
+    pub update: bool,
+    /// Whether hardware overlays are supported (CONFIG_FB_OVERLAY)
+    pub overlay: bool,
+    /// Whether the color lookup table can be accessed (CONFIG_FB_CMAP)
+    pub cmap: bool,
+}
+
+/// Rectangle math on areas
+///
+/// The right and bottom edges are exclusive: an area covers the columns from
+/// `x` to `right() - 1` and the rows from `y` to `bottom() - 1`. Edges are
+/// returned as `u32`, as they may exceed the range of [`Coord`].
+///
+/// # Examples
+///
+/// ```
+/// use nuttx::video::fb::Area;
+///
+/// let a = Area { x: 0, y: 0, w: 10, h: 10 };
+/// let b = Area { x: 5, y: 5, w: 10, h: 10 };
+/// let common = a.intersect(&b).unwrap();
+/// assert_eq!((common.x, common.y, common.right(), common.bottom()), (5, 5, 10, 10));
+/// ```
+impl Area {
+    /// The first column right of the area
+    pub fn right(&self) -> u32 {
+        self.x as u32 + self.w as u32
+    }
+
+    /// The first row below the area
+    pub fn bottom(&self) -> u32 {
+        self.y as u32 + self.h as u32
+    }
+
+    /// Whether the area covers no pixels
+    pub fn is_empty(&self) -> bool {
+        self.w == 0 || self.h == 0
+    }
+
+    /// Whether the pixel at (x, y) lies within the area
+    pub fn contains(&self, x: Coord, y: Coord) -> bool {
+        x >= self.x && (x as u32) < self.right() && y >= self.y && (y as u32) < self.bottom()
+    }
+
+    /// The region covered by both areas
     ///
-    /// This ensures that system resources are properly released even if the FrameBuffer
-    /// instance is not explicitly closed. The underlying file descriptor is closed
-    /// using the libc::close() function.
+    /// # Returns
+    /// The common region, or None if the areas don't overlap
+    pub fn intersect(&self, other: &Area) -> Option<Area> {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        let right = self.right().min(other.right());
+        let bottom = self.bottom().min(other.bottom());
+
+        if right <= x as u32 || bottom <= y as u32 {
+            return None;
+        }
+
+        Some(Area {
+            x,
+            y,
+            w: (right - x as u32) as Coord,
+            h: (bottom - y as u32) as Coord,
+        })
+    }
+
+    /// The smallest area covering both areas
     ///
-    /// # Safety
-    /// This function is marked unsafe because it calls into C code through libc::close().
-    /// The file descriptor is guaranteed to be valid as it's managed by the FrameBuffer
-    /// struct and only set during successful initialization.
-    fn drop(&mut self) {
-        unsafe { libc::close(self.fd) };
+    /// Empty areas are ignored. The size saturates at the range of [`Coord`].
+    pub fn union(&self, other: &Area) -> Area {
+        if other.is_empty() {
+            return *self;
+        }
+        if self.is_empty() {
+            return *other;
+        }
+
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+        let right = self.right().max(other.right());
+        let bottom = self.bottom().max(other.bottom());
+
+        Area {
+            x,
+            y,
+            w: (right - x as u32).min(Coord::MAX as u32) as Coord,
+            h: (bottom - y as u32).min(Coord::MAX as u32) as Coord,
+        }
+    }
+
+    /// The part of the area within `bounds`
+    ///
+    /// # Returns
+    /// The clipped area, or None if the area lies entirely outside of `bounds`
+    pub fn clip_to(&self, bounds: &Area) -> Option<Area> {
+        self.intersect(bounds)
+    }
+}
+
+/// Memory mapping of the framebuffer memory for direct pixel access
+///
+/// Obtained from [`FrameBuffer::map`]. The mapping is released when this guard
+/// is dropped, and stays valid independently of the [`FrameBuffer`] it was
+/// created from.
+#[derive(Debug)]
+pub struct FrameBufferMmap {
+    mem: *mut u8,
+    plane: PlaneInfo,
+}
+
+// SAFETY: The guard owns its mapping, which stays valid in every thread of the
+// task until it is unmapped on drop. Writes require `&mut self`, so sharing a
+// reference only allows reading, as for any byte slice.
+unsafe impl Send for FrameBufferMmap {}
+unsafe impl Sync for FrameBufferMmap {}
+
+impl FrameBufferMmap {
+    /// Plane information describing the layout of the mapped memory
+    pub fn plane_info(&self) -> &PlaneInfo {
+        &self.plane
+    }
+
+    /// Returns the mapped framebuffer memory
+    pub fn as_slice(&self) -> &[u8] {
+        // SAFETY: The mapping covers `fblen` bytes until it is unmapped on drop
+        unsafe { core::slice::from_raw_parts(self.mem, self.plane.fblen) }
+    }
+
+    /// Returns the mapped framebuffer memory for writing
+    pub fn as_mut_slice(&mut self) -> &mut [u8] {
+        // SAFETY: The mapping covers `fblen` bytes until it is unmapped on drop,
+        // and the exclusive borrow prevents aliasing through this guard
+        unsafe { core::slice::from_raw_parts_mut(self.mem, self.plane.fblen) }
+    }
+
+    /// Iterate over the scanlines of the mapped memory
+    ///
+    /// Each item covers the visible pixels of one row, `stride` bytes apart.
+    /// The padding at the end of each row is excluded, so the slices are
+    /// `xres_virtual * bpp / 8` bytes long, rounded up to whole bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use core::ffi::CStr;
+    /// use nuttx::video::fb::FrameBuffer;
+    ///
+    /// let fb = FrameBuffer::new(CStr::from_bytes_with_nul(b"/dev/fb0\0").unwrap()).unwrap();
+    /// let mut map = fb.map().unwrap();
+    ///
+    /// // Draw a horizontal gradient on an 8 bpp display
+    /// for row in map.rows_mut() {
+    ///     for (x, pixel) in row.iter_mut().enumerate() {
+    ///         *pixel = x as u8;
+    ///     }
+    /// }
+    /// ```
+    pub fn rows_mut(&mut self) -> impl Iterator<Item = &mut [u8]> {
+        let (len, count) = (self.row_len(), self.row_count());
+        let stride = (self.plane.stride as usize).max(1);
+
+        self.as_mut_slice()
+            .chunks_mut(stride)
+            .take(count)
+            .map(move |row| &mut row[..len])
+    }
+
+    /// Returns the visible pixels of the scanline at row `y`
+    ///
+    /// # Returns
+    /// The row as described for [`FrameBufferMmap::rows_mut`], or None if `y`
+    /// lies outside of the mapped memory
+    pub fn row_mut(&mut self, y: usize) -> Option<&mut [u8]> {
+        if y >= self.row_count() {
+            return None;
+        }
+
+        let start = y * self.plane.stride as usize;
+        let len = self.row_len();
+        self.as_mut_slice().get_mut(start..start + len)
+    }
+
+    /// Number of bytes holding the visible pixels of a scanline
+    ///
+    /// Falls back to the stride if the driver reports no virtual width.
+    fn row_len(&self) -> usize {
+        let stride = self.plane.stride as usize;
+        let width = (self.plane.xres_virtual as usize * self.plane.bpp as usize).div_ceil(8);
+        if width == 0 {
+            stride
+        } else {
+            width.min(stride)
+        }
+    }
+
+    /// Number of scanlines whose visible pixels lie within the mapping
+    fn row_count(&self) -> usize {
+        let (stride, len) = (self.plane.stride as usize, self.row_len());
+        if stride == 0 || len == 0 || self.plane.fblen < len {
+            return 0;
+        }
+
+        let count = (self.plane.fblen - len) / stride + 1;
+        match self.plane.yres_virtual as usize {
+            0 => count,
+            yres => count.min(yres),
+        }
+    }
+}
+
+impl Drop for FrameBufferMmap {
+    /// Unmaps the framebuffer memory
+    fn drop(&mut self) {
+        unsafe { libc::munmap(self.mem as *mut c_void, self.plane.fblen) };
+    }
+}
+
+/// Mapped framebuffer memory together with the video mode used for drawing
+#[derive(Debug)]
+struct Canvas {
+    map: FrameBufferMmap,
+    info: VideoInfo,
+}
+
+impl Canvas {
+    /// Fill a rectangular region with a color packed for the video format
+    fn fill(&mut self, area: &Area, color: Color) -> FrameBufferResult<()> {
+        let (pixel, bpp) = pack_color(self.info.fmt, color).ok_or(-libc::ENOTSUP)?;
+        let pixel = &pixel[..bpp];
+
+        let (x, y) = (area.x as usize, area.y as usize);
+        let (w, h) = (area.w as usize, area.h as usize);
+        if x + w > self.info.xres as usize || y + h > self.info.yres as usize {
+            return Err(-libc::EINVAL);
+        }
+
+        let stride = self.map.plane_info().stride as usize;
+        fill_rows(self.map.as_mut_slice(), stride, area, pixel)
+    }
+
+    /// Composite a color over a rectangular region
+    fn blend(&mut self, area: &Area, color: Color) -> FrameBufferResult<()> {
+        let fmt = self.info.fmt;
+        if !is_blendable(fmt) {
+            return Err(-libc::ENOTSUP);
+        }
+        if color.a == 0xff {
+            return self.fill(area, color);
+        }
+
+        let bpp = bytes_per_pixel(fmt).ok_or(-libc::ENOTSUP)?;
+        let (x, y) = (area.x as usize, area.y as usize);
+        let (w, h) = (area.w as usize, area.h as usize);
+        if x + w > self.info.xres as usize || y + h > self.info.yres as usize {
+            return Err(-libc::EINVAL);
+        }
+        if color.a == 0 {
+            return Ok(());
+        }
+
+        let stride = self.map.plane_info().stride as usize;
+        let mem = self.map.as_mut_slice();
+        for row in y..y + h {
+            let start = row * stride + x * bpp;
+            let line = mem.get_mut(start..start + w * bpp).ok_or(-libc::EINVAL)?;
+            for dst in line.chunks_exact_mut(bpp) {
+                let background = Color::unpack(unpack_pixel(dst), fmt).ok_or(-libc::ENOTSUP)?;
+                let (pixel, _) = pack_color(fmt, color.over(background)).ok_or(-libc::ENOTSUP)?;
+                dst.copy_from_slice(&pixel[..bpp]);
+            }
+        }
+        Ok(())
+    }
+
+    /// Copy rows of pixels in the video format into a rectangular region
+    ///
+    /// The region is clipped to the display. Returns the clipped region, or None
+    /// if it lies entirely outside of the display.
+    fn blit(
+        &mut self,
+        area: &Area,
+        src: &[u8],
+        src_stride: usize,
+    ) -> FrameBufferResult<Option<Area>> {
+        let bpp = bytes_per_pixel(self.info.fmt).ok_or(-libc::ENOTSUP)?;
+
+        let (w, h) = (area.w as usize, area.h as usize);
+        if w == 0 || h == 0 {
+            return Ok(None);
+        }
+
+        // The source must hold the whole rectangle, even the clipped part
+        let row_len = w * bpp;
+        if src_stride < row_len || src.len() < (h - 1) * src_stride + row_len {
+            return Err(-libc::EINVAL);
+        }
+
+        let (xres, yres) = (self.info.xres, self.info.yres);
+        if area.x >= xres || area.y >= yres {
+            return Ok(None);
+        }
+
+        let clipped = Area {
+            x: area.x,
+            y: area.y,
+            w: area.w.min(xres - area.x),
+            h: area.h.min(yres - area.y),
+        };
+
+        let (x, y) = (clipped.x as usize, clipped.y as usize);
+        let len = clipped.w as usize * bpp;
+        let stride = self.map.plane_info().stride as usize;
+        let mem = self.map.as_mut_slice();
+        for row in 0..clipped.h as usize {
+            let start = (y + row) * stride + x * bpp;
+            let line = mem.get_mut(start..start + len).ok_or(-libc::EINVAL)?;
+            line.copy_from_slice(&src[row * src_stride..row * src_stride + len]);
+        }
+        Ok(Some(clipped))
+    }
+
+    /// Draw a line of text with the built-in font
+    ///
+    /// Pixels outside of the display are clipped. Returns the clipped region
+    /// covered by the text, or None if it lies entirely outside of the display.
+    fn text(
+        &mut self,
+        x: Coord,
+        y: Coord,
+        s: &str,
+        fg: Color,
+        bg: Option<Color>,
+    ) -> FrameBufferResult<Option<Area>> {
+        let fmt = self.info.fmt;
+        let (fg, bpp) = pack_color(fmt, fg).ok_or(-libc::ENOTSUP)?;
+        let bg = match bg {
+            Some(color) => Some(pack_color(fmt, color).ok_or(-libc::ENOTSUP)?.0),
+            None => None,
+        };
+
+        let (xres, yres) = (self.info.xres, self.info.yres);
+        if x >= xres || y >= yres || s.is_empty() {
+            return Ok(None);
+        }
+
+        let width = s.chars().count().saturating_mul(text::GLYPH_WIDTH);
+        let clipped = Area {
+            x,
+            y,
+            w: width.min((xres - x) as usize) as Coord,
+            h: text::GLYPH_HEIGHT.min((yres - y) as usize) as Coord,
+        };
+
+        let stride = self.map.plane_info().stride as usize;
+        let mem = self.map.as_mut_slice();
+        let right = clipped.right() as usize;
+        for (i, ch) in s.chars().enumerate() {
+            let left = x as usize + i * text::GLYPH_WIDTH;
+            if left >= right {
+                break;
+            }
+
+            let glyph = text::glyph(ch);
+            for (row, bits) in glyph.iter().take(clipped.h as usize).enumerate() {
+                let start = (y as usize + row) * stride;
+                for col in 0..text::GLYPH_WIDTH.min(right - left) {
+                    let pixel = if (bits >> col) & 1 != 0 {
+                        &fg
+                    } else if let Some(bg) = &bg {
+                        bg
+                    } else {
+                        continue;
+                    };
+
+                    let offset = start + (left + col) * bpp;
+                    let dst = mem.get_mut(offset..offset + bpp).ok_or(-libc::EINVAL)?;
+                    dst.copy_from_slice(&pixel[..bpp]);
+                }
+            }
+        }
+        Ok(Some(clipped))
+    }
+}
+
+/// Pack a color into the in-memory pixel representation of `fmt`
+///
+/// Returns the packed bytes and the number of bytes per pixel, or None if the
+/// format is not supported for drawing.
+fn pack_color(fmt: u8, color: Color) -> Option<([u8; 4], usize)> {
+    let value = color.pack(fmt)?;
+    let bpp = bytes_per_pixel(fmt)?;
+
+    let bytes = match bpp {
+        1 => [value as u8, 0, 0, 0],
+        2 => {
+            let [b0, b1] = (value as u16).to_ne_bytes();
+            [b0, b1, 0, 0]
+        }
+        3 => {
+            let [b0, b1, b2, _] = value.to_le_bytes();
+            [b0, b1, b2, 0]
+        }
+        _ => value.to_ne_bytes(),
+    };
+    Some((bytes, bpp))
+}
+
+/// Read the pixel value stored by [`pack_color`] from its bytes
+fn unpack_pixel(bytes: &[u8]) -> u32 {
+    match *bytes {
+        [b0] => b0 as u32,
+        [b0, b1] => u16::from_ne_bytes([b0, b1]) as u32,
+        [b0, b1, b2] => u32::from_le_bytes([b0, b1, b2, 0]),
+        [b0, b1, b2, b3] => u32::from_ne_bytes([b0, b1, b2, b3]),
+        _ => 0,
+    }
+}
+
+/// Whether colors can be composited onto pixels of `fmt`
+///
+/// Formats with alpha keep it, the other RGB formats are treated as opaque.
+/// Palette, grayscale and YUV formats are not supported.
+fn is_blendable(fmt: u8) -> bool {
+    matches!(
+        fmt as u32,
+        FB_FMT_RGBA16
+            | FB_FMT_RGBA32
+            | FB_FMT_RGB8_222
+            | FB_FMT_RGB8_332
+            | FB_FMT_RGB16_555
+            | FB_FMT_RGB16_565
+            | FB_FMT_RGB24
+            | FB_FMT_RGB32
+    )
+}
+
+/// Fill a rectangular region of rows `stride` bytes apart with a packed pixel
+///
+/// A pixel of identical bytes, e.g. black, white or any 8 bpp color, is
+/// written with memset, over all rows at once if they are contiguous. Other
+/// pixels are copied one at a time.
+///
+/// # Errors
+/// Returns `-EINVAL` if the region lies outside of `mem`
+fn fill_rows(mem: &mut [u8], stride: usize, area: &Area, pixel: &[u8]) -> FrameBufferResult<()> {
+    let bpp = pixel.len();
+    let (x, y) = (area.x as usize, area.y as usize);
+    let (w, h) = (area.w as usize, area.h as usize);
+
+    let uniform = pixel.iter().all(|&byte| byte == pixel[0]);
+    if uniform && x == 0 && w * bpp == stride {
+        let block = mem
+            .get_mut(y * stride..(y + h) * stride)
+            .ok_or(-libc::EINVAL)?;
+        block.fill(pixel[0]);
+        return Ok(());
+    }
+
+    for row in y..y + h {
+        let start = row * stride + x * bpp;
+        let line = mem.get_mut(start..start + w * bpp).ok_or(-libc::EINVAL)?;
+        if uniform {
+            line.fill(pixel[0]);
+        } else {
+            fill_pixels(line, pixel);
+        }
+    }
+    Ok(())
+}
+
+/// Copy a packed pixel to every pixel of a row
+fn fill_pixels(line: &mut [u8], pixel: &[u8]) {
+    for dst in line.chunks_exact_mut(pixel.len()) {
+        dst.copy_from_slice(pixel);
+    }
+}
+
+/// Checks that `area` lies within a display of the given resolution
+///
+/// The edges are computed as u32, so an area whose end overflows [`Coord`] is
+/// rejected rather than wrapped around. Empty areas are always valid.
+///
+/// # Errors
+/// Returns `-EINVAL` if any part of the area lies outside of the display
+fn check_area(area: &Area, (xres, yres): (Coord, Coord)) -> FrameBufferResult<()> {
+    if area.is_empty() {
+        return Ok(());
+    }
+    if area.right() > xres as u32 || area.bottom() > yres as u32 {
+        return Err(-libc::EINVAL);
+    }
+    Ok(())
+}
+
+/// FrameBuffer structure wrapping the framebuffer functionality
+///
+/// # Thread safety
+///
+/// A FrameBuffer is `Send` and `Sync`. Descriptors are shared by all threads
+/// of a task, and the framebuffer driver serializes the ioctls issued through
+/// `&self`, so concurrent queries are safe. Drawing requires `&mut self`.
+/// Operations made of several ioctls, such as [`FrameBuffer::pan_display`],
+/// are not atomic, so threads changing the display state concurrently need
+/// external synchronization to get consistent results.
+#[derive(Debug)]
+pub struct FrameBuffer {
+    /// Mapped memory, released before the descriptor is closed
+    canvas: Option<Canvas>,
+    fd: DeviceFd,
+    /// Resolution queried when the device was opened, None if that failed
+    resolution: Option<(Coord, Coord)>,
+    /// Power level before the panel was turned off by
+    /// [`FrameBuffer::set_power`], 0 if unknown
+    saved_power: AtomicI32,
+}
+
+impl FrameBuffer {
+    /// Open the framebuffer device
+    pub fn new(path: &CStr) -> FrameBufferResult<Self> {
+        Self::new_with_flags(path, libc::O_RDWR)
+    }
+
+    /// Open the framebuffer device with the given open flags
+    ///
+    /// Use this to open the device in non-blocking mode, e.g. with
+    /// `O_RDWR | O_NONBLOCK`, see [`FrameBuffer::set_nonblocking`] for the
+    /// operations affected. [`FrameBufferBuilder`] offers the common flags as
+    /// named options.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the framebuffer device
+    /// * `flags` - Flags passed to `open`, including the access mode
+    ///
+    /// # Errors
+    /// Returns the negative errno if the device cannot be opened
+    pub fn new_with_flags(path: &CStr, flags: libc::c_int) -> FrameBufferResult<Self> {
+        let fd = errno_result(unsafe { libc::open(path.as_ptr(), flags) })?;
+        // SAFETY: The descriptor was just opened
+        Ok(Self::from_device_fd(unsafe { fd::adopt(fd) }))
+    }
+
+    /// Adopt an already opened framebuffer file descriptor
+    ///
+    /// Ownership of `fd` is transferred to the returned FrameBuffer, which
+    /// closes it when dropped. With the `std` feature, prefer converting an
+    /// `OwnedFd`, which needs no unsafe code.
+    ///
+    /// # Arguments
+    /// * `fd` - Open file descriptor of a framebuffer device
+    ///
+    /// # Safety
+    /// `fd` must be an open descriptor owned by the caller, which must not
+    /// close it or use it as an owner afterwards, e.g. a descriptor opened by
+    /// board initialization code must be handed over only once.
+    pub unsafe fn from_raw_fd(fd: RawFd) -> Self {
+        // SAFETY: The caller transfers ownership of the descriptor
+        Self::from_device_fd(unsafe { fd::adopt(fd) })
+    }
+
+    /// Wrap an owned descriptor, caching the resolution for
+    /// [`FrameBuffer::update_area`]
+    fn from_device_fd(fd: DeviceFd) -> Self {
+        let mut fb = Self {
+            canvas: None,
+            fd,
+            resolution: None,
+            saved_power: AtomicI32::new(0),
+        };
+        fb.resolution = fb.get_video_info().ok().map(|info| info.resolution());
+        fb
+    }
+
+    /// Switch the device between blocking and non-blocking mode
+    ///
+    /// Non-blocking mode only affects operations that wait on the device file:
+    /// `read` and `write` of the descriptor, and waiting for it with `poll`,
+    /// e.g. for the completion of [`FrameBuffer::pan_display`]. Queries,
+    /// drawing and mapped pixel access never block. Driver ioctls such as
+    /// [`FrameBuffer::wait_vsync`] are handled by the driver and still wait.
+    ///
+    /// # Arguments
+    /// * `nonblock` - true to return immediately from operations that cannot
+    ///   make progress
+    ///
+    /// # Errors
+    /// Returns the negative errno if the file status flags cannot be changed
+    pub fn set_nonblocking(&mut self, nonblock: bool) -> FrameBufferResult<()> {
+        let flags = errno_result(unsafe { libc::fcntl(self.as_raw_fd(), libc::F_GETFL) })?;
+
+        let flags = if nonblock {
+            flags | libc::O_NONBLOCK
+        } else {
+            flags & !libc::O_NONBLOCK
+        };
+
+        errno_result(unsafe { libc::fcntl(self.as_raw_fd(), libc::F_SETFL, flags) })?;
+        Ok(())
+    }
+
+    /// Get video information from the framebuffer device
+    ///
+    /// # Returns
+    /// `VideoInfo` structure containing the framebuffer's video information
+    ///
+    /// # Errors
+    /// Returns the negative errno if the ioctl fails, read from `errno` as
+    /// `ioctl` itself only returns -1. E.g. `-ENOTTY` tells that the device is
+    /// not a framebuffer, while `-EINVAL` is reported by the driver.
+    pub fn get_video_info(&self) -> FrameBufferResult<VideoInfo> {
+        // SAFETY: FBIOGET_VIDEOINFO stores a fb_videoinfo_s
+        unsafe { ioctl::ioctl_read(self.as_raw_fd(), FBIOGET_VIDEOINFO) }
+    }
+
+    /// Get plane information from the framebuffer device
+    ///
+    /// # Returns
+    /// `PlaneInfo` structure containing the framebuffer's plane information
+    ///
+    /// # Errors
+    /// Returns the negative errno if the ioctl fails, see
+    /// [`FrameBuffer::get_video_info`]
+    pub fn get_plane_info(&self) -> FrameBufferResult<PlaneInfo> {
+        // SAFETY: FBIOGET_PLANEINFO stores a fb_planeinfo_s
+        unsafe { ioctl::ioctl_read(self.as_raw_fd(), FBIOGET_PLANEINFO) }
+    }
+
+    /// Change the resolution and pixel format of the display
+    ///
+    /// The mode is passed to the driver with the Linux compatible
+    /// `FBIOPUT_VSCREENINFO` request, the format being described by its bits
+    /// per pixel and whether it is grayscale, and the virtual resolution
+    /// being the visible one. Many drivers support a single mode and either
+    /// reject the request or ignore it, so the mode is read back with
+    /// [`FrameBuffer::get_video_info`] and compared with the requested one.
+    ///
+    /// Once the driver accepted the request, the resolution checked by
+    /// [`FrameBuffer::update_area`] is queried again and the memory mapped for
+    /// drawing is released, to be mapped with the new layout on the next
+    /// drawing operation. Mappings returned by [`FrameBuffer::map`] keep the
+    /// old layout and must be obtained again.
+    ///
+    /// # Arguments
+    /// * `info` - The requested mode, `nplanes` and `noverlays` are ignored
+    ///
+    /// # Errors
+    /// Returns `-ENOTSUP` if the format has no known bits per pixel, if the
+    /// driver does not support changing the mode, if CONFIG_VIDEO_FB is
+    /// disabled, or if the mode read back differs from `info` in its
+    /// resolution or format. Returns the negative errno if the driver rejects
+    /// the mode or it cannot be read back.
+    #[kconfig(CONFIG_VIDEO_FB = "y")]
+    pub fn set_video_mode(&mut self, info: &VideoInfo) -> FrameBufferResult<()> {
+        let bpp = bits_per_pixel(info.fmt).ok_or(-libc::ENOTSUP)?;
+
+        let mut var = unsafe { core::mem::zeroed::<bindings::fb_var_screeninfo>() };
+        var.xres = info.xres as u32;
+        var.yres = info.yres as u32;
+        var.xres_virtual = info.xres as u32;
+        var.yres_virtual = info.yres as u32;
+        var.bits_per_pixel = bpp as u32;
+        var.grayscale = is_grayscale(info.fmt) as u32;
+
+        // SAFETY: FBIOPUT_VSCREENINFO reads a fb_var_screeninfo
+        match unsafe { ioctl::ioctl_write(self.as_raw_fd(), FBIOPUT_VSCREENINFO, &var) } {
+            Err(err) if err == -libc::ENOTTY => return Err(-libc::ENOTSUP),
+            result => result?,
+        };
+
+        self.canvas = None;
+        let current = self.get_video_info();
+        self.resolution = current.ok().map(|current| current.resolution());
+
+        let current = current?;
+        if (current.xres, current.yres, current.fmt) != (info.xres, info.yres, info.fmt) {
+            return Err(-libc::ENOTSUP);
+        }
+        Ok(())
+    }
+
+    #[kconfig(CONFIG_VIDEO_FB = "n")]
+    pub fn set_video_mode(&mut self, _info: &VideoInfo) -> FrameBufferResult<()> {
+        Err(-libc::ENOTSUP)
+    }
+
+    /// Get the capabilities of the framebuffer in a single call
+    ///
+    /// Queries the video and plane information, so it performs exactly two
+    /// ioctls. The supported features are taken from the kernel configuration.
+    ///
+    /// # Returns
+    /// `FbCaps` structure combining the video and plane information
+    ///
+    /// # Errors
+    /// Returns the negative errno if either ioctl fails
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use core::ffi::CStr;
+    /// use nuttx::video::fb::FrameBuffer;
+    ///
+    /// let fb = FrameBuffer::new(CStr::from_bytes_with_nul(b"/dev/fb0\0").unwrap()).unwrap();
+    /// let caps = fb.capabilities().unwrap();
+    /// if !caps.update {
+    ///     // Drawing is visible without calling update_area
+    /// }
+    /// ```
+    pub fn capabilities(&self) -> FrameBufferResult<FbCaps> {
+        let video = self.get_video_info()?;
+        let plane = self.get_plane_info()?;
+
+        Ok(FbCaps {
+            video,
+            plane,
+            bytes_per_pixel: video.bytes_per_pixel(),
+            size: plane.fblen,
+            update: HAS_UPDATE,
+            overlay: HAS_OVERLAY,
+            cmap: HAS_CMAP,
+        })
+    }
+
+    /// Issue an arbitrary ioctl on the framebuffer device
+    ///
+    /// This is the extension point for board or vendor specific commands that
+    /// have no typed method. Prefer the typed methods where they exist.
+    ///
+    /// # Arguments
+    /// * `request` - The ioctl command
+    /// * `arg` - The argument of the command, as expected by the driver
+    ///
+    /// # Returns
+    /// The non-negative result of the ioctl
+    ///
+    /// # Errors
+    /// Returns the negative errno if the ioctl fails
+    ///
+    /// # Safety
+    /// `arg` must be valid for the access the driver performs for `request`.
+    pub unsafe fn ioctl(
+        &self,
+        request: IoctlRequest,
+        arg: *mut c_void,
+    ) -> FrameBufferResult<libc::c_int> {
+        errno_result(unsafe { libc::ioctl(self.as_raw_fd(), request as _, arg) })
+    }
+
+    /// Issue an arbitrary ioctl whose argument is a pointer to `T`
+    ///
+    /// Typed counterpart of [`FrameBuffer::ioctl`], the request being of the
+    /// same type as for the helpers of [`crate::io::ioctl`].
+    ///
+    /// # Arguments
+    /// * `request` - The ioctl command
+    /// * `arg` - The argument structure, read and/or written by the driver
+    ///
+    /// # Returns
+    /// The non-negative result of the ioctl
+    ///
+    /// # Errors
+    /// Returns the negative errno if the ioctl fails
+    ///
+    /// # Safety
+    /// Like [`FrameBuffer::ioctl`], the driver must expect a pointer to a
+    /// structure with the layout of `T` for `request`, and any value it
+    /// stores must be valid for `T`. Pointers inside `T` must be valid for
+    /// the accesses the driver performs.
+    pub unsafe fn ioctl_ref<T>(
+        &self,
+        request: IoctlRequest,
+        arg: &mut T,
+    ) -> FrameBufferResult<libc::c_int> {
+        unsafe { ioctl::ioctl_readwrite(self.as_raw_fd(), request, arg) }
+    }
+
+    /// Map the framebuffer memory for direct pixel access
+    ///
+    /// Maps `fblen` bytes of the framebuffer as reported by
+    /// [`FrameBuffer::get_plane_info`].
+    ///
+    /// # Returns
+    /// `FrameBufferMmap` guard giving access to the mapped memory
+    ///
+    /// # Errors
+    /// Returns the negative errno if the plane information cannot be read or
+    /// the mapping fails
+    pub fn map(&self) -> FrameBufferResult<FrameBufferMmap> {
+        let plane = self.get_plane_info()?;
+        self.map_plane(plane)
+    }
+
+    /// Write pixel data to the framebuffer memory without mapping it
+    ///
+    /// Uses `pwrite` on the device, for platforms whose framebuffer driver
+    /// supports writes but not `mmap`. The data is in the in-memory format of
+    /// the plane, i.e. rows of `stride` bytes.
+    ///
+    /// # Arguments
+    /// * `byte_offset` - Offset into the framebuffer memory to write at
+    /// * `data` - The bytes to write
+    ///
+    /// # Errors
+    /// Returns `-EINVAL` if the data would extend past the `fblen` bytes of
+    /// the framebuffer, `-EIO` if the driver stops accepting data, or the
+    /// negative errno if the plane information cannot be read or a write fails
+    pub fn write_at(&self, byte_offset: usize, data: &[u8]) -> FrameBufferResult<()> {
+        let plane = self.get_plane_info()?;
+        match byte_offset.checked_add(data.len()) {
+            Some(end) if end <= plane.fblen => {}
+            _ => return Err(-libc::EINVAL),
+        }
+
+        // A write may be cut short, so continue until all data is written
+        let mut written = 0;
+        while written < data.len() {
+            let rest = &data[written..];
+            let ret = unsafe {
+                libc::pwrite(
+                    self.as_raw_fd(),
+                    rest.as_ptr() as *const c_void,
+                    rest.len(),
+                    (byte_offset + written) as libc::off_t,
+                )
+            };
+
+            match ret {
+                n if n < 0 => return Err(errno()),
+                0 => return Err(-libc::EIO),
+                n => written += n as usize,
+            }
+        }
+        Ok(())
+    }
+
+    /// Copy the visible pixels of the display into a tightly packed buffer
+    ///
+    /// Reads the framebuffer memory with `pread`, so it needs no mapping and
+    /// sees the pixels drawn through [`FrameBuffer::map`] as well. Rows are
+    /// copied without the padding at the end of each stride.
+    ///
+    /// The pixels stay in the video format of the display, which is returned
+    /// with them, e.g. for saving a screenshot on the host. Both are read
+    /// together, so they match even if the video mode is changed later.
+    ///
+    /// # Returns
+    /// The video information of the display, and `yres` rows of `xres` pixels
+    /// in its `fmt`, with rows of formats smaller than a byte per pixel padded
+    /// to whole bytes
+    ///
+    /// # Errors
+    /// Returns `-EINVAL` if the plane is too small for the resolution, `-EIO`
+    /// if the driver stops returning data, or the negative errno if the
+    /// information cannot be read or a read fails
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use core::ffi::CStr;
+    /// use nuttx::video::fb::FrameBuffer;
+    ///
+    /// let fb = FrameBuffer::new(CStr::from_bytes_with_nul(b"/dev/fb0\0").unwrap()).unwrap();
+    /// let (info, pixels) = fb.snapshot().unwrap();
+    /// std::fs::write("/tmp/screen.raw", &pixels).unwrap();
+    /// println!("{}x{} {:?}", info.xres, info.yres, info.format_name());
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn snapshot(&self) -> FrameBufferResult<(VideoInfo, std::vec::Vec<u8>)> {
+        let info = self.get_video_info()?;
+        let plane = self.get_plane_info()?;
+
+        let (xres, yres) = (info.xres as usize, info.yres as usize);
+        let stride = plane.stride as usize;
+        let row_len = (xres * plane.bpp as usize).div_ceil(8);
+        if yres > 0 && (row_len > stride || (yres - 1) * stride + row_len > plane.fblen) {
+            return Err(-libc::EINVAL);
+        }
+
+        let mut pixels = std::vec![0; row_len * yres];
+        for (y, row) in pixels.chunks_exact_mut(row_len.max(1)).enumerate() {
+            self.read_at(y * stride, row)?;
+        }
+        Ok((info, pixels))
+    }
+
+    /// Read the framebuffer memory at `byte_offset` into `buf`
+    #[cfg(feature = "std")]
+    fn read_at(&self, byte_offset: usize, buf: &mut [u8]) -> FrameBufferResult<()> {
+        // A read may be cut short, so continue until the buffer is full
+        let mut read = 0;
+        while read < buf.len() {
+            let rest = &mut buf[read..];
+            let ret = unsafe {
+                libc::pread(
+                    self.as_raw_fd(),
+                    rest.as_mut_ptr() as *mut c_void,
+                    rest.len(),
+                    (byte_offset + read) as libc::off_t,
+                )
+            };
+
+            match ret {
+                n if n < 0 => return Err(errno()),
+                0 => return Err(-libc::EIO),
+                n => read += n as usize,
+            }
+        }
+        Ok(())
+    }
+
+    /// Map `fblen` bytes of the memory described by `plane`
+    fn map_plane(&self, plane: PlaneInfo) -> FrameBufferResult<FrameBufferMmap> {
+        // SAFETY: A null address lets the kernel choose where to map the memory
+        let mem = unsafe {
+            libc::mmap(
+                ptr::null_mut(),
+                plane.fblen,
+                (bindings::PROT_READ | bindings::PROT_WRITE) as libc::c_int,
+                bindings::MAP_SHARED as libc::c_int,
+                self.as_raw_fd(),
+                0,
+            )
+        };
+
+        let mem = errno_result_ptr(mem)?;
+
+        Ok(FrameBufferMmap {
+            mem: mem as *mut u8,
+            plane,
+        })
+    }
+
+    /// Set a single pixel to a color
+    ///
+    /// The framebuffer memory is mapped on the first drawing operation.
+    ///
+    /// # Arguments
+    /// * `x` - Column of the pixel
+    /// * `y` - Row of the pixel
+    /// * `color` - Color, packed to the video format with [`Color::pack`]
+    ///
+    /// # Errors
+    /// Returns `-ENOTSUP` if the video format cannot be packed or uses less
+    /// than a byte per pixel, `-EINVAL` if the pixel lies outside of the
+    /// display, or the negative errno if the framebuffer cannot be mapped
+    pub fn put_pixel(&mut self, x: Coord, y: Coord, color: Color) -> FrameBufferResult<()> {
+        let area = Area { x, y, w: 1, h: 1 };
+        self.canvas()?.fill(&area, color)
+    }
+
+    /// Fill a rectangular region with a color
+    ///
+    /// # Arguments
+    /// * `area` - The rectangular region to fill
+    /// * `color` - Color, packed to the video format with [`Color::pack`]
+    ///
+    /// # Errors
+    /// Same as [`FrameBuffer::put_pixel`], with `-EINVAL` returned if any part
+    /// of the region lies outside of the display
+    pub fn fill_rect(&mut self, area: &Area, color: Color) -> FrameBufferResult<()> {
+        self.canvas()?.fill(area, color)
+    }
+
+    /// Composite a translucent color over a rectangular region
+    ///
+    /// Each pixel is blended with [`Color::over`], using the alpha of `color`.
+    /// `FB_FMT_RGBA16` and `FB_FMT_RGBA32` keep the resulting alpha, the
+    /// other RGB formats are treated as an opaque background.
+    ///
+    /// # Arguments
+    /// * `area` - The rectangular region to blend
+    /// * `color` - Color drawn over the region
+    ///
+    /// # Errors
+    /// Returns `-ENOTSUP` if the video format is not an RGB format of whole
+    /// bytes per pixel, e.g. a palette, grayscale or YUV format, `-EINVAL` if
+    /// any part of the region lies outside of the display, or the negative errno
+    /// if the framebuffer cannot be mapped
+    pub fn blend_rect(&mut self, area: &Area, color: Color) -> FrameBufferResult<()> {
+        self.canvas()?.blend(area, color)
+    }
+
+    /// Fill the whole display with a color
+    ///
+    /// # Arguments
+    /// * `color` - Color, packed to the video format with [`Color::pack`]
+    ///
+    /// # Errors
+    /// Same as [`FrameBuffer::put_pixel`]
+    pub fn clear(&mut self, color: Color) -> FrameBufferResult<()> {
+        let canvas = self.canvas()?;
+        let area = Area {
+            x: 0,
+            y: 0,
+            w: canvas.info.xres,
+            h: canvas.info.yres,
+        };
+        canvas.fill(&area, color)
+    }
+
+    /// Copy a block of pixels onto the framebuffer
+    ///
+    /// The source holds the pixels of `dst` row by row, already in the video
+    /// format. Rows of `dst` outside of the display are clipped. With
+    /// CONFIG_FB_UPDATE, the copied region is then passed to
+    /// [`FrameBuffer::update_area`].
+    ///
+    /// # Arguments
+    /// * `dst` - The region of the framebuffer to write
+    /// * `src` - The source pixels
+    /// * `src_stride` - The distance in bytes between the starts of two source rows
+    ///
+    /// # Errors
+    /// Returns `-EINVAL` if `src_stride` is shorter than a row of `dst` or `src`
+    /// is too small for `dst`, `-ENOTSUP` if the video format does not use whole
+    /// bytes per pixel, or the negative errno if the framebuffer cannot be mapped
+    /// or updated
+    pub fn blit(&mut self, dst: &Area, src: &[u8], src_stride: usize) -> FrameBufferResult<()> {
+        match self.canvas()?.blit(dst, src, src_stride)? {
+            Some(clipped) => self.update_area(&clipped),
+            None => Ok(()),
+        }
+    }
+
+    /// Draw a line of text with the built-in 8x8 font
+    ///
+    /// Each character takes [`GLYPH_WIDTH`](text::GLYPH_WIDTH) pixels, with
+    /// characters outside of printable ASCII drawn as `'?'`. Control characters
+    /// such as `'\n'` are not interpreted. Text outside of the display is
+    /// clipped. With CONFIG_FB_UPDATE, the drawn region is then passed to
+    /// [`FrameBuffer::update_area`].
+    ///
+    /// # Arguments
+    /// * `x` - Column of the top left corner of the text
+    /// * `y` - Row of the top left corner of the text
+    /// * `s` - The text to draw
+    /// * `fg` - Color of the glyph pixels, packed with [`Color::pack`]
+    /// * `bg` - Color of the other pixels of each character cell, or None to
+    ///   leave them unchanged
+    ///
+    /// # Errors
+    /// Returns `-ENOTSUP` if the video format cannot be packed or uses less
+    /// than a byte per pixel, or the negative errno if the framebuffer cannot
+    /// be mapped or updated
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use core::ffi::CStr;
+    /// use nuttx::video::fb::{Color, FrameBuffer};
+    ///
+    /// let mut fb = FrameBuffer::new(CStr::from_bytes_with_nul(b"/dev/fb0\0").unwrap()).unwrap();
+    /// let white = Color::rgb(0xff, 0xff, 0xff);
+    /// fb.draw_text(4, 4, "Hello, NuttX!", white, Some(Color::rgb(0, 0, 0))).unwrap();
+    /// ```
+    pub fn draw_text(
+        &mut self,
+        x: Coord,
+        y: Coord,
+        s: &str,
+        fg: Color,
+        bg: Option<Color>,
+    ) -> FrameBufferResult<()> {
+        match self.canvas()?.text(x, y, s, fg, bg)? {
+            Some(clipped) => self.update_area(&clipped),
+            None => Ok(()),
+        }
+    }
+
+    /// Get the canvas used for drawing, mapping the framebuffer on first use
+    fn canvas(&mut self) -> FrameBufferResult<&mut Canvas> {
+        if self.canvas.is_none() {
+            let info = self.get_video_info()?;
+            let map = self.map()?;
+            self.canvas = Some(Canvas { map, info });
+        }
+
+        Ok(self.canvas.as_mut().unwrap())
+    }
+
+    /// Update a rectangular region in the framebuffer
+    ///
+    /// The region is checked against the resolution cached when the device
+    /// was opened, so drivers never see a region outside of the display. An
+    /// empty region needs no update and is ignored. With CONFIG_FB_UPDATE
+    /// disabled the display needs no update either, but the region is still
+    /// checked.
+    ///
+    /// # Arguments
+    /// * `area` - The rectangular region to update
+    ///
+    /// # Errors
+    /// Returns `-EINVAL` if any part of the region lies outside of the
+    /// display, or the negative errno if the ioctl fails
+    pub fn update_area(&self, area: &Area) -> FrameBufferResult<()> {
+        if area.is_empty() {
+            return Ok(());
+        }
+
+        let resolution = match self.resolution {
+            Some(resolution) => resolution,
+            None => self.get_video_info()?.resolution(),
+        };
+        check_area(area, resolution)?;
+
+        self.update_display(area)
+    }
+
+    #[kconfig(CONFIG_FB_UPDATE = "y")]
+    fn update_display(&self, area: &Area) -> FrameBufferResult<()> {
+        // SAFETY: FBIO_UPDATE reads a fb_area_s
+        unsafe { ioctl::ioctl_write(self.as_raw_fd(), FBIO_UPDATE, area) }?;
+        Ok(())
+    }
+
+    #[kconfig(CONFIG_FB_UPDATE = "n")]
+    fn update_display(&self, _area: &Area) -> FrameBufferResult<()> {
+        Ok(())
+    }
+
+    /// Set the backlight brightness of the panel
+    ///
+    /// NuttX has no dedicated backlight command, so the level is set with
+    /// `FBIOSET_POWER`. Panels backed by an LCD driver interpret the power
+    /// level as the backlight setting, ranging from 0 (off) to
+    /// CONFIG_LCD_MAXPOWER (full on).
+    ///
+    /// # Arguments
+    /// * `level` - The backlight level
+    ///
+    /// # Errors
+    /// Returns the negative errno if the ioctl fails
+    pub fn set_backlight(&self, level: u8) -> FrameBufferResult<()> {
+        self.set_power_level(level as libc::c_ulong)
+    }
+
+    /// Turn the panel on or off
+    ///
+    /// Turning the panel off saves its power level, queried with
+    /// `FBIOGET_POWER`, and turning it on restores that level, so the
+    /// backlight setting is kept. A panel that is already on keeps its level,
+    /// and one turned off by other means is turned on at the lowest non-zero
+    /// level, use [`FrameBuffer::set_backlight`] to choose a brighter setting.
+    ///
+    /// # Arguments
+    /// * `on` - true to power the panel on, false to power it off
+    ///
+    /// # Errors
+    /// Returns the negative errno if an ioctl fails
+    pub fn set_power(&self, on: bool) -> FrameBufferResult<()> {
+        // SAFETY: FBIOGET_POWER stores the power level as an int
+        let level: libc::c_int = unsafe { ioctl::ioctl_read(self.as_raw_fd(), FBIOGET_POWER) }?;
+
+        if !on {
+            if level > 0 {
+                self.saved_power
+                    .store(level, core::sync::atomic::Ordering::Relaxed);
+            }
+            return self.set_power_level(0);
+        }
+        if level > 0 {
+            return Ok(());
+        }
+
+        let saved = self.saved_power.load(core::sync::atomic::Ordering::Relaxed);
+        self.set_power_level(saved.max(1) as libc::c_ulong)
+    }
+
+    /// Get the hardware cursor attributes
+    ///
+    /// # Returns
+    /// `CursorInfo` containing the current cursor position
+    ///
+    /// # Errors
+    /// Returns the negative errno if the ioctl fails, or `-ENOTSUP` if
+    /// CONFIG_FB_HWCURSOR is disabled
+    #[kconfig(CONFIG_FB_HWCURSOR = "y")]
+    pub fn get_cursor(&self) -> FrameBufferResult<CursorInfo> {
+        // SAFETY: FBIOGET_CURSOR stores a fb_cursorattrib_s
+        let attrib: bindings::fb_cursorattrib_s =
+            unsafe { ioctl::ioctl_read(self.as_raw_fd(), FBIOGET_CURSOR) }?;
+
+        Ok(CursorInfo {
+            x: attrib.pos.x,
+            y: attrib.pos.y,
+        })
+    }
+
+    #[kconfig(CONFIG_FB_HWCURSOR = "n")]
+    pub fn get_cursor(&self) -> FrameBufferResult<CursorInfo> {
+        Err(-libc::ENOTSUP)
+    }
+
+    /// Move the hardware cursor and show or hide it
+    ///
+    /// # Arguments
+    /// * `x` - Column of the cursor hotspot
+    /// * `y` - Row of the cursor hotspot
+    /// * `visible` - true to show the cursor, false to hide it
+    ///
+    /// # Errors
+    /// Returns the negative errno if the ioctl fails, or `-ENOTSUP` if
+    /// CONFIG_FB_HWCURSOR is disabled
+    #[kconfig(CONFIG_FB_HWCURSOR = "y")]
+    pub fn set_cursor(&self, x: Coord, y: Coord, visible: bool) -> FrameBufferResult<()> {
+        let mut cursor = unsafe { core::mem::zeroed::<bindings::fb_setcursor_s>() };
+        cursor.flags = bindings::FB_CUR_SETPOSITION as u8;
+        if visible {
+            cursor.flags |= bindings::FB_CUR_ENABLE as u8;
+        }
+        cursor.pos.x = x;
+        cursor.pos.y = y;
+
+        self.put_cursor(&cursor)
+    }
+
+    #[kconfig(CONFIG_FB_HWCURSOR = "n")]
+    pub fn set_cursor(&self, _x: Coord, _y: Coord, _visible: bool) -> FrameBufferResult<()> {
+        Err(-libc::ENOTSUP)
+    }
+
+    /// Set the image of the hardware cursor
+    ///
+    /// The visibility of the cursor is left unchanged, use
+    /// [`FrameBuffer::set_cursor`] to show or hide it.
+    ///
+    /// # Arguments
+    /// * `width` - Width of the cursor image in pixels
+    /// * `height` - Height of the cursor image in pixels
+    /// * `image` - Pixel data in the cursor format reported by the driver, row
+    ///   by row, each row starting on a byte boundary
+    ///
+    /// # Errors
+    /// Returns `-EINVAL` if `image` is shorter than `width` by `height` pixels,
+    /// `-ENOTSUP` if the cursor format is unknown or CONFIG_FB_HWCURSORIMAGE is
+    /// disabled, or the negative errno if an ioctl fails
+    #[kconfig(CONFIG_FB_HWCURSOR = "y", CONFIG_FB_HWCURSORIMAGE = "y")]
+    pub fn set_cursor_image(
+        &self,
+        width: Coord,
+        height: Coord,
+        image: &[u8],
+    ) -> FrameBufferResult<()> {
+        // SAFETY: FBIOGET_CURSOR stores a fb_cursorattrib_s
+        let attrib: bindings::fb_cursorattrib_s =
+            unsafe { ioctl::ioctl_read(self.as_raw_fd(), FBIOGET_CURSOR) }?;
+
+        // The driver reads the whole image, so it must not extend past `image`
+        let bits = bits_per_pixel(attrib.fmt).ok_or(-libc::ENOTSUP)?;
+        let row_len = (width as usize * bits).div_ceil(8);
+        if image.len() < row_len * height as usize {
+            return Err(-libc::EINVAL);
+        }
+
+        let mut cursor = unsafe { core::mem::zeroed::<bindings::fb_setcursor_s>() };
+        cursor.flags = bindings::FB_CUR_SETIMAGE as u8;
+        cursor.img.width = width;
+        cursor.img.height = height;
+        cursor.img.image = image.as_ptr();
+
+        self.put_cursor(&cursor)
+    }
+
+    #[kconfig(CONFIG_FB_HWCURSORIMAGE = "n")]
+    pub fn set_cursor_image(
+        &self,
+        _width: Coord,
+        _height: Coord,
+        _image: &[u8],
+    ) -> FrameBufferResult<()> {
+        Err(-libc::ENOTSUP)
+    }
+
+    #[kconfig(CONFIG_FB_HWCURSOR = "y")]
+    fn put_cursor(&self, cursor: &bindings::fb_setcursor_s) -> FrameBufferResult<()> {
+        // SAFETY: FBIOPUT_CURSOR reads a fb_setcursor_s, whose image pointer is
+        // valid for the size it describes
+        unsafe { ioctl::ioctl_write(self.as_raw_fd(), FBIOPUT_CURSOR, cursor) }?;
+        Ok(())
+    }
+
+    /// Pan the display to show the region starting at a row of the virtual
+    /// resolution
+    ///
+    /// Combined with [`FrameBuffer::wait_vsync`] this allows tear-free double
+    /// buffering: render into the hidden half of a double-height framebuffer,
+    /// then pan to it. The plane's `yres_virtual` is expected to be at least
+    /// twice the display's `yres`.
+    ///
+    /// # Arguments
+    /// * `y_offset` - First row of the virtual resolution to show
+    ///
+    /// # Errors
+    /// Returns `-EINVAL` if `yres_virtual` is less than twice `yres`, or if the
+    /// region starting at `y_offset` exceeds `yres_virtual`. Returns a libc error
+    /// code if an ioctl fails.
+    pub fn pan_display(&self, y_offset: Coord) -> FrameBufferResult<()> {
+        let yres = self.get_video_info()?.yres as u32;
+        let mut plane = self.get_plane_info()?;
+
+        if plane.yres_virtual < 2 * yres || y_offset as u32 + yres > plane.yres_virtual {
+            return Err(-libc::EINVAL);
+        }
+
+        plane.xoffset = 0;
+        plane.yoffset = y_offset as u32;
+
+        // SAFETY: FBIOPAN_DISPLAY reads a fb_planeinfo_s
+        unsafe { ioctl::ioctl_write(self.as_raw_fd(), FBIOPAN_DISPLAY, &plane) }?;
+        Ok(())
+    }
+
+    /// Wait for the next vertical sync of the display
+    ///
+    /// # Errors
+    /// Returns the negative errno if the ioctl fails, or `-ENOTSUP` if
+    /// CONFIG_FB_SYNC is disabled
+    #[kconfig(CONFIG_FB_SYNC = "y")]
+    pub fn wait_vsync(&self) -> FrameBufferResult<()> {
+        // SAFETY: FBIO_WAITFORVSYNC takes no argument
+        unsafe { ioctl::ioctl_none(self.as_raw_fd(), FBIO_WAITFORVSYNC) }?;
+        Ok(())
+    }
+
+    #[kconfig(CONFIG_FB_SYNC = "n")]
+    pub fn wait_vsync(&self) -> FrameBufferResult<()> {
+        Err(-libc::ENOTSUP)
+    }
+
+    /// Read entries of the color lookup table
+    ///
+    /// # Arguments
+    /// * `cmap` - Range of entries to read, the channel slices are filled in
+    ///
+    /// # Errors
+    /// Returns `-EINVAL` if the channel slices differ in length, the negative
+    /// errno if the ioctl fails, or `-ENOTSUP` if CONFIG_FB_CMAP is disabled
+    #[kconfig(CONFIG_FB_CMAP = "y")]
+    pub fn get_cmap(&self, cmap: &mut ColorMap) -> FrameBufferResult<()> {
+        let mut raw = cmap.as_raw_mut()?;
+
+        // SAFETY: The channel pointers reference slices of `len` writable bytes
+        unsafe { ioctl::ioctl_readwrite(self.as_raw_fd(), FBIOGET_CMAP, &mut raw) }?;
+        Ok(())
+    }
+
+    #[kconfig(CONFIG_FB_CMAP = "n")]
+    pub fn get_cmap(&self, _cmap: &mut ColorMap) -> FrameBufferResult<()> {
+        Err(-libc::ENOTSUP)
+    }
+
+    /// Write entries of the color lookup table
+    ///
+    /// A [`ColorMap`] read with [`FrameBuffer::get_cmap`] can be written back
+    /// by converting it with `ColorMapRef::from(&cmap)`.
+    ///
+    /// # Arguments
+    /// * `cmap` - Range of entries to write
+    ///
+    /// # Errors
+    /// Returns `-EINVAL` if the channel slices differ in length, the negative
+    /// errno if the ioctl fails, or `-ENOTSUP` if CONFIG_FB_CMAP is disabled
+    #[kconfig(CONFIG_FB_CMAP = "y")]
+    pub fn put_cmap(&self, cmap: &ColorMapRef) -> FrameBufferResult<()> {
+        let raw = cmap.as_raw()?;
+
+        // SAFETY: The channel pointers reference slices of `len` bytes, which
+        // the driver only reads
+        unsafe { ioctl::ioctl_write(self.as_raw_fd(), FBIOPUT_CMAP, &raw) }?;
+        Ok(())
+    }
+
+    #[kconfig(CONFIG_FB_CMAP = "n")]
+    pub fn put_cmap(&self, _cmap: &ColorMapRef) -> FrameBufferResult<()> {
+        Err(-libc::ENOTSUP)
+    }
+
+    /// Get a hardware overlay of the display
+    ///
+    /// # Arguments
+    /// * `index` - Index of the overlay, below the `noverlays` video information
+    ///
+    /// # Returns
+    /// `Overlay` giving access to the overlay settings and memory
+    ///
+    /// # Errors
+    /// Returns the negative errno if the overlay information cannot be read
+    #[kconfig(CONFIG_FB_OVERLAY = "y")]
+    pub fn overlay(&self, index: u8) -> FrameBufferResult<Overlay<'_>> {
+        let mut info = unsafe { core::mem::zeroed::<OverlayInfo>() };
+        info.overlay = index;
+
+        // SAFETY: FBIOGET_OVERLAYINFO fills the fb_overlayinfo_s of the selected
+        // overlay
+        unsafe { ioctl::ioctl_readwrite(self.as_raw_fd(), FBIOGET_OVERLAYINFO, &mut info) }?;
+        Ok(Overlay { fb: self, info })
+    }
+
+    #[kconfig(CONFIG_FB_OVERLAY = "y")]
+    fn select_overlay(&self, index: u8) -> FrameBufferResult<()> {
+        // SAFETY: FBIO_SELECT_OVERLAY takes the overlay index by value
+        unsafe {
+            ioctl::ioctl_value(
+                self.as_raw_fd(),
+                FBIO_SELECT_OVERLAY,
+                index as libc::c_ulong,
+            )
+        }?;
+        Ok(())
+    }
+
+    fn set_power_level(&self, level: libc::c_ulong) -> FrameBufferResult<()> {
+        // SAFETY: FBIOSET_POWER takes the power level by value
+        unsafe { ioctl::ioctl_value(self.as_raw_fd(), FBIOSET_POWER, level) }?;
+        Ok(())
+    }
+}
+
+/// Options for opening a [`FrameBuffer`]
+///
+/// Combines opening the device and mapping its memory into a single call.
+/// The defaults match [`FrameBuffer::new`]: `/dev/fb0` is opened for reading
+/// and writing in blocking mode, and the memory is mapped on first use.
+///
+/// # Examples
+///
+/// ```no_run
+/// use core::ffi::CStr;
+/// use nuttx::video::fb::{Color, FrameBufferBuilder};
+///
+/// let mut fb = FrameBufferBuilder::new()
+///     .path(CStr::from_bytes_with_nul(b"/dev/fb1\0").unwrap())
+///     .map(true)
+///     .build()
+///     .unwrap();
+/// fb.clear(Color::BLACK).unwrap();
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct FrameBufferBuilder<'a> {
+    path: &'a CStr,
+    read_only: bool,
+    nonblocking: bool,
+    map: bool,
+}
+
+impl<'a> FrameBufferBuilder<'a> {
+    /// Creates the options of [`FrameBuffer::new`] for `/dev/fb0`
+    pub fn new() -> Self {
+        FrameBufferBuilder {
+            path: c"/dev/fb0",
+            read_only: false,
+            nonblocking: false,
+            map: false,
+        }
+    }
+
+    /// Sets the path of the framebuffer device
+    pub fn path(mut self, path: &'a CStr) -> Self {
+        self.path = path;
+        self
+    }
+
+    /// Opens the device for reading only, e.g. to query video information
+    ///
+    /// A read only framebuffer cannot be drawn to or mapped.
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Opens the device in non-blocking mode, see
+    /// [`FrameBuffer::set_nonblocking`] for the operations affected
+    pub fn nonblocking(mut self, nonblocking: bool) -> Self {
+        self.nonblocking = nonblocking;
+        self
+    }
+
+    /// Maps the framebuffer memory while opening
+    ///
+    /// Drawing maps the memory on first use anyway, this reports mapping
+    /// failures up front instead.
+    pub fn map(mut self, map: bool) -> Self {
+        self.map = map;
+        self
+    }
+
+    /// Opens the framebuffer device with these options
+    ///
+    /// # Errors
+    /// Returns `-EINVAL` if mapping is requested for a read only framebuffer,
+    /// or the negative errno if the device cannot be opened or mapped
+    pub fn build(&self) -> FrameBufferResult<FrameBuffer> {
+        if self.read_only && self.map {
+            return Err(-libc::EINVAL);
+        }
+
+        let mut flags = if self.read_only {
+            libc::O_RDONLY
+        } else {
+            libc::O_RDWR
+        };
+        if self.nonblocking {
+            flags |= libc::O_NONBLOCK;
+        }
+
+        let mut fb = FrameBuffer::new_with_flags(self.path, flags)?;
+        if self.map {
+            fb.canvas()?;
+        }
+        Ok(fb)
+    }
+}
+
+impl Default for FrameBufferBuilder<'_> {
+    /// Creates the options of [`FrameBuffer::new`] for `/dev/fb0`
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AsRawFd for FrameBuffer {
+    /// Returns the file descriptor of the framebuffer device, e.g. for custom
+    /// ioctls or an external poll loop. The descriptor stays owned by the
+    /// FrameBuffer and is closed when it is dropped.
+    fn as_raw_fd(&self) -> RawFd {
+        fd::raw(&self.fd)
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl Drop for FrameBuffer {
+    /// Automatically closes the framebuffer device when the FrameBuffer instance goes out of scope
+    ///
+    /// This ensures that system resources are properly released even if the FrameBuffer
+    /// instance is not explicitly closed. The underlying file descriptor is closed
+    /// using the libc::close() function.
+    ///
+    /// # Safety
+    /// This function is marked unsafe because it calls into C code through libc::close().
+    /// The file descriptor is guaranteed to be valid as it's managed by the FrameBuffer
+    /// struct and only set during successful initialization.
+    fn drop(&mut self) {
+        // Unmap the memory first, as the fields are only dropped afterwards
+        self.canvas = None;
+        unsafe { libc::close(self.fd) };
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::os::fd::AsFd for FrameBuffer {
+    /// Borrows the descriptor of the framebuffer device
+    fn as_fd(&self) -> std::os::fd::BorrowedFd<'_> {
+        std::os::fd::AsFd::as_fd(&self.fd)
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<std::os::fd::OwnedFd> for FrameBuffer {
+    /// Adopts an owned framebuffer descriptor
+    fn from(fd: std::os::fd::OwnedFd) -> Self {
+        Self::from_device_fd(fd)
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<FrameBuffer> for std::os::fd::OwnedFd {
+    /// Releases the descriptor of the framebuffer device, which stays open
+    ///
+    /// The drawing canvas, if any, is unmapped.
+    fn from(fb: FrameBuffer) -> Self {
+        fb.fd
+    }
+}
+
+/// Maximum number of separate regions a [`DirtyTracker`] keeps
+pub const MAX_DIRTY_AREAS: usize = 8;
+
+/// Collects the regions changed while rendering, to update them in one pass
+///
+/// Overlapping regions are coalesced into their bounding box, so every pixel
+/// is updated once. A cluster only grows by overlap: regions that merely touch
+/// or lie apart stay separate, as updating the gap between them would waste
+/// bandwidth. Since a grown bounding box may overlap further regions, those
+/// are merged too until all kept regions are disjoint.
+///
+/// At most [`MAX_DIRTY_AREAS`] regions are kept. When another disjoint region
+/// is marked, it is merged into the region whose bounding box grows the least.
+///
+/// # Examples
+///
+/// ```
+/// use nuttx::video::fb::{Area, DirtyTracker};
+///
+/// let mut dirty = DirtyTracker::new();
+/// dirty.mark(&Area { x: 0, y: 0, w: 10, h: 10 });
+/// dirty.mark(&Area { x: 5, y: 5, w: 10, h: 10 });
+/// dirty.mark(&Area { x: 40, y: 40, w: 4, h: 4 });
+///
+/// let edges: Vec<_> = dirty
+///     .areas()
+///     .iter()
+///     .map(|area| (area.x, area.y, area.right(), area.bottom()))
+///     .collect();
+/// assert_eq!(edges, [(0, 0, 15, 15), (40, 40, 44, 44)]);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct DirtyTracker {
+    areas: [Area; MAX_DIRTY_AREAS],
+    len: usize,
+}
+
+impl DirtyTracker {
+    /// Creates a tracker without dirty regions
+    pub fn new() -> Self {
+        let area = Area {
+            x: 0,
+            y: 0,
+            w: 0,
+            h: 0,
+        };
+
+        DirtyTracker {
+            areas: [area; MAX_DIRTY_AREAS],
+            len: 0,
+        }
+    }
+
+    /// Marks a region as changed
+    ///
+    /// Empty regions are ignored.
+    ///
+    /// # Arguments
+    /// * `area` - The changed region
+    pub fn mark(&mut self, area: &Area) {
+        if area.is_empty() {
+            return;
+        }
+
+        // Absorb every kept region overlapping the growing bounding box
+        let mut merged = *area;
+        let mut index = 0;
+        while index < self.len {
+            if self.areas[index].intersect(&merged).is_some() {
+                merged = merged.union(&self.areas[index]);
+                self.len -= 1;
+                self.areas[index] = self.areas[self.len];
+                index = 0;
+            } else {
+                index += 1;
+            }
+        }
+
+        if self.len < MAX_DIRTY_AREAS {
+            self.areas[self.len] = merged;
+            self.len += 1;
+            return;
+        }
+
+        let size = |area: &Area| area.w as u64 * area.h as u64;
+        let (index, _) = self
+            .areas
+            .iter()
+            .enumerate()
+            .map(|(index, kept)| (index, size(&kept.union(&merged)) - size(kept)))
+            .min_by_key(|&(_, growth)| growth)
+            .unwrap();
+
+        // The grown region may now overlap others, so mark it again
+        let grown = self.areas[index].union(&merged);
+        self.len -= 1;
+        self.areas[index] = self.areas[self.len];
+        self.mark(&grown);
+    }
+
+    /// The coalesced dirty regions, which don't overlap each other
+    pub fn areas(&self) -> &[Area] {
+        &self.areas[..self.len]
+    }
+
+    /// Returns true if no region was marked since the last flush
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Forgets all dirty regions without updating them
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Updates the dirty regions of the display and forgets them
+    ///
+    /// Issues one [`FrameBuffer::update_area`] call per coalesced region. With
+    /// CONFIG_FB_UPDATE disabled the display needs no update, so the regions
+    /// are only forgotten.
+    ///
+    /// # Arguments
+    /// * `fb` - The framebuffer rendered into
+    ///
+    /// # Errors
+    /// Returns the negative errno if an update fails. The regions not updated
+    /// yet are kept, so the flush can be retried.
+    #[kconfig(CONFIG_FB_UPDATE = "y")]
+    pub fn flush(&mut self, fb: &FrameBuffer) -> FrameBufferResult<()> {
+        while let Some(area) = self.areas().last() {
+            fb.update_area(area)?;
+            self.len -= 1;
+        }
+        Ok(())
+    }
+
+    #[kconfig(CONFIG_FB_UPDATE = "n")]
+    pub fn flush(&mut self, _fb: &FrameBuffer) -> FrameBufferResult<()> {
+        self.clear();
+        Ok(())
+    }
+}
+
+impl Default for DirtyTracker {
+    /// Creates a tracker without dirty regions
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// How a [`DoubleBuffer`] presents the rendered frame
+#[derive(Debug)]
+enum Presentation<'a> {
+    /// Flip between the two halves of the virtual resolution, `back` being
+    /// the index of the hidden one
+    Flip { back: usize },
+    /// Render into an off-screen buffer and copy it to the display
+    Copy(&'a mut [u8]),
+    /// Like `Copy`, with a buffer allocated by [`DoubleBuffer::new`]
+    #[cfg(feature = "std")]
+    Allocated(std::vec::Vec<u8>),
+    /// Render into the visible framebuffer memory
+    #[cfg(not(feature = "std"))]
+    Direct,
+}
+
+/// Tear-free rendering by flipping between two buffers
+///
+/// If the plane's virtual resolution holds two screens, the frame is rendered
+/// into the hidden one and [`DoubleBuffer::present`] pans the display to it
+/// with [`FrameBuffer::pan_display`], then waits for the vertical sync. The
+/// former front buffer becomes the next back buffer.
+///
+/// Otherwise rendering degrades to a single framebuffer. Given an off-screen
+/// buffer with [`DoubleBuffer::with_fallback`], frames are rendered there and
+/// copied to the display on the vertical sync, which avoids showing partly
+/// drawn frames. [`DoubleBuffer::new`] allocates that buffer with the `std`
+/// feature. Otherwise frames are rendered into the visible memory.
+///
+/// In every mode the back buffer has the layout of a screen of the plane:
+/// `yres` rows, `stride` bytes apart. Its content is undefined after a
+/// present, so each frame must be drawn completely.
+///
+/// # Examples
+///
+/// ```no_run
+/// use core::ffi::CStr;
+/// use nuttx::video::fb::{DoubleBuffer, FrameBuffer};
+///
+/// let fb = FrameBuffer::new(CStr::from_bytes_with_nul(b"/dev/fb0\0").unwrap()).unwrap();
+/// let mut buffers = DoubleBuffer::new(fb).unwrap();
+///
+/// for frame in 0u8.. {
+///     buffers.back_buffer().fill(frame);
+///     buffers.present().unwrap();
+/// }
+/// ```
+#[derive(Debug)]
+pub struct DoubleBuffer<'a> {
+    presentation: Presentation<'a>,
+    map: FrameBufferMmap,
+    fb: FrameBuffer,
+    info: VideoInfo,
+    /// Size in bytes of one screen
+    screen_len: usize,
+}
+
+impl DoubleBuffer<'static> {
+    /// Sets up double buffering
+    ///
+    /// If the virtual resolution does not hold two screens, frames are
+    /// rendered into an off-screen buffer allocated here with the `std`
+    /// feature, and into the visible memory without it.
+    ///
+    /// # Arguments
+    /// * `fb` - The framebuffer, which must be opened for writing
+    ///
+    /// # Errors
+    /// Returns `-EINVAL` if the plane is too small for a screen, or the
+    /// negative errno if the framebuffer cannot be queried, mapped or panned
+    /// to its first screen
+    pub fn new(fb: FrameBuffer) -> FrameBufferResult<Self> {
+        DoubleBuffer::setup(fb, None)
+    }
+}
+
+impl<'a> DoubleBuffer<'a> {
+    /// Sets up double buffering, rendering into `buffer` if the virtual
+    /// resolution does not hold two screens
+    ///
+    /// # Arguments
+    /// * `fb` - The framebuffer, which must be opened for writing
+    /// * `buffer` - Off-screen buffer of at least `stride * yres` bytes, only
+    ///   used if the framebuffer cannot flip
+    ///
+    /// # Errors
+    /// Returns `-EINVAL` if the plane is too small for a screen or `buffer` is
+    /// needed but too small, or the negative errno if the framebuffer cannot
+    /// be queried, mapped or panned to its first screen
+    pub fn with_fallback(fb: FrameBuffer, buffer: &'a mut [u8]) -> FrameBufferResult<Self> {
+        DoubleBuffer::setup(fb, Some(buffer))
+    }
+
+    fn setup(fb: FrameBuffer, buffer: Option<&'a mut [u8]>) -> FrameBufferResult<Self> {
+        let info = fb.get_video_info()?;
+        let map = fb.map()?;
+        let plane = *map.plane_info();
+
+        let yres = info.yres as usize;
+        let screen_len = plane.stride as usize * yres;
+        if screen_len > plane.fblen {
+            return Err(-libc::EINVAL);
+        }
+        let flip = plane.yres_virtual as usize >= 2 * yres && plane.fblen >= 2 * screen_len;
+
+        let presentation = if flip {
+            fb.pan_display(0)?;
+            Presentation::Flip { back: 1 }
+        } else if let Some(buffer) = buffer {
+            if buffer.len() < screen_len {
+                return Err(-libc::EINVAL);
+            }
+            Presentation::Copy(&mut buffer[..screen_len])
+        } else {
+            #[cfg(feature = "std")]
+            let presentation = Presentation::Allocated(std::vec![0; screen_len]);
+            #[cfg(not(feature = "std"))]
+            let presentation = Presentation::Direct;
+            presentation
+        };
+
+        Ok(DoubleBuffer {
+            presentation,
+            map,
+            fb,
+            info,
+            screen_len,
+        })
+    }
+
+    /// Returns true if frames are presented by flipping between two buffers
+    pub fn is_flipping(&self) -> bool {
+        matches!(self.presentation, Presentation::Flip { .. })
+    }
+
+    /// The video information of the display
+    pub fn video_info(&self) -> &VideoInfo {
+        &self.info
+    }
+
+    /// The plane information, giving the stride and pixel size of the buffers
+    pub fn plane_info(&self) -> &PlaneInfo {
+        self.map.plane_info()
+    }
+
+    /// The framebuffer, e.g. to set the backlight
+    pub fn framebuffer(&self) -> &FrameBuffer {
+        &self.fb
+    }
+
+    /// The buffer to render the next frame into
+    pub fn back_buffer(&mut self) -> &mut [u8] {
+        let len = self.screen_len;
+        match &mut self.presentation {
+            Presentation::Flip { back } => {
+                let start = *back * len;
+                &mut self.map.as_mut_slice()[start..start + len]
+            }
+            Presentation::Copy(buffer) => buffer,
+            #[cfg(feature = "std")]
+            Presentation::Allocated(buffer) => buffer,
+            #[cfg(not(feature = "std"))]
+            Presentation::Direct => &mut self.map.as_mut_slice()[..len],
+        }
+    }
+
+    /// Shows the rendered frame
+    ///
+    /// Flips to the back buffer, or copies it to the display, and waits for
+    /// the vertical sync so that the next frame is not drawn into the visible
+    /// memory. Displays without vertical sync support are not waited for.
+    /// With CONFIG_FB_UPDATE the display is then updated, except when
+    /// flipping.
+    ///
+    /// # Errors
+    /// Returns the negative errno if panning, waiting or updating fails
+    pub fn present(&mut self) -> FrameBufferResult<()> {
+        let len = self.screen_len;
+        match &mut self.presentation {
+            Presentation::Flip { back } => {
+                self.fb
+                    .pan_display((*back * self.info.yres as usize) as Coord)?;
+                *back ^= 1;
+                return wait_vsync_if_supported(&self.fb);
+            }
+            Presentation::Copy(buffer) => {
+                wait_vsync_if_supported(&self.fb)?;
+                self.map.as_mut_slice()[..len].copy_from_slice(buffer);
+            }
+            #[cfg(feature = "std")]
+            Presentation::Allocated(buffer) => {
+                wait_vsync_if_supported(&self.fb)?;
+                self.map.as_mut_slice()[..len].copy_from_slice(buffer);
+            }
+            #[cfg(not(feature = "std"))]
+            Presentation::Direct => {}
+        }
+
+        self.fb.update_area(&Area {
+            x: 0,
+            y: 0,
+            w: self.info.xres,
+            h: self.info.yres,
+        })
+    }
+
+    /// Releases the framebuffer, unmapping its memory
+    pub fn into_inner(self) -> FrameBuffer {
+        self.fb
+    }
+}
+
+/// Waits for the vertical sync, if the display supports it
+///
+/// Drivers without vertical sync support reject the ioctl with `-ENOTTY`.
+fn wait_vsync_if_supported(fb: &FrameBuffer) -> FrameBufferResult<()> {
+    match fb.wait_vsync() {
+        Err(err) if err == -libc::ENOTSUP || err == -libc::ENOTTY => Ok(()),
+        result => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fills `area` one pixel at a time, as done for non-uniform pixels
+    fn fill_rows_per_pixel(mem: &mut [u8], stride: usize, area: &Area, pixel: &[u8]) {
+        let (x, bpp) = (area.x as usize, pixel.len());
+        for row in area.y as usize..area.bottom() as usize {
+            let start = row * stride + x * bpp;
+            fill_pixels(&mut mem[start..start + area.w as usize * bpp], pixel);
+        }
+    }
+
+    #[test]
+    fn fill_rows_matches_per_pixel() {
+        let stride = 16 * 4;
+        let areas = [
+            Area {
+                x: 0,
+                y: 0,
+                w: 16,
+                h: 8,
+            },
+            Area {
+                x: 3,
+                y: 2,
+                w: 5,
+                h: 4,
+            },
+            Area {
+                x: 0,
+                y: 7,
+                w: 16,
+                h: 1,
+            },
+        ];
+
+        for pixel in [[0u8; 4], [0xff; 4], [0x12, 0x34, 0x56, 0xff]] {
+            for area in &areas {
+                let mut fast = [0xa5u8; 16 * 4 * 8];
+                let mut slow = fast;
+                fill_rows(&mut fast, stride, area, &pixel).unwrap();
+                fill_rows_per_pixel(&mut slow, stride, area, &pixel);
+                assert_eq!(fast, slow, "{area:?} with {pixel:?}");
+            }
+        }
+
+        let mut mem = [0u8; 16 * 4 * 8];
+        let outside = Area {
+            x: 0,
+            y: 4,
+            w: 16,
+            h: 5,
+        };
+        assert_eq!(
+            fill_rows(&mut mem, stride, &outside, &[0; 4]),
+            Err(-libc::EINVAL)
+        );
+    }
+
+    /// Compares the memset path with the per-pixel path, run with
+    /// `cargo test --release -- --ignored --nocapture fill_rows_bench`
+    #[test]
+    #[ignore]
+    fn fill_rows_bench() {
+        use std::hint::black_box;
+        use std::time::Instant;
+        use std::{println, vec};
+
+        const ITERATIONS: u32 = 50;
+        let (xres, yres, bpp) = (800, 480, 4);
+        let area = Area {
+            x: 0,
+            y: 0,
+            w: xres,
+            h: yres,
+        };
+        let stride = xres as usize * bpp;
+        let mut mem = vec![0u8; stride * yres as usize];
+
+        let start = Instant::now();
+        for i in 0..ITERATIONS {
+            fill_rows(black_box(&mut mem), stride, &area, &[i as u8; 4]).unwrap();
+        }
+        let memset = start.elapsed();
+
+        let start = Instant::now();
+        for i in 0..ITERATIONS {
+            fill_rows_per_pixel(black_box(&mut mem), stride, &area, &[i as u8; 4]);
+        }
+        let per_pixel = start.elapsed();
+
+        println!(
+            "{xres}x{yres} RGB32, {ITERATIONS} fills: memset {memset:?}, per pixel {per_pixel:?}"
+        );
+    }
+
+    #[test]
+    fn check_area_bounds() {
+        let resolution = (320, 240);
+
+        assert_eq!(
+            check_area(
+                &Area {
+                    x: 0,
+                    y: 0,
+                    w: 320,
+                    h: 240
+                },
+                resolution
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            check_area(
+                &Area {
+                    x: 300,
+                    y: 0,
+                    w: 20,
+                    h: 1
+                },
+                resolution
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            check_area(
+                &Area {
+                    x: 301,
+                    y: 0,
+                    w: 20,
+                    h: 1
+                },
+                resolution
+            ),
+            Err(-libc::EINVAL)
+        );
+        assert_eq!(
+            check_area(
+                &Area {
+                    x: 0,
+                    y: 200,
+                    w: 1,
+                    h: 41
+                },
+                resolution
+            ),
+            Err(-libc::EINVAL)
+        );
+    }
+
+    #[test]
+    fn check_area_empty() {
+        // Empty areas need no update, wherever they lie
+        assert_eq!(
+            check_area(
+                &Area {
+                    x: 400,
+                    y: 0,
+                    w: 0,
+                    h: 10
+                },
+                (320, 240)
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            check_area(
+                &Area {
+                    x: 0,
+                    y: 0,
+                    w: 10,
+                    h: 0
+                },
+                (320, 240)
+            ),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn check_area_overflow() {
+        let max = Coord::MAX;
+        let area = Area {
+            x: max - 10,
+            y: 0,
+            w: 20,
+            h: 1,
+        };
+
+        // x + w wraps around in Coord, but not in the u32 edges
+        assert_eq!(area.x.wrapping_add(area.w), 9);
+        assert_eq!(check_area(&area, (max, max)), Err(-libc::EINVAL));
+        assert_eq!(
+            check_area(
+                &Area {
+                    x: max - 10,
+                    y: 0,
+                    w: 10,
+                    h: 1
+                },
+                (max, max)
+            ),
+            Ok(())
+        );
+    }
+
+    /// The edges of an area, as (x, y, right, bottom)
+    fn edges(area: Area) -> (Coord, Coord, u32, u32) {
+        (area.x, area.y, area.right(), area.bottom())
+    }
+
+    fn area(x: Coord, y: Coord, w: Coord, h: Coord) -> Area {
+        Area { x, y, w, h }
+    }
+
+    #[test]
+    fn area_overlapping() {
+        let (a, b) = (area(0, 0, 10, 10), area(5, 5, 10, 10));
+
+        // Overlapping areas intersect in their common region
+        assert_eq!(a.intersect(&b).map(edges), Some((5, 5, 10, 10)));
+        assert_eq!(b.intersect(&a).map(edges), Some((5, 5, 10, 10)));
+        assert_eq!(edges(a.union(&b)), (0, 0, 15, 15));
+    }
+
+    #[test]
+    fn area_adjacent() {
+        let a = area(0, 0, 10, 10);
+
+        // Adjacent areas share an edge, but no pixels
+        let right = area(10, 0, 10, 10);
+        assert!(a.intersect(&right).is_none());
+        assert_eq!(edges(a.union(&right)), (0, 0, 20, 10));
+
+        let below = area(0, 10, 10, 10);
+        assert!(a.intersect(&below).is_none());
+        assert_eq!(edges(a.union(&below)), (0, 0, 10, 20));
+    }
+
+    #[test]
+    fn area_disjoint() {
+        let (a, d) = (area(0, 0, 10, 10), area(20, 20, 5, 5));
+
+        // Disjoint areas are joined by their bounding box
+        assert!(a.intersect(&d).is_none());
+        assert_eq!(edges(a.union(&d)), (0, 0, 25, 25));
+        // Empty areas don't grow the union
+        assert_eq!(edges(a.union(&area(50, 50, 0, 5))), (0, 0, 10, 10));
+    }
+
+    #[test]
+    fn color_over_transparent() {
+        for dst in [Color::BLACK, Color::WHITE, Color::rgba(10, 20, 30, 40)] {
+            assert_eq!(Color::rgba(0xff, 0x80, 0x00, 0).over(dst), dst);
+        }
+    }
+
+    #[test]
+    fn color_over_half_transparent() {
+        let src = Color::rgba(0xff, 0xff, 0xff, 128);
+        assert_eq!(src.over(Color::BLACK), Color::rgb(128, 128, 128));
+        assert_eq!(
+            Color::rgba(0xff, 0x00, 0x40, 128).over(Color::WHITE),
+            Color::rgb(255, 127, 159)
+        );
+
+        // A transparent background takes half of the alpha
+        assert_eq!(
+            src.over(Color::TRANSPARENT),
+            Color::rgba(128, 128, 128, 128)
+        );
+    }
+
+    #[test]
+    fn color_over_opaque() {
+        for dst in [Color::BLACK, Color::WHITE, Color::TRANSPARENT] {
+            assert_eq!(Color::RED.over(dst), Color::RED);
+            assert_eq!(Color::GRAY.over(dst), Color::GRAY);
+        }
+    }
+
+    /// Component widths (R, G, B, A) of the formats supported by
+    /// `Color::pack`, 0 for formats without alpha
+    const COLOR_FORMATS: [(u32, [u32; 4]); 9] = [
+        (FB_FMT_RGB8_222, [2, 2, 2, 0]),
+        (FB_FMT_RGB8_332, [3, 3, 2, 0]),
+        (FB_FMT_RGB12_444, [4, 4, 4, 0]),
+        (FB_FMT_RGB16_555, [5, 5, 5, 0]),
+        (FB_FMT_RGB16_565, [5, 6, 5, 0]),
+        (FB_FMT_RGBA16, [4, 4, 4, 4]),
+        (FB_FMT_RGB24, [8, 8, 8, 0]),
+        (FB_FMT_RGB32, [8, 8, 8, 0]),
+        (FB_FMT_RGBA32, [8, 8, 8, 8]),
+    ];
+
+    /// Grayscale formats supported by `Color::pack`
+    const GRAY_FORMATS: [u32; 2] = [FB_FMT_Y8, FB_FMT_Y16];
+
+    /// Colors sweeping the range of every component
+    fn sweep() -> impl Iterator<Item = Color> {
+        (0..=255u8)
+            .step_by(5)
+            .map(|v| Color::rgba(v, v / 2, 255 - v, v / 3))
+    }
+
+    #[test]
+    fn color_formats_cover_pack() {
+        let supported: std::vec::Vec<u32> = (0..=u8::MAX)
+            .filter(|&fmt| Color::WHITE.pack(fmt).is_some())
+            .map(u32::from)
+            .collect();
+
+        let mut expected: std::vec::Vec<u32> = COLOR_FORMATS
+            .iter()
+            .map(|&(fmt, _)| fmt)
+            .chain(GRAY_FORMATS)
+            .collect();
+        expected.sort_unstable();
+        expected.dedup();
+        assert_eq!(supported, expected);
+    }
+
+    #[test]
+    fn color_round_trip() {
+        for (fmt, bits) in COLOR_FORMATS {
+            let fmt = fmt as u8;
+            let width = bits_per_pixel(fmt).unwrap();
+            let bpp = width.is_multiple_of(8).then_some(width / 8);
+            assert_eq!(bytes_per_pixel(fmt), bpp, "format {fmt}");
+
+            for color in sweep() {
+                let value = color.pack(fmt).unwrap();
+                assert!(u64::from(value) < 1 << width, "format {fmt}");
+
+                // Precision documented for Color::pack
+                let back = Color::unpack(value, fmt).unwrap();
+                let pairs = [(color.r, back.r), (color.g, back.g), (color.b, back.b)];
+                for ((before, after), bits) in pairs.into_iter().zip(bits) {
+                    assert!(
+                        u32::from(before.abs_diff(after)) <= 255 >> bits,
+                        "format {fmt}: {color:?} became {back:?}"
+                    );
+                }
+                match bits[3] {
+                    0 => assert_eq!(back.a, 0xff, "format {fmt}"),
+                    a => assert!(u32::from(color.a.abs_diff(back.a)) <= 255 >> a),
+                }
+
+                // The pixel bytes of byte-sized formats hold the same value
+                if let Some((bytes, bpp)) = pack_color(fmt, color) {
+                    assert_eq!(unpack_pixel(&bytes[..bpp]), value, "format {fmt}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn color_round_trip_gray() {
+        for fmt in GRAY_FORMATS {
+            let fmt = fmt as u8;
+            let width = bits_per_pixel(fmt).unwrap();
+
+            for v in 0..=255u8 {
+                let gray = Color::rgb(v, v, v);
+                let value = gray.pack(fmt).unwrap();
+                assert!(u64::from(value) < 1 << width, "format {fmt}");
+                assert_eq!(Color::unpack(value, fmt), Some(gray), "format {fmt}");
+            }
+
+            // Other colors are reduced to their luma
+            for color in sweep() {
+                let back = Color::unpack(color.pack(fmt).unwrap(), fmt).unwrap();
+                assert!(back.r == back.g && back.g == back.b && back.a == 0xff);
+            }
+        }
     }
 }
@@ -0,0 +1,171 @@
+//! LCD character driver interface
+//!
+//! This module provides Rust bindings for the NuttX LCD character driver.
+//! It allows querying displays exposed as `/dev/lcdN` and drawing rectangular
+//! areas on them, for LCDs that are not registered as a framebuffer.
+//!
+//! The implementation matches the NuttX LCD interface defined in
+//! `nuttx/include/nuttx/lcd/lcd_dev.h`. The module is only available with
+//! `CONFIG_LCD`, and the device node requires `CONFIG_LCD_DEV`.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use core::ffi::CStr;
+//! use nuttx::video::fb::Area;
+//! use nuttx::video::lcd::Lcd;
+//!
+//! let lcd = Lcd::open(CStr::from_bytes_with_nul(b"/dev/lcd0\0").unwrap()).unwrap();
+//! let (xres, yres) = lcd.resolution();
+//!
+//! // Fill the top left 16x16 pixels, assuming an RGB565 display
+//! let pixels = [0xffu8; 16 * 16 * 2];
+//! lcd.put_area(&Area { x: 0, y: 0, w: 16, h: 16 }, &pixels).unwrap();
+//! ```
+
+use core::ffi::CStr;
+use core::mem::zeroed;
+use libc::{EINVAL, O_RDWR, c_int, c_void, ioctl, open};
+
+use crate::bindings::{self, lcddev_area_s};
+use crate::errno::errno;
+use crate::fd::{AsRawFd, RawFd};
+use crate::io::ioctl::IoctlRequest;
+use crate::video::fb::{Area, Coord, VideoInfo, bits_per_pixel};
+
+/// IOCTL command to write a rectangular area to the display
+///
+/// Matches C's LCDDEVIO_PUTAREA
+const LCDDEVIO_PUTAREA: IoctlRequest = bindings::LCDDEVIO_PUTAREA as IoctlRequest;
+
+/// IOCTL command to get video information
+///
+/// Matches C's LCDDEVIO_GETVIDEOINFO
+const LCDDEVIO_GETVIDEOINFO: IoctlRequest = bindings::LCDDEVIO_GETVIDEOINFO as IoctlRequest;
+
+/// Result type for LCD operations, errors hold the negative errno
+pub type LcdResult<T> = Result<T, i32>;
+
+/// Represents an open LCD device
+#[derive(Debug)]
+pub struct Lcd {
+    fd: c_int,
+    info: VideoInfo,
+}
+
+impl Lcd {
+    /// Opens an LCD device at the specified path
+    ///
+    /// # Arguments
+    /// * `path` - Path to the LCD device as a C string (e.g. "/dev/lcd0")
+    ///
+    /// # Errors
+    /// Returns the negative errno if the device could not be opened or the video
+    /// information could not be read
+    pub fn open(path: &CStr) -> LcdResult<Self> {
+        let fd = unsafe { open(path.as_ptr(), O_RDWR) };
+        if fd < 0 {
+            return Err(errno());
+        }
+
+        let mut lcd = Lcd {
+            fd,
+            // SAFETY: VideoInfo is a plain C structure, for which zero is valid
+            info: unsafe { zeroed() },
+        };
+        lcd.info = lcd.get_video_info()?;
+        Ok(lcd)
+    }
+
+    /// Gets video information from the LCD device
+    ///
+    /// # Errors
+    /// Returns the negative errno if the ioctl fails
+    pub fn get_video_info(&self) -> LcdResult<VideoInfo> {
+        // SAFETY: VideoInfo is a plain C structure, for which zero is valid
+        let mut info: VideoInfo = unsafe { zeroed() };
+
+        let result = unsafe {
+            ioctl(
+                self.fd,
+                LCDDEVIO_GETVIDEOINFO as _,
+                &mut info as *mut VideoInfo as *mut c_void,
+            )
+        };
+
+        if result < 0 { Err(errno()) } else { Ok(info) }
+    }
+
+    /// Returns the resolution of the display as (xres, yres)
+    pub fn resolution(&self) -> (Coord, Coord) {
+        (self.info.xres, self.info.yres)
+    }
+
+    /// Returns the pixel format of the display, one of the `FB_FMT_*` constants
+    pub fn format(&self) -> u8 {
+        self.info.fmt
+    }
+
+    /// Writes a rectangular area to the display
+    ///
+    /// # Arguments
+    /// * `area` - The area to write
+    /// * `pixels` - The pixels of the area in the display format, row by row.
+    ///   Rows of formats with less than 8 bits per pixel start on a byte boundary.
+    ///
+    /// # Errors
+    /// - `-EINVAL` if the area is empty or exceeds the display, if `pixels` is too
+    ///   short for the area, or if the display format is unknown
+    /// - The negative errno if the ioctl fails
+    pub fn put_area(&self, area: &Area, pixels: &[u8]) -> LcdResult<()> {
+        let (xres, yres) = self.resolution();
+        if area.w == 0
+            || area.h == 0
+            || area.x as u32 + area.w as u32 > xres as u32
+            || area.y as u32 + area.h as u32 > yres as u32
+        {
+            return Err(-EINVAL);
+        }
+
+        let bits = bits_per_pixel(self.info.fmt).ok_or(-EINVAL)?;
+        let stride = (area.w as usize * bits).div_ceil(8);
+        if pixels.len() < stride * area.h as usize {
+            return Err(-EINVAL);
+        }
+
+        // SAFETY: A zeroed lcddev_area_s is valid
+        let mut lcd_area: lcddev_area_s = unsafe { zeroed() };
+        lcd_area.row_start = area.y;
+        lcd_area.row_end = area.y + area.h - 1;
+        lcd_area.col_start = area.x;
+        lcd_area.col_end = area.x + area.w - 1;
+        lcd_area.stride = stride as Coord;
+        // The driver only reads the pixels for LCDDEVIO_PUTAREA
+        lcd_area.data = pixels.as_ptr() as *mut u8;
+
+        let result = unsafe {
+            ioctl(
+                self.fd,
+                LCDDEVIO_PUTAREA as _,
+                &mut lcd_area as *mut lcddev_area_s as *mut c_void,
+            )
+        };
+
+        if result < 0 { Err(errno()) } else { Ok(()) }
+    }
+}
+
+impl AsRawFd for Lcd {
+    /// Returns the file descriptor of the LCD device. The descriptor stays owned
+    /// by the Lcd.
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd
+    }
+}
+
+impl Drop for Lcd {
+    /// Closes the LCD device when the Lcd goes out of scope
+    fn drop(&mut self) {
+        unsafe { libc::close(self.fd) };
+    }
+}
@@ -1 +1,10 @@
+use kconfig::kconfig_if;
+
 pub mod fb;
+pub mod text;
+
+// Attributes cannot be applied to file modules, so the module is gated with
+// kconfig_if! rather than #[kconfig]
+kconfig_if!(CONFIG_LCD = "y" {
+    pub mod lcd;
+});
@@ -12,5 +12,73 @@
 /* Touchscreen interface */
 #include <nuttx/input/touchscreen.h>
 
+/* File control commands for signal notification */
+#include <fcntl.h>
+
 /* Framebuffer interface */
 #include <nuttx/video/fb.h>
+
+/* Memory mapping of framebuffer memory */
+#include <sys/mman.h>
+
+/* GPIO character driver interface */
+#include <nuttx/ioexpander/gpio.h>
+
+/* Signal delivery of GPIO interrupts */
+#include <signal.h>
+
+/* User LED interface */
+#include <nuttx/leds/userled.h>
+
+/* Button interface */
+#include <nuttx/input/buttons.h>
+
+/* Discrete and analog joystick interfaces */
+#include <nuttx/input/djoystick.h>
+#include <nuttx/input/ajoystick.h>
+
+/* Keyboard encoding */
+#include <nuttx/input/kbd_codec.h>
+
+/* ADC interface */
+#include <nuttx/analog/adc.h>
+
+/* DAC interface */
+#include <nuttx/analog/dac.h>
+
+/* PWM interface */
+#include <nuttx/timers/pwm.h>
+
+/* I2C character driver interface */
+#include <nuttx/i2c/i2c_master.h>
+
+/* SPI character driver interface */
+#include <nuttx/spi/spi_transfer.h>
+
+/* Serial port configuration */
+#include <termios.h>
+
+/* RTC interface */
+#include <nuttx/timers/rtc.h>
+
+/* Watchdog interface */
+#include <nuttx/timers/watchdog.h>
+
+/* Timer interface */
+#include <nuttx/timers/timer.h>
+
+/* Sensor interface */
+#include <nuttx/uorb.h>
+
+/* Battery interface */
+#include <nuttx/power/battery_ioctl.h>
+
+/* CAN interface */
+#include <nuttx/can/can.h>
+
+/* LCD character driver interface */
+#include <nuttx/lcd/lcd_dev.h>
+
+/* Audio interface */
+#include <mqueue.h>
+#include <nuttx/audio/audio.h>